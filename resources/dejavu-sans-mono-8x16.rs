@@ -0,0 +1,75 @@
+//!
+//! Rasterizes DejaVu Sans Mono into `dejavu-sans-mono-8x16.a8` -- the
+//! bitmap font `compositor::components::canvas` draws text with.
+//!
+//! Printable ASCII (` ` to `~`), one 8x16 cell per character, row by
+//! row, one coverage byte (0-255) per pixel.
+//!
+//! Not part of the build. To regenerate the font (from `resources/`):
+//!
+//! ```sh
+//! cargo new --bin /tmp/rasterize-font
+//! cp dejavu-sans-mono-8x16.rs /tmp/rasterize-font/src/main.rs
+//! cargo add --manifest-path /tmp/rasterize-font/Cargo.toml ab_glyph@=0.2.21
+//! cargo run --release --manifest-path /tmp/rasterize-font/Cargo.toml -- \
+//!     /usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf dejavu-sans-mono-8x16.a8
+//! ```
+//!
+//! The checked-in font is from DejaVu 2.37 -- see `dejavu-sans-mono-8x16.LICENSE`.
+//!
+
+use ab_glyph::{point, Font, FontRef, PxScale, ScaleFont};
+
+const WIDTH: usize = 8;
+const HEIGHT: usize = 16;
+
+///
+/// Picked so the glyphs fill their cells
+/// without spilling out of them.
+///
+const SIZE: f32 = 13.5;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let (Some(ttf), Some(out)) = (args.next(), args.next()) else {
+        eprintln!("Usage: rasterize-font <DejaVuSansMono.ttf> <OUTPUT.a8>");
+        std::process::exit(1);
+    };
+
+    let data = std::fs::read(ttf).unwrap();
+    let font = FontRef::try_from_slice(&data).unwrap();
+    let scaled = font.as_scaled(PxScale::from(SIZE));
+
+    let chars = ' '..='~';
+    let mut glyphs = vec![0u8; chars.clone().count() * WIDTH * HEIGHT];
+
+    for (i, c) in chars.enumerate() {
+        let mut glyph = scaled.scaled_glyph(c);
+        let advance = scaled.h_advance(glyph.id);
+
+        // Centered in the cell, sitting on a common baseline.
+        glyph.position = point(
+            ((WIDTH as f32 - advance) / 2.0).max(0.0),
+            scaled.ascent().round() + 1.0,
+        );
+
+        let Some(outline) = font.outline_glyph(glyph) else {
+            continue;
+        };
+
+        let bounds = outline.px_bounds();
+        let cell = &mut glyphs[i * WIDTH * HEIGHT..(i + 1) * WIDTH * HEIGHT];
+
+        outline.draw(|x, y, coverage| {
+            let x = bounds.min.x as i32 + x as i32;
+            let y = bounds.min.y as i32 + y as i32;
+
+            if (0..WIDTH as i32).contains(&x) && (0..HEIGHT as i32).contains(&y) {
+                let pixel = &mut cell[y as usize * WIDTH + x as usize];
+                *pixel = (*pixel).max((coverage.min(1.0) * 255.0) as u8);
+            }
+        });
+    }
+
+    std::fs::write(out, glyphs).unwrap();
+}
@@ -0,0 +1,29 @@
+// A rounded rectangle filling the whole
+// element, with anti-aliased corners.
+
+precision mediump float;
+uniform float alpha;
+#if defined(DEBUG_FLAGS)
+uniform float tint;
+#endif
+uniform vec2 size;
+varying vec2 v_coords;
+
+uniform vec4 color;
+uniform float radius;
+uniform float opacity;
+
+float rounded_box(vec2 p, vec2 half_size, float r) {
+    vec2 q = abs(p) - half_size + vec2(r);
+    return length(max(q, 0.0)) + min(max(q.x, q.y), 0.0) - r;
+}
+
+void main() {
+    vec2 p = v_coords * size - size / 2.0;
+    float d = rounded_box(p, size / 2.0, radius);
+
+    float coverage = clamp(0.5 - d, 0.0, 1.0);
+    float a = color.a * coverage * opacity * alpha;
+
+    gl_FragColor = vec4(color.rgb * a, a);
+}
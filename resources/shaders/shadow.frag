@@ -0,0 +1,31 @@
+// A soft shadow around a rounded rectangle, spreading
+// `spread` pixels out from it -- and nothing under it.
+
+precision mediump float;
+uniform float alpha;
+#if defined(DEBUG_FLAGS)
+uniform float tint;
+#endif
+uniform vec2 size;
+varying vec2 v_coords;
+
+uniform vec4 color;
+uniform float radius;
+uniform float spread;
+uniform float opacity;
+
+float rounded_box(vec2 p, vec2 half_size, float r) {
+    vec2 q = abs(p) - half_size + vec2(r);
+    return length(max(q, 0.0)) + min(max(q.x, q.y), 0.0) - r;
+}
+
+void main() {
+    vec2 p = v_coords * size - size / 2.0;
+    float d = rounded_box(p, size / 2.0 - vec2(spread), radius);
+
+    // Translucent windows shouldn't show their own shadow.
+    float strength = pow(clamp(1.0 - d / spread, 0.0, 1.0), 2.0) * step(0.0, d);
+    float a = color.a * strength * opacity * alpha;
+
+    gl_FragColor = vec4(color.rgb * a, a);
+}
@@ -6,7 +6,10 @@
 //! View the original spec at [specification.freedesktop.org](https://specifications.freedesktop.org/basedir-spec/basedir-spec-latest.html).
 //!
 
-use std::{env, path::PathBuf};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
 
 pub trait BaseDir {
     ///
@@ -21,25 +24,74 @@ pub trait BaseDir {
     /// * The folder at the explicitly path does not exist; or
     /// * The corresponding environment vaiable is not set.  
     ///
+    /// `None` when there isn't one (without a `$HOME`).
     ///
-    fn default(&self) -> PathBuf;
+    fn default(&self) -> Option<PathBuf>;
 
     ///
     /// Returns the path of the base directory.
     /// Handles any error cases.
     ///
+    /// `None` when it isn't set, and there's no default.
     ///
-    fn path(&self) -> PathBuf {
+    fn path(&self) -> Option<PathBuf> {
         env::var(self.env_variable())
             .ok()
             .and_then(|p| {
                 let p = PathBuf::from(p);
                 p.exists().then_some(p)
             })
-            .unwrap_or(self.default())
+            .or_else(|| self.default())
+    }
+
+    ///
+    /// Return the label of the environment variable listing the directories
+    /// searched after this one (`XDG_CONFIG_DIRS` for `XDG_CONFIG_HOME`),
+    /// if there is one.
+    ///
+    fn search_variable(&self) -> Option<&'static str> {
+        None
+    }
+
+    ///
+    /// Return the directories searched when
+    /// the search variable isn't set.
+    ///
+    fn search_defaults(&self) -> Vec<PathBuf> {
+        vec![]
+    }
+
+    ///
+    /// Returns the directories searched after this one,
+    /// most important first.
+    ///
+    fn search_path(&self) -> Vec<PathBuf> {
+        let dirs: Vec<PathBuf> = self
+            .search_variable()
+            .and_then(|v| env::var(v).ok())
+            .map(|v| split_search_path(&v))
+            .unwrap_or_default();
+
+        match dirs.is_empty() {
+            true => self.search_defaults(),
+            false => dirs,
+        }
     }
 }
 
+///
+/// The directories in a `:`-separated search path --
+/// relative ones are ignored, as the spec says.
+///
+fn split_search_path(value: &str) -> Vec<PathBuf> {
+    value
+        .split(':')
+        .map(Path::new)
+        .filter(|p| p.is_absolute())
+        .map(Path::to_path_buf)
+        .collect()
+}
+
 ///
 /// Wrapper enum over the XDG base directories.
 ///
@@ -76,12 +128,10 @@ impl BaseDir for XdgBaseDir {
         }
     }
 
-    fn default(&self) -> PathBuf {
-        let mut path: PathBuf = env::var("HOME").expect("NO $HOME SET! YOU MONSTER!").into();
-
-        if !path.exists() {
-            panic!("$HOME INEXISTANT -- PANIC!");
-        }
+    fn default(&self) -> Option<PathBuf> {
+        let mut path: PathBuf = env::var_os("HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute() && p.exists())?;
 
         match self {
             XdgBaseDir::Data => path.push(".local/share"),
@@ -89,6 +139,39 @@ impl BaseDir for XdgBaseDir {
             XdgBaseDir::State => path.push(".local/state"),
         };
 
-        path
+        Some(path)
+    }
+
+    fn search_variable(&self) -> Option<&'static str> {
+        match self {
+            Self::Data => Some("XDG_DATA_DIRS"),
+            Self::Config => Some("XDG_CONFIG_DIRS"),
+            Self::State => None,
+        }
+    }
+
+    fn search_defaults(&self) -> Vec<PathBuf> {
+        let dirs: &[&str] = match self {
+            Self::Data => &["/usr/local/share", "/usr/share"],
+            Self::Config => &["/etc/xdg"],
+            Self::State => &[],
+        };
+
+        dirs.iter().map(PathBuf::from).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::split_search_path;
+
+    #[test]
+    fn search_path() {
+        assert_eq!(
+            split_search_path("/etc/xdg/desktop:relative::/etc/xdg"),
+            vec![PathBuf::from("/etc/xdg/desktop"), PathBuf::from("/etc/xdg")]
+        );
     }
 }
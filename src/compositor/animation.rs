@@ -0,0 +1,443 @@
+//!
+//! Time-based window animations -- opening, closing,
+//! moving and (un)maximizing -- driven by the compositor's clock.
+//!
+//! Animations never touch where a window really is in the `Space`;
+//! they only change where (and how big) it's drawn, through its
+//! [`RenderTransform`].
+//!
+//! Workspace switches will hook in here too, once there are workspaces.
+//!
+
+use std::time::Duration;
+
+use smithay::{
+    desktop::space::SpaceElement,
+    utils::{IsAlive, Logical, Point, Rectangle, Scale},
+};
+
+use crate::config::sections::animations::Animations;
+
+use super::{backend::Backend, effects::offscreen, shell::AvWindow, state::Navda};
+
+///
+/// How far from full size windows
+/// start opening (and end closing).
+///
+const OPEN_CLOSE_SCALE: f64 = 0.9;
+
+///
+/// How an animation speeds up and slows down.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    Linear,
+    EaseOutQuad,
+    EaseOutCubic,
+    EaseInOutCubic,
+}
+
+impl<'a> TryFrom<&'a str> for Curve {
+    type Error = String;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        match value {
+            "linear" => Ok(Self::Linear),
+            "easeOutQuad" => Ok(Self::EaseOutQuad),
+            "easeOutCubic" => Ok(Self::EaseOutCubic),
+            "easeInOutCubic" => Ok(Self::EaseInOutCubic),
+            _ => Err(value.to_string()),
+        }
+    }
+}
+
+impl Curve {
+    ///
+    /// Ease `t` (from 0 to 1) along this curve.
+    ///
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Self::Linear => t,
+            Self::EaseOutQuad => 1.0 - (1.0 - t).powi(2),
+            Self::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOutCubic if t < 0.5 => 4.0 * t.powi(3),
+            Self::EaseInOutCubic => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+///
+/// The user's animation preferences, read
+/// from the `animations` config section.
+///
+#[derive(Debug, Clone)]
+pub struct AnimationPolicy {
+    pub enabled: bool,
+    pub curve: Curve,
+    pub open: Duration,
+    pub close: Duration,
+    pub movement: Duration,
+    pub maximize: Duration,
+}
+
+impl Default for AnimationPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            curve: Curve::EaseOutCubic,
+            open: Duration::from_millis(200),
+            close: Duration::from_millis(150),
+            movement: Duration::from_millis(200),
+            maximize: Duration::from_millis(250),
+        }
+    }
+}
+
+impl AnimationPolicy {
+    pub fn from_config(animations: &Animations, log: &slog::Logger) -> Self {
+        let default = Self::default();
+
+        let curve = Curve::try_from(animations.curve.as_str()).unwrap_or_else(|v| {
+            slog::warn!(
+                log,
+                "Unknown animation curve `{}`, using `easeOutCubic`.",
+                v
+            );
+            default.curve
+        });
+
+        Self {
+            enabled: animations.enabled,
+            curve,
+            open: animations.openDuration.into(),
+            close: animations.closeDuration.into(),
+            movement: animations.moveDuration.into(),
+            maximize: animations.maximizeDuration.into(),
+        }
+    }
+}
+
+///
+/// Where (and how) to draw a window, relative to where it really is.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderTransform {
+    ///
+    /// How far to shift the window.
+    ///
+    pub offset: Point<f64, Logical>,
+
+    ///
+    /// How much to scale the window, from
+    /// the top-left of its geometry.
+    ///
+    pub scale: Scale<f64>,
+
+    ///
+    /// Only drawn for windows drawn from a texture
+    /// (see [`effects::offscreen`](super::effects::offscreen)).
+    ///
+    pub opacity: f32,
+}
+
+impl RenderTransform {
+    ///
+    /// The transform that draws `actual` at `target`.
+    ///
+    fn between(
+        actual: Rectangle<f64, Logical>,
+        target: Rectangle<f64, Logical>,
+        opacity: f32,
+    ) -> Self {
+        Self {
+            offset: target.loc - actual.loc,
+            scale: Scale::from((
+                target.size.w / actual.size.w.max(1.0),
+                target.size.h / actual.size.h.max(1.0),
+            )),
+            opacity,
+        }
+    }
+}
+
+///
+/// The way a window moves over an animation.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Path {
+    ///
+    /// From one rectangle to another.
+    ///
+    Between(Rectangle<f64, Logical>, Rectangle<f64, Logical>),
+
+    ///
+    /// Scaling about its own center (wherever
+    /// it ends up being), from one factor to another.
+    ///
+    Scaling(f64, f64),
+}
+
+///
+/// A window sliding (and scaling, and fading) over time.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    path: Path,
+    opacity: (f32, f32),
+    started: Duration,
+    duration: Duration,
+    curve: Curve,
+}
+
+impl Animation {
+    ///
+    /// How far along the animation is, eased, from 0 to 1.
+    ///
+    fn progress(&self, now: Duration) -> f64 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+
+        let elapsed = now.saturating_sub(self.started);
+        self.curve
+            .apply(elapsed.as_secs_f64() / self.duration.as_secs_f64())
+    }
+
+    pub fn is_done(&self, now: Duration) -> bool {
+        now.saturating_sub(self.started) >= self.duration
+    }
+
+    ///
+    /// Where a window that's really at `actual`
+    /// should be drawn at `now`.
+    ///
+    fn rect(&self, actual: Rectangle<f64, Logical>, now: Duration) -> Rectangle<f64, Logical> {
+        let t = self.progress(now);
+        let lerp = |from: f64, to: f64| from + (to - from) * t;
+
+        match self.path {
+            Path::Between(from, to) => Rectangle::from_loc_and_size(
+                (lerp(from.loc.x, to.loc.x), lerp(from.loc.y, to.loc.y)),
+                (lerp(from.size.w, to.size.w), lerp(from.size.h, to.size.h)),
+            ),
+            Path::Scaling(from, to) => scaled_about_center(actual, lerp(from, to)),
+        }
+    }
+
+    fn opacity(&self, now: Duration) -> f32 {
+        let (from, to) = self.opacity;
+        from + (to - from) * self.progress(now) as f32
+    }
+
+    ///
+    /// How to draw a window that's really at `actual`, at `now`.
+    ///
+    pub fn transform(&self, actual: Rectangle<f64, Logical>, now: Duration) -> RenderTransform {
+        RenderTransform::between(actual, self.rect(actual, now), self.opacity(now))
+    }
+}
+
+///
+/// A window that's gone, still being drawn (from the
+/// texture it was last drawn into) while it animates out.
+///
+#[derive(Debug)]
+pub struct ClosingWindow {
+    pub window: AvWindow,
+
+    ///
+    /// Where the window last was.
+    ///
+    pub geometry: Rectangle<i32, Logical>,
+
+    animation: Animation,
+}
+
+impl<BEnd: Backend> Navda<BEnd> {
+    fn animation_now(&self) -> Duration {
+        self.clock.now().into()
+    }
+
+    ///
+    /// Where `window` really is -- `None` if it isn't mapped.
+    ///
+    fn window_rect(&self, window: &AvWindow) -> Option<Rectangle<f64, Logical>> {
+        let location = self.space.element_location(window)?;
+        Some(Rectangle::from_loc_and_size(location, window.geometry().size).to_f64())
+    }
+
+    fn start_animation(&self, path: Path, opacity: (f32, f32), duration: Duration) -> Animation {
+        Animation {
+            path,
+            opacity,
+            started: self.animation_now(),
+            duration,
+            curve: self.animation_policy.curve,
+        }
+    }
+
+    ///
+    /// Grow and fade `window` in, wherever it's mapped
+    /// -- once it's got a buffer to show.
+    ///
+    pub fn animate_open(&mut self, window: &AvWindow) {
+        if !self.animation_policy.enabled {
+            return;
+        }
+
+        let animation = self.start_animation(
+            Path::Scaling(OPEN_CLOSE_SCALE, 1.0),
+            (0.0, 1.0),
+            self.animation_policy.open,
+        );
+        window.set_animation(Some(animation));
+    }
+
+    ///
+    /// Where `window` is drawn right now -- part-way
+    /// through an animation, or where it really is.
+    ///
+    /// Grab this before moving or resizing a window,
+    /// and hand it to [`Navda::animate_from`] after.
+    ///
+    pub fn drawn_rect(&self, window: &AvWindow) -> Option<Rectangle<f64, Logical>> {
+        let rect = self.window_rect(window)?;
+
+        match window.animation() {
+            Some(animation) => Some(animation.rect(rect, self.animation_now())),
+            None => Some(rect),
+        }
+    }
+
+    ///
+    /// Where `window` is headed -- its new location, at the size
+    /// it's been asked to take (which the client may not have caught up with).
+    ///
+    fn target_rect(&self, window: &AvWindow) -> Option<Rectangle<f64, Logical>> {
+        let location = self.space.element_location(window)?;
+        let size = match window {
+            AvWindow::Wayland(w) => w
+                .toplevel()
+                .with_pending_state(|state| state.size)
+                .unwrap_or_else(|| window.geometry().size),
+            AvWindow::X11(w) => w.geometry().size,
+        };
+
+        Some(Rectangle::from_loc_and_size(location, size).to_f64())
+    }
+
+    ///
+    /// Slide `window` from `from` (see [`Navda::drawn_rect`])
+    /// to wherever it's just been moved and resized to.
+    ///
+    /// `maximize` picks the (un)maximize duration over the move one.
+    ///
+    pub fn animate_from(
+        &mut self,
+        window: &AvWindow,
+        from: Option<Rectangle<f64, Logical>>,
+        maximize: bool,
+    ) {
+        if !self.animation_policy.enabled {
+            return;
+        }
+
+        let (Some(from), Some(to)) = (from, self.target_rect(window)) else {
+            return;
+        };
+        if from == to {
+            return;
+        }
+
+        let duration = if maximize {
+            self.animation_policy.maximize
+        } else {
+            self.animation_policy.movement
+        };
+
+        let animation = self.start_animation(Path::Between(from, to), (1.0, 1.0), duration);
+        window.set_animation(Some(animation));
+    }
+
+    ///
+    /// Keep drawing `window` -- which is about to be unmapped
+    /// -- while it shrinks and fades out.
+    ///
+    /// Only once it's been drawn into a texture, as
+    /// its surfaces won't be around to draw it from.
+    ///
+    pub fn animate_close(&mut self, window: &AvWindow) {
+        if !self.animation_policy.enabled || !offscreen::has_snapshot(window) {
+            return;
+        }
+
+        let Some(rect) = self.window_rect(window) else {
+            return;
+        };
+
+        let animation = self.start_animation(
+            Path::Scaling(1.0, OPEN_CLOSE_SCALE),
+            (1.0, 0.0),
+            self.animation_policy.close,
+        );
+
+        self.closing_windows.push(ClosingWindow {
+            window: window.clone(),
+            geometry: rect.to_i32_round(),
+            animation,
+        });
+    }
+
+    ///
+    /// Start closing animations for windows whose clients have
+    /// destroyed them -- before `Space::refresh` forgets them.
+    ///
+    pub fn animate_dead_windows(&mut self) {
+        let dead = self
+            .space
+            .elements()
+            .filter(|w| !w.alive())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for window in dead {
+            self.animate_close(&window);
+        }
+    }
+
+    ///
+    /// Move every animation along to the current time,
+    /// dropping the ones that have finished.
+    ///
+    /// Called once per event loop iteration.
+    ///
+    pub fn advance_animations(&mut self) {
+        let now = self.animation_now();
+
+        for window in self.space.elements() {
+            let Some(animation) = window.animation() else {
+                continue;
+            };
+
+            if animation.is_done(now) {
+                window.set_animation(None);
+                window.set_render_transform(None);
+                continue;
+            }
+
+            if let Some(rect) = self.window_rect(window) {
+                window.set_render_transform(Some(animation.transform(rect, now)));
+            }
+        }
+
+        self.closing_windows
+            .retain(|closing| !closing.animation.is_done(now));
+
+        for closing in &self.closing_windows {
+            let transform = closing.animation.transform(closing.geometry.to_f64(), now);
+            closing.window.set_render_transform(Some(transform));
+        }
+    }
+}
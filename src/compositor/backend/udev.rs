@@ -43,9 +43,9 @@ use smithay::{
         libinput::{LibinputInputBackend, LibinputSessionInterface},
         renderer::{
             damage::{DamageTrackedRenderer, DamageTrackedRendererError},
-            element::{texture::TextureBuffer, AsRenderElements},
-            gles2::{Gles2Renderbuffer, Gles2Renderer},
-            multigpu::{egl::EglGlesBackend, GpuManager, MultiRenderer, MultiTexture},
+            element::{texture::TextureBuffer, AsRenderElements, RenderElement},
+            gles2::{element::PixelShaderElement, Gles2Renderbuffer, Gles2Renderer},
+            multigpu::{egl::EglGlesBackend, GpuManager, MultiError, MultiRenderer, MultiTexture},
             Bind, Frame, ImportDma, ImportEgl, Renderer,
         },
         session::{libseat::LibSeatSession, Event as SessionEvent, Session},
@@ -55,7 +55,7 @@ use smithay::{
     delegate_dmabuf,
     desktop::{space::SurfaceTree, utils::OutputPresentationFeedback, Space},
     input::pointer::{CursorImageAttributes, CursorImageStatus},
-    output::{Mode, Output, PhysicalProperties, Subpixel},
+    output::{Mode, Output, PhysicalProperties, Scale as OutputScale, Subpixel},
     reexports::{
         calloop::{
             timer::{TimeoutAction, Timer},
@@ -76,7 +76,10 @@ use smithay::{
         wayland_protocols::wp::presentation_time::server::wp_presentation_feedback,
         wayland_server::{backend::GlobalId, protocol::wl_surface, Display, DisplayHandle},
     },
-    utils::{Clock, DeviceFd, IsAlive, Logical, Monotonic, Point, Rectangle, Scale, Transform},
+    utils::{
+        Buffer, Clock, DeviceFd, IsAlive, Logical, Monotonic, Physical, Point, Rectangle, Scale,
+        Transform,
+    },
     wayland::{
         compositor,
         dmabuf::{DmabufGlobal, DmabufHandler, DmabufState, ImportError},
@@ -87,7 +90,9 @@ use smithay::{
 use crate::compositor::{
     components::Cursor,
     drawing::{PointerElement, CLEAR_COLOR},
-    render::{render_output, CustomRenderElements},
+    effects::{prepare_output, EffectsRenderer, OffscreenElement, Shaders},
+    render::{render_output, CustomRenderElements, RenderExtras},
+    scale::ScalePolicy,
     shell::{self, AvWindow},
     state::{post_repaint, take_presentation_feedback, CalloopData, Navda},
 };
@@ -102,6 +107,44 @@ type UdevRenderer<'a> = MultiRenderer<
     Gles2Renderbuffer,
 >;
 
+///
+/// Effects are drawn by the GPU doing the rendering.
+///
+impl<'a> EffectsRenderer for UdevRenderer<'a> {
+    fn effect_shaders(&mut self, log: &slog::Logger) -> Option<Shaders> {
+        Shaders::get(self.as_mut(), log)
+    }
+
+    fn draw_shader<'frame>(
+        frame: &mut <Self as Renderer>::Frame<'frame>,
+        element: &PixelShaderElement,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+        log: &slog::Logger,
+    ) -> Result<(), <Self as Renderer>::Error> {
+        RenderElement::<Gles2Renderer>::draw(element, frame.as_mut(), src, dst, damage, log)
+            .map_err(MultiError::Render)
+    }
+
+    fn offscreen_renderer(&mut self) -> Option<&mut Gles2Renderer> {
+        Some(self.as_mut())
+    }
+
+    fn draw_offscreen<'frame>(
+        frame: &mut <Self as Renderer>::Frame<'frame>,
+        element: &OffscreenElement,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+        _log: &slog::Logger,
+    ) -> Result<(), <Self as Renderer>::Error> {
+        element
+            .draw_gles(frame.as_mut(), src, dst, damage)
+            .map_err(MultiError::Render)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 struct UdevOutputId {
     device_id: DrmNode,
@@ -368,8 +411,16 @@ pub fn run_udev(log: Logger) {
         if result.is_err() {
             state.running.store(false, Ordering::SeqCst);
         } else {
+            state.animate_dead_windows();
             state.space.refresh();
             state.popups.cleanup();
+            state.refresh_overview();
+            state.refit_maximized_windows();
+            state.refresh_foreign_toplevels();
+            state.refresh_clipboard();
+            state.refresh_config_banner();
+            state.ping_clients();
+            state.advance_animations();
             display.flush_clients().unwrap();
         }
     }
@@ -408,6 +459,8 @@ fn scan_connectors(
     gbm: &GbmDevice<DrmDeviceFd>,
     display: &mut Display<Navda<UdevData>>,
     space: &mut Space<AvWindow>,
+    scale_policy: &ScalePolicy,
+    scales: &HashMap<String, f64>,
     #[cfg(feature = "debug")] fps_texture: &MultiTexture,
     logger: &::slog::Logger,
 ) -> HashMap<crtc::Handle, Rc<RefCell<SurfaceData>>> {
@@ -526,7 +579,19 @@ fn scan_connectors(
                 0,
             )
                 .into();
-            output.change_current_state(Some(mode), None, None, Some(position));
+            // Outputs that were already around keep their scale.
+            let scale = scales
+                .get(&output.name())
+                .copied()
+                .unwrap_or_else(|| scale_policy.scale_for_output(&output, mode));
+            slog::info!(logger, "Scaling {} by {}", output.name(), scale);
+
+            output.change_current_state(
+                Some(mode),
+                None,
+                Some(OutputScale::Fractional(scale)),
+                Some(position),
+            );
             output.set_preferred(mode);
             space.map_output(&output, position);
 
@@ -609,6 +674,8 @@ impl Navda<UdevData> {
             &gbm,
             display,
             &mut self.space,
+            &self.scale_policy,
+            &HashMap::new(),
             &self.log,
         )));
 
@@ -661,7 +728,8 @@ impl Navda<UdevData> {
             let logger = self.log.clone();
             let loop_handle = self.handle.clone();
 
-            // scan_connectors will recreate the outputs (and sadly also reset the scales)
+            // scan_connectors will recreate the outputs, so hang on to their scales
+            let mut scales = HashMap::new();
             for output in self
                 .space
                 .outputs()
@@ -675,6 +743,7 @@ impl Navda<UdevData> {
                 .collect::<Vec<_>>()
                 .into_iter()
             {
+                scales.insert(output.name(), output.current_scale().fractional_scale());
                 self.space.unmap_output(&output);
             }
 
@@ -686,6 +755,8 @@ impl Navda<UdevData> {
                 &backend_data.gbm,
                 display,
                 &mut self.space,
+                &self.scale_policy,
+                &scales,
                 #[cfg(feature = "debug")]
                 &self.backend_data.fps_texture,
                 &logger,
@@ -940,11 +1011,26 @@ impl Navda<UdevData> {
             };
 
         for (&crtc, surface) in to_render_iter {
-            // TODO get scale from the rendersurface when supporting HiDPI
+            let output = if let Some(output) = self.space.outputs().find(|o| {
+                o.user_data().get::<UdevOutputId>()
+                    == Some(&UdevOutputId {
+                        device_id: surface.borrow().device_id,
+                        crtc,
+                    })
+            }) {
+                output.clone()
+            } else {
+                // somehow we got called with an invalid output
+                continue;
+            };
+
+            // The cursor image for the output's scale (rounded up).
+            let scale = output.current_scale().integer_scale() as u32;
             let frame = self
                 .backend_data
                 .pointer_image
-                .get_image(1 /*scale*/, self.clock.now().try_into().unwrap());
+                .get_image(scale, self.clock.now().try_into().unwrap());
+            let buffer_scale = self.backend_data.pointer_image.buffer_scale(&frame);
             let primary_gpu = self.backend_data.primary_gpu;
             let mut renderer = self
                 .backend_data
@@ -967,7 +1053,7 @@ impl Navda<UdevData> {
                         &frame.pixels_rgba,
                         (frame.width as i32, frame.height as i32),
                         false,
-                        1,
+                        buffer_scale,
                         Transform::Normal,
                         None,
                     )
@@ -976,23 +1062,19 @@ impl Navda<UdevData> {
                     texture
                 });
 
-            let output = if let Some(output) = self.space.outputs().find(|o| {
-                o.user_data().get::<UdevOutputId>()
-                    == Some(&UdevOutputId {
-                        device_id: surface.borrow().device_id,
-                        crtc,
-                    })
-            }) {
-                output.clone()
-            } else {
-                // somehow we got called with an invalid output
-                continue;
-            };
-
             let result = render_surface(
                 &mut surface.borrow_mut(),
                 &mut renderer,
                 &self.space,
+                RenderExtras {
+                    custom_elements: &[],
+                    switcher: self.switcher.as_ref(),
+                    overview: self.overview.as_ref(),
+                    tile_preview: self.tile_preview,
+                    closing_windows: &self.closing_windows,
+                    wallpaper: &self.wallpaper,
+                    banner: self.config_banner.as_ref(),
+                },
                 &output,
                 self.seat.input_method().unwrap(),
                 self.pointer_location,
@@ -1056,6 +1138,7 @@ fn render_surface<'a>(
     surface: &'a mut SurfaceData,
     renderer: &mut UdevRenderer<'a>,
     space: &Space<AvWindow>,
+    extras: RenderExtras<'_, UdevRenderer<'a>>,
     output: &Output,
     input_method: &InputMethodHandle,
     pointer_location: Point<f64, Logical>,
@@ -1069,6 +1152,8 @@ fn render_surface<'a>(
     let output_geometry = space.output_geometry(output).unwrap();
     let scale = Scale::from(output.current_scale().fractional_scale());
 
+    prepare_output(renderer, space, output, logger);
+
     let (dmabuf, age) = surface.surface.next_buffer()?;
     renderer.bind(dmabuf)?;
 
@@ -1142,7 +1227,10 @@ fn render_surface<'a>(
     let (rendered, states) = render_output(
         output,
         space,
-        &elements,
+        RenderExtras {
+            custom_elements: &elements,
+            ..extras
+        },
         renderer,
         &mut surface.damage_tracked_renderer,
         age.into(),
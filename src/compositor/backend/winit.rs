@@ -36,7 +36,8 @@ use smithay::{
 
 use crate::compositor::{
     drawing::PointerElement,
-    render::{render_output, CustomRenderElements},
+    effects::prepare_output,
+    render::{render_output, CustomRenderElements, RenderExtras},
     shell,
     state::{post_repaint, take_presentation_feedback, CalloopData, Navda},
 };
@@ -218,6 +219,12 @@ pub fn run_winit(log: Logger) {
 
             let input_method = state.seat.input_method().unwrap();
             let dnd_icon = state.dnd_icon.as_ref();
+            let switcher = state.switcher.as_ref();
+            let overview = state.overview.as_ref();
+            let tile_preview = state.tile_preview;
+            let closing_windows = &state.closing_windows;
+            let wallpaper = &state.wallpaper;
+            let banner = state.config_banner.as_ref();
 
             let scale = Scale::from(output.current_scale().fractional_scale());
             let cursor_hotspot = if let CursorImageStatus::Surface(ref surface) = *cursor_guard {
@@ -236,6 +243,8 @@ pub fn run_winit(log: Logger) {
             let cursor_pos = state.pointer_location - cursor_hotspot.to_f64();
             let cursor_pos_scaled = cursor_pos.to_physical(scale).to_i32_round();
 
+            prepare_output(backend.renderer(), space, &output, &log);
+
             let render_res = backend.bind().and_then(|_| {
                 let age = if *full_redraw > 0 {
                     0
@@ -283,7 +292,15 @@ pub fn run_winit(log: Logger) {
                 render_output(
                     &output,
                     space,
-                    &elements,
+                    RenderExtras {
+                        custom_elements: &elements,
+                        switcher,
+                        overview,
+                        tile_preview,
+                        closing_windows,
+                        wallpaper,
+                        banner,
+                    },
                     renderer,
                     damage_tracked_renderer,
                     age,
@@ -338,8 +355,16 @@ pub fn run_winit(log: Logger) {
         if result.is_err() {
             state.running.store(false, Ordering::SeqCst);
         } else {
+            state.animate_dead_windows();
             state.space.refresh();
             state.popups.cleanup();
+            state.refresh_overview();
+            state.refit_maximized_windows();
+            state.refresh_foreign_toplevels();
+            state.refresh_clipboard();
+            state.refresh_config_banner();
+            state.ping_clients();
+            state.advance_animations();
             display.flush_clients().unwrap();
         }
     }
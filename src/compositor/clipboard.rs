@@ -0,0 +1,548 @@
+//!
+//! The clipboard (and primary selection), as the compositor sees them
+//! -- so they can outlive the programs they were copied from,
+//! and clipboard tools can get at them (see [`data_control`]).
+//!
+//! What's copied is read out of its program straight away, for the
+//! MIME types in [`PERSISTED_MIME_TYPES`]. Once that program goes away,
+//! the compositor takes over the clipboard and hands out its copy instead.
+//!
+//! The primary selection changes with every bit of highlighted text,
+//! so it isn't copied -- it's only passed along.
+//!
+//! [`data_control`]: super::protocols::data_control
+//!
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{ErrorKind, Read, Write},
+    mem,
+    os::unix::prelude::{AsRawFd, FromRawFd, OwnedFd},
+    rc::Rc,
+};
+
+use smithay::{
+    reexports::{
+        calloop::{generic::Generic, Interest, Mode, PostAction},
+        nix::{
+            fcntl::{fcntl, FcntlArg, OFlag},
+            unistd::pipe2,
+        },
+        wayland_protocols::wp::primary_selection::zv1::server::zwp_primary_selection_source_v1::ZwpPrimarySelectionSourceV1,
+        wayland_protocols_wlr::data_control::v1::server::zwlr_data_control_source_v1::ZwlrDataControlSourceV1,
+        wayland_server::{protocol::wl_data_source::WlDataSource, Resource},
+    },
+    wayland::{data_device, primary_selection},
+};
+
+use crate::config::sections::clipboard::Clipboard;
+
+use super::{
+    backend::Backend,
+    protocols::data_control::{DataControlAccess, DataControlSource},
+    state::Navda,
+};
+
+///
+/// What's worth keeping a copy of -- text, links and
+/// images. Anything else is left with its program.
+///
+pub const PERSISTED_MIME_TYPES: [&str; 9] = [
+    "text/plain;charset=utf-8",
+    "text/plain",
+    "UTF8_STRING",
+    "STRING",
+    "TEXT",
+    "text/html",
+    "text/uri-list",
+    "x-special/gnome-copied-files",
+    "image/png",
+];
+
+const READ_CHUNK: usize = 64 * 1024;
+
+///
+/// How the clipboard is kept, read
+/// from the `clipboard` config section.
+///
+#[derive(Debug, Clone)]
+pub struct ClipboardPolicy {
+    pub persist: bool,
+    pub history_size: usize,
+
+    ///
+    /// In bytes.
+    ///
+    pub max_entry_size: usize,
+
+    pub data_control: DataControlAccess,
+}
+
+impl ClipboardPolicy {
+    pub fn from_config(clipboard: &Clipboard) -> Self {
+        Self {
+            persist: clipboard.persist,
+            history_size: clipboard.historySize.max(1) as usize,
+            max_entry_size: clipboard.maxEntrySize.max(0) as usize * 1024,
+            data_control: DataControlAccess::from(clipboard.dataControl.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionKind {
+    Clipboard,
+    Primary,
+}
+
+///
+/// A copy of a selection -- its data for each MIME type.
+///
+#[derive(Debug, Default, PartialEq)]
+pub struct SelectionContents(Vec<(String, Vec<u8>)>);
+
+impl SelectionContents {
+    fn mime_types(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|(mime_type, _)| mime_type.clone())
+            .collect()
+    }
+
+    fn get(&self, mime_type: &str) -> Option<&[u8]> {
+        self.0
+            .iter()
+            .find(|(m, _)| m == mime_type)
+            .map(|(_, data)| data.as_slice())
+    }
+
+    fn size(&self) -> usize {
+        self.0.iter().map(|(_, data)| data.len()).sum()
+    }
+}
+
+///
+/// Whoever the selection's data comes from.
+///
+#[derive(Debug, Clone)]
+pub enum SelectionSource {
+    Data(WlDataSource),
+    Primary(ZwpPrimarySelectionSourceV1),
+
+    ///
+    /// A clipboard tool's, set through `wlr-data-control`.
+    ///
+    DataControl(ZwlrDataControlSourceV1),
+
+    ///
+    /// Kept by the compositor, after the program
+    /// it came from went away (or from the history).
+    ///
+    Persisted(Rc<SelectionContents>),
+}
+
+impl SelectionSource {
+    pub fn mime_types(&self) -> Vec<String> {
+        match self {
+            Self::Data(source) => {
+                data_device::with_source_metadata(source, |meta| meta.mime_types.clone())
+                    .unwrap_or_default()
+            }
+            Self::Primary(source) => {
+                primary_selection::with_source_metadata(source, |meta| meta.mime_types.clone())
+                    .unwrap_or_default()
+            }
+            Self::DataControl(source) => source
+                .data::<DataControlSource>()
+                .map(DataControlSource::mime_types)
+                .unwrap_or_default(),
+            Self::Persisted(contents) => contents.mime_types(),
+        }
+    }
+
+    fn alive(&self) -> bool {
+        match self {
+            Self::Data(source) => source.is_alive(),
+            Self::Primary(source) => source.is_alive(),
+            Self::DataControl(source) => source.is_alive(),
+            Self::Persisted(_) => true,
+        }
+    }
+
+    ///
+    /// Write the selection, as `mime_type`, into `fd`.
+    ///
+    fn send(&self, mime_type: String, fd: OwnedFd) {
+        match self {
+            Self::Data(source) => source.send(mime_type, fd.as_raw_fd()),
+            Self::Primary(source) => source.send(mime_type, fd.as_raw_fd()),
+            Self::DataControl(source) => source.send(mime_type, fd.as_raw_fd()),
+            Self::Persisted(contents) => {
+                let data = match contents.get(&mime_type) {
+                    Some(data) => data.to_vec(),
+                    None => return,
+                };
+
+                // The reader could take its time -- don't hold up the compositor.
+                std::thread::spawn(move || {
+                    let _ = File::from(fd).write_all(&data);
+                });
+            }
+        }
+    }
+
+    fn cancel(&self) {
+        // Clients' own sources are cancelled by smithay.
+        if let Self::DataControl(source) = self {
+            source.cancelled();
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Selection {
+    ///
+    /// Bumped every time the selection changes,
+    /// to spot stale offers and copies.
+    ///
+    serial: u64,
+    source: Option<SelectionSource>,
+
+    ///
+    /// The source's contents, once
+    /// they've all been read out of it.
+    ///
+    copied: Option<Rc<SelectionContents>>,
+    copying: SelectionContents,
+
+    ///
+    /// How many MIME types are still being read.
+    ///
+    pending: usize,
+}
+
+///
+/// Both selections, and the clipboard's history.
+///
+#[derive(Debug)]
+pub struct ClipboardState {
+    policy: ClipboardPolicy,
+    clipboard: Selection,
+    primary: Selection,
+
+    ///
+    /// Most recent first.
+    ///
+    history: VecDeque<Rc<SelectionContents>>,
+    next_serial: u64,
+}
+
+impl ClipboardState {
+    pub fn new(policy: ClipboardPolicy) -> Self {
+        Self {
+            policy,
+            clipboard: Selection::default(),
+            primary: Selection::default(),
+            history: VecDeque::new(),
+            next_serial: 1,
+        }
+    }
+
+    pub fn policy(&self) -> &ClipboardPolicy {
+        &self.policy
+    }
+
+    fn selection(&self, kind: SelectionKind) -> &Selection {
+        match kind {
+            SelectionKind::Clipboard => &self.clipboard,
+            SelectionKind::Primary => &self.primary,
+        }
+    }
+
+    fn selection_mut(&mut self, kind: SelectionKind) -> &mut Selection {
+        match kind {
+            SelectionKind::Clipboard => &mut self.clipboard,
+            SelectionKind::Primary => &mut self.primary,
+        }
+    }
+
+    ///
+    /// The current `kind` selection, and its serial.
+    ///
+    pub fn current(&self, kind: SelectionKind) -> (u64, Option<&SelectionSource>) {
+        let selection = self.selection(kind);
+        (selection.serial, selection.source.as_ref())
+    }
+
+    fn remember(&mut self, contents: Rc<SelectionContents>) {
+        self.history.retain(|entry| **entry != *contents);
+        self.history.push_front(contents);
+        self.history.truncate(self.policy.history_size);
+    }
+}
+
+impl<BEnd: Backend + 'static> Navda<BEnd> {
+    ///
+    /// The `kind` selection changed -- smithay already
+    /// knows, so just keep track of it, and pass it on.
+    ///
+    pub fn selection_changed(&mut self, kind: SelectionKind, source: Option<SelectionSource>) {
+        // Its program going away clears the selection -- unless there's a copy to hand out.
+        if source.is_none() && self.take_over_selection(kind) {
+            return;
+        }
+
+        let serial = self.clipboard_state.next_serial;
+        self.clipboard_state.next_serial += 1;
+
+        let selection = self.clipboard_state.selection_mut(kind);
+        if let Some(old) = selection.source.take() {
+            old.cancel();
+        }
+        *selection = Selection {
+            serial,
+            source,
+            ..Default::default()
+        };
+
+        let from_client = matches!(
+            selection.source,
+            Some(SelectionSource::Data(_) | SelectionSource::DataControl(_))
+        );
+        if from_client && kind == SelectionKind::Clipboard && self.clipboard_state.policy.persist {
+            self.copy_selection(kind);
+        }
+
+        self.offer_selection_to_data_control(kind);
+    }
+
+    ///
+    /// Make the `kind` selection one the compositor
+    /// hands out itself -- see [`Self::write_selection`].
+    ///
+    pub fn set_selection(&mut self, kind: SelectionKind, source: Option<SelectionSource>) {
+        // Clearing it isn't possible in our version of smithay,
+        // so offering nothing will have to do.
+        let mime_types = source
+            .as_ref()
+            .map(SelectionSource::mime_types)
+            .unwrap_or_default();
+
+        let dh = &self.display_handle;
+        match kind {
+            SelectionKind::Clipboard => {
+                data_device::set_data_device_selection(dh, &self.seat, mime_types)
+            }
+            SelectionKind::Primary => {
+                primary_selection::set_primary_selection(dh, &self.seat, mime_types)
+            }
+        }
+
+        self.selection_changed(kind, source);
+    }
+
+    ///
+    /// Someone wants the `kind` selection as `mime_type`
+    /// -- have whoever it came from write it into `fd`.
+    ///
+    pub fn write_selection(&mut self, kind: SelectionKind, mime_type: String, fd: OwnedFd) {
+        if let Some(source) = &self.clipboard_state.selection(kind).source {
+            source.send(mime_type, fd);
+        }
+    }
+
+    ///
+    /// Start reading the `kind` selection
+    /// out of its source, in the background.
+    ///
+    fn copy_selection(&mut self, kind: SelectionKind) {
+        let selection = self.clipboard_state.selection(kind);
+        let (serial, source) = match &selection.source {
+            Some(source) => (selection.serial, source.clone()),
+            None => return,
+        };
+
+        let max_size = self.clipboard_state.policy.max_entry_size;
+        let mut pending = 0;
+
+        let mime_types = source.mime_types();
+        for mime_type in mime_types
+            .into_iter()
+            .filter(|m| PERSISTED_MIME_TYPES.contains(&m.as_str()))
+        {
+            let (read, write) = match pipe2(OFlag::O_CLOEXEC) {
+                // Safe, as the pipe's brand new -- nothing else owns its ends.
+                Ok((read, write)) => unsafe {
+                    (File::from_raw_fd(read), OwnedFd::from_raw_fd(write))
+                },
+                Err(err) => {
+                    slog::warn!(
+                        self.log,
+                        "Failed to create a pipe for the clipboard: {}",
+                        err
+                    );
+                    break;
+                }
+            };
+
+            // Only our end -- the other belongs to the client now.
+            if let Err(err) = fcntl(read.as_raw_fd(), FcntlArg::F_SETFL(OFlag::O_NONBLOCK)) {
+                slog::warn!(self.log, "Failed to set up clipboard pipe: {}", err);
+                break;
+            }
+
+            let mut data = Vec::new();
+            let mut chunk = vec![0; READ_CHUNK];
+            let name = mime_type.clone();
+
+            let inserted = self.handle.insert_source(
+                Generic::new(read, Interest::READ, Mode::Level),
+                move |_, file, calloop_data| loop {
+                    let state = &mut calloop_data.state;
+                    if state.clipboard_state.selection(kind).serial != serial {
+                        // Something else got copied in the meantime.
+                        return Ok(PostAction::Remove);
+                    }
+
+                    match file.read(&mut chunk) {
+                        Ok(0) => {
+                            let data = mem::take(&mut data);
+                            state.selection_copied(kind, serial, Some((name.clone(), data)));
+                            return Ok(PostAction::Remove);
+                        }
+                        Ok(n) if data.len() + n > max_size => {
+                            state.selection_copied(kind, serial, None);
+                            return Ok(PostAction::Remove);
+                        }
+                        Ok(n) => data.extend_from_slice(&chunk[..n]),
+                        Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                            return Ok(PostAction::Continue)
+                        }
+                        Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                        Err(_) => {
+                            state.selection_copied(kind, serial, None);
+                            return Ok(PostAction::Remove);
+                        }
+                    }
+                },
+            );
+
+            if let Err(err) = inserted {
+                slog::warn!(self.log, "Failed to read the clipboard: {}", err);
+                break;
+            }
+
+            source.send(mime_type, write);
+            pending += 1;
+        }
+
+        self.clipboard_state.selection_mut(kind).pending = pending;
+    }
+
+    ///
+    /// One of the `kind` selection's MIME types has been read
+    /// (or couldn't be) -- once they all have, keep the copy.
+    ///
+    fn selection_copied(
+        &mut self,
+        kind: SelectionKind,
+        serial: u64,
+        copied: Option<(String, Vec<u8>)>,
+    ) {
+        let max_size = self.clipboard_state.policy.max_entry_size;
+        let selection = self.clipboard_state.selection_mut(kind);
+        if selection.serial != serial {
+            return;
+        }
+
+        selection.copying.0.extend(copied);
+        selection.pending = selection.pending.saturating_sub(1);
+        if selection.pending > 0 {
+            return;
+        }
+
+        let contents = mem::take(&mut selection.copying);
+        if contents.0.is_empty() || contents.size() > max_size {
+            return;
+        }
+
+        let contents = Rc::new(contents);
+        selection.copied = Some(contents.clone());
+        self.clipboard_state.remember(contents);
+    }
+
+    ///
+    /// Take over selections whose programs have gone away
+    /// -- called once per event loop iteration.
+    ///
+    pub fn refresh_clipboard(&mut self) {
+        for kind in [SelectionKind::Clipboard, SelectionKind::Primary] {
+            let selection = self.clipboard_state.selection(kind);
+            if selection
+                .source
+                .as_ref()
+                .map(SelectionSource::alive)
+                .unwrap_or(true)
+            {
+                continue;
+            }
+
+            // Smithay clears selections from clients' own sources by itself.
+            let cleared = matches!(
+                selection.source,
+                Some(SelectionSource::Data(_) | SelectionSource::Primary(_))
+            );
+
+            if self.take_over_selection(kind) {
+                continue;
+            }
+
+            if cleared {
+                self.selection_changed(kind, None);
+            } else {
+                self.set_selection(kind, None);
+            }
+        }
+    }
+
+    ///
+    /// Hand out the copy of the `kind` selection, if its
+    /// source has gone away (and there is a copy).
+    ///
+    fn take_over_selection(&mut self, kind: SelectionKind) -> bool {
+        let selection = self.clipboard_state.selection(kind);
+        let dead = selection
+            .source
+            .as_ref()
+            .map(|source| !source.alive())
+            .unwrap_or(false);
+
+        match selection.copied.clone() {
+            Some(contents) if dead => {
+                self.set_selection(kind, Some(SelectionSource::Persisted(contents)));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    ///
+    /// Put the clipboard's previous entry back on
+    /// it -- the current one goes to the end of the line.
+    ///
+    pub fn cycle_clipboard(&mut self) {
+        let history = &mut self.clipboard_state.history;
+        if history.len() < 2 {
+            return;
+        }
+
+        history.rotate_left(1);
+        let previous = history[0].clone();
+
+        self.set_selection(
+            SelectionKind::Clipboard,
+            Some(SelectionSource::Persisted(previous)),
+        );
+    }
+}
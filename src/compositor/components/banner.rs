@@ -0,0 +1,181 @@
+//!
+//! A banner across the top of every output, saying the config
+//! couldn't be loaded (and why) -- otherwise the only sign is
+//! the compositor quietly ignoring it.
+//!
+
+use std::{cell::RefCell, time::Duration};
+
+use smithay::{
+    backend::renderer::{
+        element::texture::{TextureBuffer, TextureRenderElement},
+        ImportMem, Renderer, Texture,
+    },
+    utils::{user_data::UserDataMap, Logical, Point, Rectangle, Scale, Size, Transform},
+};
+
+use crate::config::config::LoadFailure;
+
+use super::canvas::{Canvas, GLYPH_HEIGHT};
+
+///
+/// How long the banner stays up.
+///
+const SHOWN_FOR: Duration = Duration::from_secs(20);
+
+///
+/// Errors listed before the rest
+/// are left to `--check-config`.
+///
+const MAX_ERRORS: usize = 5;
+
+const LINE_HEIGHT: i32 = GLYPH_HEIGHT + 4;
+const PADDING: i32 = 8;
+
+const BANNER_COLOR: [f32; 4] = [0.84, 0.15, 0.22, 0.92];
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+///
+/// The banner, uploaded for each output size it's been drawn on.
+///
+type Textures<T> = RefCell<Vec<(Size<i32, Logical>, TextureBuffer<T>)>>;
+
+#[derive(Debug)]
+pub struct ConfigBanner {
+    lines: Vec<String>,
+
+    ///
+    /// When it went up, by the compositor's clock.
+    ///
+    shown_at: Duration,
+
+    ///
+    /// [`Textures`] for each kind of renderer it's drawn with.
+    ///
+    textures: UserDataMap,
+}
+
+impl ConfigBanner {
+    pub fn from_failure(failure: &LoadFailure, now: Duration) -> Self {
+        let mut lines: Vec<String> = vec![format!(
+            "Couldn't load {} -- using {} instead.",
+            failure.path, failure.fallback
+        )];
+
+        lines.extend(failure.errors.iter().take(MAX_ERRORS).map(|d| {
+            // Only the first line of longer descriptions.
+            let description: &str = d.description.lines().next().unwrap_or_default();
+
+            // Errors in included files say which.
+            match d.file == failure.path {
+                true => format!("{}:{}: {}", d.line, d.column, description),
+                false => format!("{}:{}:{}: {}", d.file, d.line, d.column, description),
+            }
+        }));
+
+        if failure.errors.len() > MAX_ERRORS {
+            lines.push(format!(
+                "...and {} more.",
+                failure.errors.len() - MAX_ERRORS
+            ));
+        }
+
+        lines.push(format!(
+            "Run `navda --check-config {}` for the details.",
+            failure.path
+        ));
+
+        Self {
+            lines,
+            shown_at: now,
+            textures: UserDataMap::new(),
+        }
+    }
+
+    pub fn expired(&self, now: Duration) -> bool {
+        now.saturating_sub(self.shown_at) >= SHOWN_FOR
+    }
+
+    ///
+    /// Draw the banner for an output of `output_size`.
+    ///
+    fn draw(&self, output_size: Size<i32, Logical>) -> Canvas {
+        let height: i32 = self.lines.len() as i32 * LINE_HEIGHT + 2 * PADDING;
+        let mut canvas = Canvas::new(output_size.w, height);
+
+        canvas.fill_rect(
+            Rectangle::from_loc_and_size((0, 0), (output_size.w, height)),
+            BANNER_COLOR,
+        );
+
+        for (n, line) in self.lines.iter().enumerate() {
+            let rect = Rectangle::from_loc_and_size(
+                (PADDING, PADDING + n as i32 * LINE_HEIGHT),
+                (output_size.w - 2 * PADDING, LINE_HEIGHT),
+            );
+            canvas.draw_text(rect, line, TEXT_COLOR);
+        }
+
+        canvas
+    }
+
+    ///
+    /// Render the banner along the top of
+    /// an output of `output_size`.
+    ///
+    pub fn render_elements<R, C>(
+        &self,
+        renderer: &mut R,
+        output_size: Size<i32, Logical>,
+        scale: Scale<f64>,
+        log: &slog::Logger,
+    ) -> Vec<C>
+    where
+        R: Renderer + ImportMem,
+        <R as Renderer>::TextureId: Texture + Clone + 'static,
+        C: From<TextureRenderElement<<R as Renderer>::TextureId>>,
+    {
+        self.textures
+            .insert_if_missing(Textures::<<R as Renderer>::TextureId>::default);
+        let textures = self
+            .textures
+            .get::<Textures<<R as Renderer>::TextureId>>()
+            .unwrap();
+        let mut textures = textures.borrow_mut();
+
+        // Only uploaded again when there's a new output size.
+        if !textures.iter().any(|(size, _)| *size == output_size) {
+            let canvas = self.draw(output_size);
+
+            match TextureBuffer::from_memory(
+                renderer,
+                canvas.data(),
+                canvas.size(),
+                false,
+                1,
+                Transform::Normal,
+                None,
+            ) {
+                Ok(buffer) => textures.push((output_size, buffer)),
+                Err(err) => {
+                    slog::warn!(log, "Failed to upload the config banner: {:?}", err);
+                    return Vec::new();
+                }
+            }
+        }
+
+        textures
+            .iter()
+            .filter(|(size, _)| *size == output_size)
+            .map(|(_, buffer)| {
+                C::from(TextureRenderElement::from_texture_buffer(
+                    Point::<f64, Logical>::from((0.0, 0.0)).to_physical(scale),
+                    buffer,
+                    None,
+                    None,
+                    None,
+                ))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,328 @@
+//!
+//! CPU-side drawing surface for the bits of UI
+//! the compositor draws itself (switcher, overlays, ...).
+//!
+//! Text uses a fixed-width bitmap font
+//! (DejaVu Sans Mono, pre-rasterized into 8x16 cells
+//! by `resources/dejavu-sans-mono-8x16.rs`).
+//!
+
+use smithay::{
+    backend::renderer::{
+        element::texture::{TextureBuffer, TextureRenderElement},
+        ImportMem, Renderer, Texture,
+    },
+    utils::{Logical, Rectangle, Scale, Transform},
+};
+
+static GLYPHS: &[u8] = include_bytes!("../../../resources/dejavu-sans-mono-8x16.a8");
+
+pub const GLYPH_WIDTH: i32 = 8;
+pub const GLYPH_HEIGHT: i32 = 16;
+
+///
+/// First and last characters in the font --
+/// printable ASCII.
+///
+const FIRST_GLYPH: char = ' ';
+const LAST_GLYPH: char = '~';
+
+///
+/// Coverage values (0-255) of a single glyph,
+/// row by row. Characters outside the font are drawn as `?`.
+///
+fn glyph(c: char) -> &'static [u8] {
+    let c = if (FIRST_GLYPH..=LAST_GLYPH).contains(&c) {
+        c
+    } else {
+        '?'
+    };
+
+    let size = (GLYPH_WIDTH * GLYPH_HEIGHT) as usize;
+    let start = (c as usize - FIRST_GLYPH as usize) * size;
+    &GLYPHS[start..start + size]
+}
+
+///
+/// Where the middle of pixel (`x`, `y`) is,
+/// relative to the middle of `rect`.
+///
+fn pixel_center(rect: Rectangle<i32, Logical>, x: i32, y: i32) -> (f32, f32) {
+    (
+        (x - rect.loc.x) as f32 + 0.5 - rect.size.w as f32 / 2.0,
+        (y - rect.loc.y) as f32 + 0.5 - rect.size.h as f32 / 2.0,
+    )
+}
+
+///
+/// How far (`x`, `y`) is from the edge of a rounded rectangle
+/// centered on the origin -- negative inside it.
+///
+/// Same as `rounded_box` in the effect shaders.
+///
+fn rounded_box(x: f32, y: f32, half_w: f32, half_h: f32, radius: f32) -> f32 {
+    let qx = x.abs() - half_w + radius;
+    let qy = y.abs() - half_h + radius;
+    let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt();
+
+    outside + qx.max(qy).min(0.0) - radius
+}
+
+///
+/// RGBA image, with premultiplied alpha,
+/// ready to be uploaded as a texture.
+///
+/// Drawn into in logical pixels -- each of
+/// which is `scale` by `scale` pixels of the image.
+///
+pub struct Canvas {
+    width: i32,
+    height: i32,
+    scale: i32,
+    data: Vec<u8>,
+}
+
+impl Canvas {
+    ///
+    /// Fully transparent canvas.
+    ///
+    pub fn new(width: i32, height: i32) -> Self {
+        Self::scaled(width, height, 1)
+    }
+
+    ///
+    /// Fully transparent canvas of `width` by `height` logical
+    /// pixels, for a buffer scale of `scale`.
+    ///
+    pub fn scaled(width: i32, height: i32, scale: i32) -> Self {
+        let scale = scale.max(1);
+        let (width, height) = (width.max(0) * scale, height.max(0) * scale);
+
+        Self {
+            width,
+            height,
+            scale,
+            data: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    ///
+    /// In the image's own pixels.
+    ///
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    pub fn scale(&self) -> i32 {
+        self.scale
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    ///
+    /// Blend a (non-premultiplied) `color`
+    /// into a single pixel, with extra `coverage`.
+    ///
+    fn blend(&mut self, x: i32, y: i32, color: [f32; 4], coverage: f32) {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return;
+        }
+
+        let alpha = color[3] * coverage;
+        let i = ((y * self.width + x) * 4) as usize;
+        let pixel = &mut self.data[i..i + 4];
+
+        for (c, channel) in pixel.iter_mut().enumerate() {
+            let src = if c == 3 { alpha } else { color[c] * alpha };
+            let dst = *channel as f32 / 255.0;
+            *channel = ((src + dst * (1.0 - alpha)) * 255.0).round() as u8;
+        }
+    }
+
+    ///
+    /// Blend `color` into every pixel of the logical pixel (`x`, `y`).
+    ///
+    fn blend_scaled(&mut self, x: i32, y: i32, color: [f32; 4], coverage: f32) {
+        for dy in 0..self.scale {
+            for dx in 0..self.scale {
+                self.blend(x * self.scale + dx, y * self.scale + dy, color, coverage);
+            }
+        }
+    }
+
+    ///
+    /// `rect`, in the image's own pixels.
+    ///
+    fn to_image(&self, rect: Rectangle<i32, Logical>) -> Rectangle<i32, Logical> {
+        let s = self.scale;
+        Rectangle::from_loc_and_size(
+            (rect.loc.x * s, rect.loc.y * s),
+            (rect.size.w * s, rect.size.h * s),
+        )
+    }
+
+    ///
+    /// Fill `rect` with `color`.
+    ///
+    pub fn fill_rect(&mut self, rect: Rectangle<i32, Logical>, color: [f32; 4]) {
+        let rect = self.to_image(rect);
+
+        for y in rect.loc.y..rect.loc.y + rect.size.h {
+            for x in rect.loc.x..rect.loc.x + rect.size.w {
+                self.blend(x, y, color, 1.0);
+            }
+        }
+    }
+
+    ///
+    /// Fill `rect` with `color`, rounding its corners
+    /// off by `radius` (anti-aliased).
+    ///
+    pub fn fill_rounded_rect(
+        &mut self,
+        rect: Rectangle<i32, Logical>,
+        radius: f32,
+        color: [f32; 4],
+    ) {
+        let rect = self.to_image(rect);
+        let radius = radius * self.scale as f32;
+        let (half_w, half_h) = (rect.size.w as f32 / 2.0, rect.size.h as f32 / 2.0);
+
+        for y in rect.loc.y..rect.loc.y + rect.size.h {
+            for x in rect.loc.x..rect.loc.x + rect.size.w {
+                let (px, py) = pixel_center(rect, x, y);
+                let d = rounded_box(px, py, half_w, half_h, radius);
+                self.blend(x, y, color, (0.5 - d).clamp(0.0, 1.0));
+            }
+        }
+    }
+
+    ///
+    /// Fill what `rect`'s corners lose when rounded
+    /// off by `radius` with `color` (anti-aliased).
+    ///
+    pub fn fill_corners(&mut self, rect: Rectangle<i32, Logical>, radius: f32, color: [f32; 4]) {
+        let rect = self.to_image(rect);
+        let radius = radius * self.scale as f32;
+        let (half_w, half_h) = (rect.size.w as f32 / 2.0, rect.size.h as f32 / 2.0);
+
+        for y in rect.loc.y..rect.loc.y + rect.size.h {
+            for x in rect.loc.x..rect.loc.x + rect.size.w {
+                let (px, py) = pixel_center(rect, x, y);
+                let d = rounded_box(px, py, half_w, half_h, radius);
+                self.blend(x, y, color, (0.5 + d).clamp(0.0, 1.0));
+            }
+        }
+    }
+
+    ///
+    /// Draw a shadow filling `rect`, around a rounded rectangle
+    /// inset from it by `spread` -- and nothing under that rectangle.
+    ///
+    pub fn draw_shadow(
+        &mut self,
+        rect: Rectangle<i32, Logical>,
+        radius: f32,
+        spread: f32,
+        color: [f32; 4],
+    ) {
+        let rect = self.to_image(rect);
+        let (radius, spread) = (radius * self.scale as f32, spread * self.scale as f32);
+        let half_w = rect.size.w as f32 / 2.0 - spread;
+        let half_h = rect.size.h as f32 / 2.0 - spread;
+
+        for y in rect.loc.y..rect.loc.y + rect.size.h {
+            for x in rect.loc.x..rect.loc.x + rect.size.w {
+                let (px, py) = pixel_center(rect, x, y);
+                let d = rounded_box(px, py, half_w, half_h, radius);
+                if d >= 0.0 {
+                    let strength = (1.0 - d / spread).clamp(0.0, 1.0).powi(2);
+                    self.blend(x, y, color, strength);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Draw a single line of `text` inside `rect`, centered.
+    ///
+    /// Text too wide for `rect` is cut short
+    /// and ends with `...`. The font's a bitmap, so
+    /// on scaled canvases, each of its pixels is too.
+    ///
+    pub fn draw_text(&mut self, rect: Rectangle<i32, Logical>, text: &str, color: [f32; 4]) {
+        let fits = (rect.size.w / GLYPH_WIDTH).max(0) as usize;
+        let chars = text.chars().collect::<Vec<_>>();
+
+        let line = if chars.len() <= fits {
+            chars
+        } else if fits > 3 {
+            chars[..fits - 3]
+                .iter()
+                .chain(['.'; 3].iter())
+                .cloned()
+                .collect()
+        } else {
+            chars[..fits].to_vec()
+        };
+
+        let width = line.len() as i32 * GLYPH_WIDTH;
+        let x = rect.loc.x + (rect.size.w - width) / 2;
+        let y = rect.loc.y + (rect.size.h - GLYPH_HEIGHT) / 2;
+
+        for (n, c) in line.into_iter().enumerate() {
+            let glyph = glyph(c);
+            let gx = x + n as i32 * GLYPH_WIDTH;
+
+            for row in 0..GLYPH_HEIGHT {
+                for col in 0..GLYPH_WIDTH {
+                    let coverage = glyph[(row * GLYPH_WIDTH + col) as usize];
+                    if coverage > 0 {
+                        self.blend_scaled(gx + col, y + row, color, coverage as f32 / 255.0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+///
+/// A `color`ed rectangle as a render element,
+/// stretched from a single pixel.
+///
+pub fn fill_element<R>(
+    renderer: &mut R,
+    rect: Rectangle<i32, Logical>,
+    color: [f32; 4],
+    scale: Scale<f64>,
+    log: &slog::Logger,
+) -> Option<TextureRenderElement<<R as Renderer>::TextureId>>
+where
+    R: Renderer + ImportMem,
+    <R as Renderer>::TextureId: Texture + Clone + 'static,
+{
+    let mut canvas = Canvas::new(1, 1);
+    canvas.fill_rect(Rectangle::from_loc_and_size((0, 0), (1, 1)), color);
+
+    let buffer = TextureBuffer::from_memory(
+        renderer,
+        canvas.data(),
+        canvas.size(),
+        false,
+        1,
+        Transform::Normal,
+        None,
+    )
+    .map_err(|err| slog::warn!(log, "Failed to upload fill: {:?}", err))
+    .ok()?;
+
+    Some(TextureRenderElement::from_texture_buffer(
+        rect.loc.to_f64().to_physical(scale),
+        &buffer,
+        None,
+        None,
+        Some(rect.size),
+    ))
+}
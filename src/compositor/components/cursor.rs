@@ -47,6 +47,14 @@ impl Cursor {
         let size = self.size * scale;
         frame(time.as_millis() as u32, size, &self.icons)
     }
+
+    ///
+    /// The buffer scale to draw `image` with, so it comes out at
+    /// the cursor's size -- `1` for images the theme hasn't got bigger.
+    ///
+    pub fn buffer_scale(&self, image: &Image) -> i32 {
+        (image.size / self.size).max(1) as i32
+    }
 }
 
 fn nearest_images(size: u32, images: &[Image]) -> impl Iterator<Item = &Image> {
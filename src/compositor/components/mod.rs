@@ -1,5 +1,17 @@
 //!
 //! Custom components.
 //!
+mod banner;
+mod canvas;
 mod cursor;
+mod overview;
+mod switcher;
+mod tile_preview;
+mod wallpaper;
+pub use banner::ConfigBanner;
+pub use canvas::{fill_element, Canvas};
 pub use cursor::Cursor;
+pub use overview::{Grid, Overview, OverviewRenderElement};
+pub use switcher::{SwitcherRenderElement, WindowSwitcher};
+pub use tile_preview::tile_preview_elements;
+pub use wallpaper::Wallpaper;
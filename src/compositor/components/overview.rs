@@ -0,0 +1,300 @@
+//!
+//! Overview (exposé) mode -- every window on an output
+//! scaled down into a grid, to pick one from.
+//!
+
+use std::time::{Duration, Instant};
+
+use smithay::{
+    backend::renderer::{
+        element::{
+            texture::TextureRenderElement,
+            utils::{CropRenderElement, RelocateRenderElement, RescaleRenderElement},
+        },
+        ImportAll, ImportMem, Renderer, Texture,
+    },
+    desktop::{
+        space::{constrain_space_element, ConstrainAlign, ConstrainScaleBehavior, SpaceElement},
+        Space,
+    },
+    output::Output,
+    render_elements,
+    utils::{IsAlive, Logical, Point, Rectangle, Scale, Size},
+};
+
+use crate::compositor::{
+    effects::EffectsRenderer,
+    shell::{AvWindow, AvWindowRenderElement},
+};
+
+use super::canvas::fill_element;
+
+const ANIMATION_DURATION: Duration = Duration::from_millis(250);
+
+/// Gap between grid cells, and around the grid.
+const SPACING: i32 = 32;
+
+/// How far the selection highlight sticks out around its window.
+const HIGHLIGHT_PADDING: i32 = 8;
+
+const HIGHLIGHT_COLOR: [f32; 4] = [0.35, 0.55, 0.95, 0.6];
+
+/// Dims the desktop behind the grid, once fully open.
+const BACKDROP_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.4];
+
+render_elements! {
+    pub OverviewRenderElement<R> where
+        R: ImportAll + ImportMem + EffectsRenderer;
+    Fill=TextureRenderElement<<R as Renderer>::TextureId>,
+    Window=CropRenderElement<RelocateRenderElement<RescaleRenderElement<AvWindowRenderElement<R>>>>,
+}
+
+///
+/// Windows on an output laid out in a grid.
+///
+pub struct Grid {
+    pub columns: usize,
+
+    ///
+    /// Each window, and where it goes
+    /// (relative to the output).
+    ///
+    pub tiles: Vec<(AvWindow, Rectangle<i32, Logical>)>,
+}
+
+impl Grid {
+    ///
+    /// Fit every window on `output` into a grid,
+    /// as square as possible.
+    ///
+    /// Windows are only ever shrunk, never blown up.
+    ///
+    pub fn new(space: &Space<AvWindow>, output: &Output) -> Option<Self> {
+        let output_geo = space.output_geometry(output)?;
+        let windows = space
+            .elements_for_output(output)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let count = windows.len().max(1) as i32;
+        let columns = (count as f64).sqrt().ceil() as i32;
+        let rows = (count + columns - 1) / columns;
+
+        let cell: Size<i32, Logical> = (
+            ((output_geo.size.w - SPACING * (columns + 1)) / columns).max(1),
+            ((output_geo.size.h - SPACING * (rows + 1)) / rows).max(1),
+        )
+            .into();
+
+        let tiles = windows
+            .into_iter()
+            .enumerate()
+            .map(|(i, window)| {
+                let (column, row) = (i as i32 % columns, i as i32 / columns);
+                let cell_loc: Point<i32, Logical> = (
+                    SPACING + column * (cell.w + SPACING),
+                    SPACING + row * (cell.h + SPACING),
+                )
+                    .into();
+
+                let geo = window.geometry();
+                let scale = (cell.w as f64 / geo.size.w.max(1) as f64)
+                    .min(cell.h as f64 / geo.size.h.max(1) as f64)
+                    .min(1.0);
+
+                let size: Size<i32, Logical> = (
+                    ((geo.size.w as f64 * scale).round() as i32).max(1),
+                    ((geo.size.h as f64 * scale).round() as i32).max(1),
+                )
+                    .into();
+                let loc = cell_loc + Point::from(((cell.w - size.w) / 2, (cell.h - size.h) / 2));
+
+                (window, Rectangle::from_loc_and_size(loc, size))
+            })
+            .collect();
+
+        Some(Self {
+            columns: columns as usize,
+            tiles,
+        })
+    }
+
+    ///
+    /// The window whose tile contains `point`
+    /// (relative to the output).
+    ///
+    pub fn window_under(&self, point: Point<f64, Logical>) -> Option<&AvWindow> {
+        self.tiles
+            .iter()
+            .find(|(_, tile)| tile.to_f64().contains(point))
+            .map(|(window, _)| window)
+    }
+}
+
+#[derive(Debug)]
+pub struct Overview {
+    ///
+    /// When the overview last started opening or closing.
+    ///
+    toggled_at: Instant,
+    closing: bool,
+    selected: Option<AvWindow>,
+}
+
+impl Default for Overview {
+    fn default() -> Self {
+        Self {
+            toggled_at: Instant::now(),
+            closing: false,
+            selected: None,
+        }
+    }
+}
+
+impl Overview {
+    ///
+    /// How far open the overview is, from 0 to 1.
+    ///
+    pub fn progress(&self) -> f64 {
+        let t =
+            (self.toggled_at.elapsed().as_secs_f64() / ANIMATION_DURATION.as_secs_f64()).min(1.0);
+
+        // Smoothstep -- symmetric, so reversing
+        // half-way through doesn't jump.
+        let eased = t * t * (3.0 - 2.0 * t);
+
+        if self.closing {
+            1.0 - eased
+        } else {
+            eased
+        }
+    }
+
+    pub fn is_closing(&self) -> bool {
+        self.closing
+    }
+
+    ///
+    /// Has the closing animation played out?
+    ///
+    pub fn is_finished(&self) -> bool {
+        self.closing && self.toggled_at.elapsed() >= ANIMATION_DURATION
+    }
+
+    ///
+    /// Start animating the windows back into place.
+    ///
+    pub fn close(&mut self) {
+        if !self.closing {
+            self.reverse();
+        }
+    }
+
+    ///
+    /// Head back into the grid, if on the way out.
+    ///
+    pub fn open(&mut self) {
+        if self.closing {
+            self.reverse();
+        }
+    }
+
+    fn reverse(&mut self) {
+        // Pick up from wherever the current animation got to.
+        let remaining = ANIMATION_DURATION.saturating_sub(self.toggled_at.elapsed());
+        let now = Instant::now();
+        self.toggled_at = now.checked_sub(remaining).unwrap_or(now);
+        self.closing = !self.closing;
+    }
+
+    pub fn selected(&self) -> Option<&AvWindow> {
+        self.selected.as_ref().filter(|w| w.alive())
+    }
+
+    pub fn select(&mut self, window: Option<AvWindow>) {
+        self.selected = window;
+    }
+
+    ///
+    /// Render the windows on `output`, part-way between
+    /// where they really are and their place in the grid.
+    ///
+    pub fn render_elements<R, C>(
+        &self,
+        renderer: &mut R,
+        space: &Space<AvWindow>,
+        output: &Output,
+        scale: Scale<f64>,
+        log: &slog::Logger,
+    ) -> Vec<C>
+    where
+        R: Renderer + ImportAll + ImportMem + EffectsRenderer,
+        <R as Renderer>::TextureId: Texture + Clone + 'static,
+        C: From<OverviewRenderElement<R>>,
+    {
+        let (output_geo, grid) = match space.output_geometry(output).zip(Grid::new(space, output)) {
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+
+        let progress = self.progress();
+        let lerp = |from: i32, to: i32| from + ((to - from) as f64 * progress).round() as i32;
+
+        let mut elements = Vec::new();
+
+        // Topmost windows come first.
+        for (window, tile) in grid.tiles.iter().rev() {
+            let from = Rectangle::from_loc_and_size(
+                space.element_location(window).unwrap_or_default() - output_geo.loc,
+                window.geometry().size,
+            );
+
+            let rect = Rectangle::from_loc_and_size(
+                (lerp(from.loc.x, tile.loc.x), lerp(from.loc.y, tile.loc.y)),
+                (
+                    lerp(from.size.w, tile.size.w),
+                    lerp(from.size.h, tile.size.h),
+                ),
+            );
+
+            elements.extend(
+                constrain_space_element::<_, _, OverviewRenderElement<R>>(
+                    renderer,
+                    window,
+                    rect.loc,
+                    scale,
+                    rect,
+                    ConstrainScaleBehavior::Fit,
+                    ConstrainAlign::CENTER,
+                )
+                .map(C::from),
+            );
+
+            if self.selected() == Some(window) {
+                let mut highlight = rect;
+                highlight.loc -= (HIGHLIGHT_PADDING, HIGHLIGHT_PADDING).into();
+                highlight.size += (2 * HIGHLIGHT_PADDING, 2 * HIGHLIGHT_PADDING).into();
+
+                elements.extend(
+                    fill_element(renderer, highlight, HIGHLIGHT_COLOR, scale, log)
+                        .map(|e| C::from(OverviewRenderElement::from(e))),
+                );
+            }
+        }
+
+        let mut backdrop = BACKDROP_COLOR;
+        backdrop[3] *= progress as f32;
+        elements.extend(
+            fill_element(
+                renderer,
+                Rectangle::from_loc_and_size((0, 0), output_geo.size),
+                backdrop,
+                scale,
+                log,
+            )
+            .map(|e| C::from(OverviewRenderElement::from(e))),
+        );
+
+        elements
+    }
+}
@@ -0,0 +1,320 @@
+//!
+//! Alt-Tab style window switcher.
+//!
+//! Holding the modifier and tapping Tab steps through windows
+//! in most-recently-used order; letting go of the modifier
+//! focuses the selected window.
+//!
+
+use std::{cell::RefCell, ops::Range};
+
+use smithay::{
+    backend::renderer::{
+        element::{
+            texture::{TextureBuffer, TextureRenderElement},
+            utils::{CropRenderElement, RelocateRenderElement, RescaleRenderElement},
+        },
+        ImportAll, ImportMem, Renderer, Texture,
+    },
+    desktop::space::{constrain_space_element, ConstrainAlign, ConstrainScaleBehavior},
+    render_elements,
+    utils::{user_data::UserDataMap, IsAlive, Logical, Rectangle, Scale, Size, Transform},
+};
+
+use crate::compositor::{
+    effects::EffectsRenderer,
+    shell::{AvWindow, AvWindowRenderElement},
+};
+
+use super::canvas::{Canvas, GLYPH_HEIGHT};
+
+/// Widest a tile gets, when there's room.
+const TILE_WIDTH: i32 = 192;
+
+/// Narrowest a tile gets before windows start scrolling out of view.
+const MIN_TILE_WIDTH: i32 = 96;
+
+const THUMBNAIL_HEIGHT: i32 = 120;
+const LABEL_HEIGHT: i32 = GLYPH_HEIGHT + 8;
+const SPACING: i32 = 16;
+
+/// How far the selection highlight sticks out around its tile.
+const HIGHLIGHT_PADDING: i32 = 6;
+
+const PANEL_COLOR: [f32; 4] = [0.1, 0.1, 0.12, 0.85];
+const HIGHLIGHT_COLOR: [f32; 4] = [0.35, 0.55, 0.95, 0.6];
+const LABEL_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+render_elements! {
+    pub SwitcherRenderElement<R> where
+        R: ImportAll + ImportMem + EffectsRenderer;
+    Panel=TextureRenderElement<<R as Renderer>::TextureId>,
+    Thumbnail=CropRenderElement<RelocateRenderElement<RescaleRenderElement<AvWindowRenderElement<R>>>>,
+}
+
+///
+/// Where everything goes on a given output.
+///
+struct Layout {
+    ///
+    /// The whole switcher, relative to the output.
+    ///
+    panel: Rectangle<i32, Logical>,
+    tile_width: i32,
+
+    ///
+    /// Which windows are shown -- the rest
+    /// don't fit, and scroll in as they're selected.
+    ///
+    visible: Range<usize>,
+}
+
+impl Layout {
+    ///
+    /// The `n`th visible tile (thumbnail and label),
+    /// relative to the panel.
+    ///
+    fn tile(&self, n: usize) -> Rectangle<i32, Logical> {
+        Rectangle::from_loc_and_size(
+            (SPACING + n as i32 * (self.tile_width + SPACING), SPACING),
+            (self.tile_width, THUMBNAIL_HEIGHT + LABEL_HEIGHT),
+        )
+    }
+}
+
+///
+/// What the panel shows on an output -- the
+/// panel's uploaded again when that changes.
+///
+#[derive(Debug, PartialEq)]
+struct PanelContents {
+    output_size: Size<i32, Logical>,
+    scale: i32,
+    selected: usize,
+    labels: Vec<String>,
+}
+
+///
+/// The panel, uploaded for each output size
+/// (and scale) it's shown on.
+///
+type Panels<T> = RefCell<Vec<(PanelContents, TextureBuffer<T>)>>;
+
+#[derive(Debug)]
+pub struct WindowSwitcher {
+    windows: Vec<AvWindow>,
+    selected: usize,
+
+    ///
+    /// [`Panels`] for each kind of renderer it's drawn with.
+    ///
+    panels: UserDataMap,
+}
+
+impl WindowSwitcher {
+    ///
+    /// Open the switcher over `windows` (most recent first),
+    /// with the selection already one step away from the current window.
+    ///
+    /// There's nothing to switch between without any windows.
+    ///
+    pub fn new(windows: Vec<AvWindow>, backwards: bool) -> Option<Self> {
+        if windows.is_empty() {
+            return None;
+        }
+
+        let mut switcher = Self {
+            windows,
+            selected: 0,
+            panels: UserDataMap::new(),
+        };
+        switcher.step(backwards);
+
+        Some(switcher)
+    }
+
+    ///
+    /// Move the selection along by one, wrapping around.
+    ///
+    pub fn step(&mut self, backwards: bool) {
+        let len = self.windows.len();
+        self.selected = if backwards {
+            (self.selected + len - 1) % len
+        } else {
+            (self.selected + 1) % len
+        };
+    }
+
+    pub fn selected(&self) -> Option<&AvWindow> {
+        self.windows.get(self.selected).filter(|w| w.alive())
+    }
+
+    fn label(window: &AvWindow) -> String {
+        window
+            .title()
+            .or_else(|| window.app_id())
+            .unwrap_or_else(|| "Untitled".into())
+    }
+
+    fn layout(&self, output_size: Size<i32, Logical>) -> Layout {
+        let count = self.windows.len() as i32;
+        let available = output_size.w - 3 * SPACING;
+
+        let tile_width = (available / count - SPACING).clamp(MIN_TILE_WIDTH, TILE_WIDTH);
+        let per_row = (available / (tile_width + SPACING)).clamp(1, count);
+
+        // Keep the selection in view.
+        let start = (self.selected as i32 - per_row + 1).max(0) as usize;
+        let visible = start..start + per_row as usize;
+
+        let size: Size<i32, Logical> = (
+            per_row * (tile_width + SPACING) + SPACING,
+            THUMBNAIL_HEIGHT + LABEL_HEIGHT + 2 * SPACING,
+        )
+            .into();
+
+        Layout {
+            panel: Rectangle::from_loc_and_size(
+                ((output_size.w - size.w) / 2, (output_size.h - size.h) / 2),
+                size,
+            ),
+            tile_width,
+            visible,
+        }
+    }
+
+    ///
+    /// Background, selection highlight and window labels.
+    ///
+    fn draw_panel(&self, layout: &Layout, scale: i32) -> Canvas {
+        let mut canvas = Canvas::scaled(layout.panel.size.w, layout.panel.size.h, scale);
+
+        canvas.fill_rect(
+            Rectangle::from_loc_and_size((0, 0), layout.panel.size),
+            PANEL_COLOR,
+        );
+
+        for (n, i) in layout.visible.clone().enumerate() {
+            let tile = layout.tile(n);
+
+            if i == self.selected {
+                let mut highlight = tile;
+                highlight.loc -= (HIGHLIGHT_PADDING, HIGHLIGHT_PADDING).into();
+                highlight.size += (2 * HIGHLIGHT_PADDING, 2 * HIGHLIGHT_PADDING).into();
+                canvas.fill_rect(highlight, HIGHLIGHT_COLOR);
+            }
+
+            let label = Rectangle::from_loc_and_size(
+                (tile.loc.x, tile.loc.y + THUMBNAIL_HEIGHT),
+                (tile.size.w, LABEL_HEIGHT),
+            );
+            canvas.draw_text(label, &Self::label(&self.windows[i]), LABEL_COLOR);
+        }
+
+        canvas
+    }
+
+    ///
+    /// Render the switcher in the middle of
+    /// an output of `output_size`.
+    ///
+    pub fn render_elements<R, C>(
+        &self,
+        renderer: &mut R,
+        output_size: Size<i32, Logical>,
+        scale: Scale<f64>,
+        log: &slog::Logger,
+    ) -> Vec<C>
+    where
+        R: Renderer + ImportAll + ImportMem + EffectsRenderer,
+        <R as Renderer>::TextureId: Texture + Clone + 'static,
+        C: From<SwitcherRenderElement<R>>,
+    {
+        let layout = self.layout(output_size);
+        let mut elements = Vec::new();
+
+        // Thumbnails go in front of the panel.
+        for (n, i) in layout.visible.clone().enumerate() {
+            let window = &self.windows[i];
+            if !window.alive() {
+                continue;
+            }
+
+            let tile = layout.tile(n);
+            let thumbnail = Rectangle::from_loc_and_size(
+                layout.panel.loc + tile.loc,
+                (tile.size.w, THUMBNAIL_HEIGHT),
+            );
+
+            elements.extend(
+                constrain_space_element::<_, _, SwitcherRenderElement<R>>(
+                    renderer,
+                    window,
+                    thumbnail.loc,
+                    scale,
+                    thumbnail,
+                    ConstrainScaleBehavior::Fit,
+                    ConstrainAlign::CENTER,
+                )
+                .map(C::from),
+            );
+        }
+
+        // Drawn at (at least) the output's scale, so it stays sharp.
+        let buffer_scale = scale.x.ceil() as i32;
+
+        let contents = PanelContents {
+            output_size,
+            scale: buffer_scale,
+            selected: self.selected,
+            labels: layout
+                .visible
+                .clone()
+                .map(|i| Self::label(&self.windows[i]))
+                .collect(),
+        };
+
+        self.panels
+            .insert_if_missing(Panels::<<R as Renderer>::TextureId>::default);
+        let mut panels = self
+            .panels
+            .get::<Panels<<R as Renderer>::TextureId>>()
+            .unwrap()
+            .borrow_mut();
+
+        if !panels.iter().any(|(c, _)| *c == contents) {
+            panels.retain(|(c, _)| c.output_size != output_size || c.scale != buffer_scale);
+
+            let canvas = self.draw_panel(&layout, buffer_scale);
+            match TextureBuffer::from_memory(
+                renderer,
+                canvas.data(),
+                canvas.size(),
+                false,
+                canvas.scale(),
+                Transform::Normal,
+                None,
+            ) {
+                Ok(buffer) => panels.push((contents, buffer)),
+                Err(err) => slog::warn!(log, "Failed to upload window switcher: {:?}", err),
+            }
+        }
+
+        if let Some((_, buffer)) = panels
+            .iter()
+            .find(|(c, _)| c.output_size == output_size && c.scale == buffer_scale)
+        {
+            elements.push(C::from(SwitcherRenderElement::from(
+                TextureRenderElement::from_texture_buffer(
+                    layout.panel.loc.to_f64().to_physical(scale),
+                    buffer,
+                    None,
+                    None,
+                    None,
+                ),
+            )));
+        }
+
+        elements
+    }
+}
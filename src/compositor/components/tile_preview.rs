@@ -0,0 +1,48 @@
+//!
+//! Shows where a dragged window will be tiled
+//! to, if it's let go of right now.
+//!
+
+use smithay::{
+    backend::renderer::{element::texture::TextureRenderElement, ImportMem, Renderer, Texture},
+    utils::{Logical, Rectangle, Scale},
+};
+
+use super::canvas::fill_element;
+
+const FILL_COLOR: [f32; 4] = [0.35, 0.55, 0.95, 0.25];
+const BORDER_COLOR: [f32; 4] = [0.35, 0.55, 0.95, 0.8];
+const BORDER_WIDTH: i32 = 2;
+
+///
+/// A translucent rectangle with a border,
+/// covering `rect` (relative to the output).
+///
+pub fn tile_preview_elements<R, C>(
+    renderer: &mut R,
+    rect: Rectangle<i32, Logical>,
+    scale: Scale<f64>,
+    log: &slog::Logger,
+) -> Vec<C>
+where
+    R: Renderer + ImportMem,
+    <R as Renderer>::TextureId: Texture + Clone + 'static,
+    C: From<TextureRenderElement<<R as Renderer>::TextureId>>,
+{
+    let (x, y, w, h) = (rect.loc.x, rect.loc.y, rect.size.w, rect.size.h);
+
+    let borders = [
+        Rectangle::from_loc_and_size((x, y), (w, BORDER_WIDTH)),
+        Rectangle::from_loc_and_size((x, y + h - BORDER_WIDTH), (w, BORDER_WIDTH)),
+        Rectangle::from_loc_and_size((x, y), (BORDER_WIDTH, h)),
+        Rectangle::from_loc_and_size((x + w - BORDER_WIDTH, y), (BORDER_WIDTH, h)),
+    ];
+
+    borders
+        .into_iter()
+        .map(|border| (border, BORDER_COLOR))
+        .chain(std::iter::once((rect, FILL_COLOR)))
+        .filter_map(|(rect, color)| fill_element(renderer, rect, color, scale, log))
+        .map(C::from)
+        .collect()
+}
@@ -0,0 +1,315 @@
+//!
+//! The compositor's own wallpaper -- a solid color, with an image
+//! over it if there is one, drawn beneath everything else.
+//!
+//! Layer-shell backgrounds (from `swaybg` and the like)
+//! are drawn over it, and take its place.
+//!
+
+use std::{cell::RefCell, path::PathBuf};
+
+use smithay::{
+    backend::renderer::{
+        element::texture::{TextureBuffer, TextureRenderElement},
+        ImportMem, Renderer, Texture,
+    },
+    utils::{user_data::UserDataMap, Logical, Rectangle, Scale, Size, Transform},
+};
+
+use crate::config::sections::background::Background;
+
+///
+/// How the wallpaper image covers an output.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WallpaperMode {
+    ///
+    /// Scaled to cover the whole output, cropping
+    /// whatever sticks out.
+    ///
+    Fill,
+
+    ///
+    /// Scaled to fit inside the output, with the
+    /// color showing around it.
+    ///
+    Fit,
+
+    ///
+    /// At its own size, in the middle of the output.
+    ///
+    Center,
+
+    ///
+    /// At its own size, repeated from the top-left corner.
+    ///
+    Tile,
+}
+
+impl<'a> TryFrom<&'a str> for WallpaperMode {
+    type Error = String;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        match value {
+            "fill" => Ok(Self::Fill),
+            "fit" => Ok(Self::Fit),
+            "center" => Ok(Self::Center),
+            "tile" => Ok(Self::Tile),
+            _ => Err(value.to_string()),
+        }
+    }
+}
+
+impl WallpaperMode {
+    ///
+    /// Where an `image`-sized image goes on an `output`-sized output
+    /// -- everything but `Tile`, which has to repeat it.
+    ///
+    fn place(
+        &self,
+        image: Size<i32, Logical>,
+        output: Size<i32, Logical>,
+    ) -> Rectangle<i32, Logical> {
+        let (iw, ih) = (image.w.max(1) as f64, image.h.max(1) as f64);
+        let (ow, oh) = (output.w as f64, output.h as f64);
+
+        let scale = match self {
+            Self::Fill => (ow / iw).max(oh / ih),
+            Self::Fit => (ow / iw).min(oh / ih),
+            Self::Center | Self::Tile => 1.0,
+        };
+
+        let size = Size::from(((iw * scale).round() as i32, (ih * scale).round() as i32));
+        let loc = ((output.w - size.w) / 2, (output.h - size.h) / 2);
+
+        Rectangle::from_loc_and_size(loc, size)
+    }
+}
+
+///
+/// A decoded image -- RGBA, with premultiplied alpha.
+///
+#[derive(Debug)]
+struct Image {
+    size: Size<i32, Logical>,
+    data: Vec<u8>,
+}
+
+impl Image {
+    fn open(path: &str) -> Result<Self, image::ImageError> {
+        // `~/...` is the only bit of shell expansion worth doing.
+        let path = match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+            (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+            _ => PathBuf::from(path),
+        };
+
+        let image = image::open(path)?.into_rgba8();
+        let size = Size::from((image.width() as i32, image.height() as i32));
+
+        let mut data = image.into_raw();
+        for pixel in data.chunks_exact_mut(4) {
+            let alpha = pixel[3] as u32;
+            for channel in &mut pixel[..3] {
+                *channel = (*channel as u32 * alpha / 255) as u8;
+            }
+        }
+
+        Ok(Self { size, data })
+    }
+
+    ///
+    /// This image, repeated across `size`.
+    ///
+    fn tiled(&self, size: Size<i32, Logical>) -> Vec<u8> {
+        let (iw, ih) = (self.size.w as usize, self.size.h as usize);
+        let (w, h) = (size.w.max(0) as usize, size.h.max(0) as usize);
+
+        if iw == 0 || ih == 0 {
+            return vec![0; w * h * 4];
+        }
+
+        let mut data = Vec::with_capacity(w * h * 4);
+        for y in 0..h {
+            let row = &self.data[(y % ih) * iw * 4..][..iw * 4];
+            for x in 0..w {
+                data.extend_from_slice(&row[(x % iw) * 4..][..4]);
+            }
+        }
+
+        data
+    }
+}
+
+///
+/// The wallpaper, uploaded to a renderer.
+///
+#[derive(Debug)]
+struct Textures<T> {
+    color: TextureBuffer<T>,
+    image: Option<TextureBuffer<T>>,
+
+    ///
+    /// The image tiled across each output size it's been
+    /// drawn on -- a single texture keeps damage tracking simple.
+    ///
+    tiled: Vec<(Size<i32, Logical>, TextureBuffer<T>)>,
+}
+
+#[derive(Debug)]
+pub struct Wallpaper {
+    color: [f32; 4],
+    image: Option<Image>,
+    mode: WallpaperMode,
+
+    ///
+    /// [`Textures`] for each kind of renderer it's drawn with
+    /// -- uploaded the first time they're needed.
+    ///
+    textures: UserDataMap,
+}
+
+fn upload<R>(
+    renderer: &mut R,
+    data: &[u8],
+    size: Size<i32, Logical>,
+    log: &slog::Logger,
+) -> Option<TextureBuffer<<R as Renderer>::TextureId>>
+where
+    R: Renderer + ImportMem,
+    <R as Renderer>::TextureId: Texture + Clone + 'static,
+{
+    TextureBuffer::from_memory(
+        renderer,
+        data,
+        (size.w, size.h),
+        false,
+        1,
+        Transform::Normal,
+        None,
+    )
+    .map_err(|err| slog::warn!(log, "Failed to upload wallpaper: {:?}", err))
+    .ok()
+}
+
+impl Wallpaper {
+    ///
+    /// Loads the image (if there is one) straight away -- a
+    /// missing or broken one leaves just the color (with a warning).
+    ///
+    pub fn from_config(background: &Background, log: &slog::Logger) -> Self {
+        let color = background.color.0;
+
+        let mode = WallpaperMode::try_from(background.mode.as_str()).unwrap_or_else(|v| {
+            slog::warn!(log, "Unknown wallpaper mode `{}`, using `fill`.", v);
+            WallpaperMode::Fill
+        });
+
+        let image = match background.image.as_str() {
+            "" => None,
+            path => Image::open(path)
+                .map_err(|err| {
+                    slog::warn!(log, "Failed to load wallpaper `{}`: {}", path, err);
+                })
+                .ok(),
+        };
+
+        Self {
+            color,
+            image,
+            mode,
+            textures: UserDataMap::new(),
+        }
+    }
+
+    fn textures<R>(
+        &self,
+        renderer: &mut R,
+        log: &slog::Logger,
+    ) -> &RefCell<Option<Textures<<R as Renderer>::TextureId>>>
+    where
+        R: Renderer + ImportMem,
+        <R as Renderer>::TextureId: Texture + Clone + 'static,
+    {
+        self.textures.insert_if_missing(|| {
+            // Nothing to show (or retry) if even the color won't upload.
+            let [r, g, b, a] = self.color;
+            let pixel = [r * a, g * a, b * a, a].map(|c| (c * 255.0).round() as u8);
+
+            let textures =
+                upload(renderer, &pixel, Size::from((1, 1)), log).map(|color| Textures {
+                    color,
+                    image: self
+                        .image
+                        .as_ref()
+                        .and_then(|image| upload(renderer, &image.data, image.size, log)),
+                    tiled: Vec::new(),
+                });
+
+            RefCell::new(textures)
+        });
+
+        self.textures.get().unwrap()
+    }
+
+    ///
+    /// The wallpaper for an `output_size`d output, topmost first.
+    ///
+    pub fn render_elements<R, C>(
+        &self,
+        renderer: &mut R,
+        output_size: Size<i32, Logical>,
+        scale: Scale<f64>,
+        log: &slog::Logger,
+    ) -> Vec<C>
+    where
+        R: Renderer + ImportMem,
+        <R as Renderer>::TextureId: Texture + Clone + 'static,
+        C: From<TextureRenderElement<<R as Renderer>::TextureId>>,
+    {
+        let mut textures = self.textures(renderer, log).borrow_mut();
+        let textures = match textures.as_mut() {
+            Some(textures) => textures,
+            None => return Vec::new(),
+        };
+
+        let element = |buffer: &TextureBuffer<_>, rect: Rectangle<i32, Logical>| {
+            C::from(TextureRenderElement::from_texture_buffer(
+                rect.loc.to_f64().to_physical(scale),
+                buffer,
+                None,
+                None,
+                Some(rect.size),
+            ))
+        };
+
+        let output = Rectangle::from_loc_and_size((0, 0), output_size);
+        let mut elements = Vec::new();
+
+        match (&self.image, &textures.image, self.mode) {
+            (Some(image), Some(_), WallpaperMode::Tile) => {
+                if !textures.tiled.iter().any(|(size, _)| *size == output_size) {
+                    if let Some(tiled) =
+                        upload(renderer, &image.tiled(output_size), output_size, log)
+                    {
+                        textures.tiled.push((output_size, tiled));
+                    }
+                }
+
+                if let Some((_, tiled)) =
+                    textures.tiled.iter().find(|(size, _)| *size == output_size)
+                {
+                    elements.push(element(tiled, output));
+                }
+            }
+            (Some(image), Some(buffer), mode) => {
+                elements.push(element(buffer, mode.place(image.size, output_size)));
+            }
+            _ => {}
+        }
+
+        // Under the image -- and around it, if it doesn't cover the output.
+        elements.push(element(&textures.color, output));
+
+        elements
+    }
+}
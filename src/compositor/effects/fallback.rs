@@ -0,0 +1,110 @@
+//!
+//! Effects drawn on the CPU, into textures -- for renderers
+//! that can't run the effect shaders.
+//!
+//! Drawn at logical size (and stretched), so they're
+//! a little blurry on scaled outputs.
+//!
+
+use std::cell::RefCell;
+
+use smithay::{
+    backend::renderer::{
+        element::texture::{TextureBuffer, TextureRenderElement},
+        Renderer, Texture,
+    },
+    utils::{Logical, Rectangle, Scale, Size, Transform},
+};
+
+use crate::compositor::{components::Canvas, shell::AvWindow};
+
+use super::{Effect, EffectsRenderer};
+
+///
+/// The textures last drawn for a window, one per kind of effect.
+///
+#[derive(Debug)]
+struct TextureCache<T>(RefCell<Vec<(Effect, Size<i32, Logical>, TextureBuffer<T>)>>);
+
+fn draw(effect: &Effect, size: Size<i32, Logical>) -> Canvas {
+    let mut canvas = Canvas::new(size.w, size.h);
+    let rect = Rectangle::from_loc_and_size((0, 0), size);
+
+    match *effect {
+        Effect::Shadow {
+            radius,
+            spread,
+            color,
+        } => canvas.draw_shadow(rect, radius, spread, color),
+        Effect::RoundedRect { radius, color } => canvas.fill_rounded_rect(rect, radius, color),
+        Effect::CornerMask { radius } => canvas.fill_corners(rect, radius, [0.0, 0.0, 0.0, 1.0]),
+    }
+
+    canvas
+}
+
+///
+/// `effect` over `rect`, only redrawn if it
+/// (or its size) changed since it was last drawn for `window`.
+///
+pub(super) fn element<R>(
+    renderer: &mut R,
+    window: &AvWindow,
+    effect: Effect,
+    rect: Rectangle<i32, Logical>,
+    opacity: f32,
+    scale: Scale<f64>,
+    log: &slog::Logger,
+) -> Option<TextureRenderElement<<R as Renderer>::TextureId>>
+where
+    R: EffectsRenderer,
+    <R as Renderer>::TextureId: Texture + Clone + 'static,
+{
+    window
+        .user_data()
+        .insert_if_missing(|| TextureCache::<R::TextureId>(RefCell::new(Vec::new())));
+    let mut cache = window
+        .user_data()
+        .get::<TextureCache<R::TextureId>>()
+        .unwrap()
+        .0
+        .borrow_mut();
+
+    let index = cache
+        .iter()
+        .position(|(cached, _, _)| cached.same_kind(&effect));
+
+    let buffer = match index {
+        Some(i) if cache[i].0 == effect && cache[i].1 == rect.size => cache[i].2.clone(),
+        _ => {
+            let canvas = draw(&effect, rect.size);
+            let buffer = TextureBuffer::from_memory(
+                renderer,
+                canvas.data(),
+                canvas.size(),
+                false,
+                1,
+                Transform::Normal,
+                None,
+            )
+            .map_err(|err| slog::warn!(log, "Failed to upload effect: {:?}", err))
+            .ok()?;
+
+            let entry = (effect, rect.size, buffer.clone());
+            match index {
+                Some(i) => cache[i] = entry,
+                None => cache.push(entry),
+            }
+
+            buffer
+        }
+    };
+
+    Some(TextureRenderElement::from_texture_buffer(
+        rect.loc.to_f64().to_physical(scale),
+        &buffer,
+        Some(opacity),
+        None,
+        Some(rect.size),
+    ))
+}
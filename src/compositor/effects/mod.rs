@@ -0,0 +1,382 @@
+//!
+//! Window effects -- rounded corners, drop shadows and translucency.
+//!
+//! Drawn with GLES pixel shaders where the renderer can run them
+//! (see [`EffectsRenderer`]), and with textures drawn on the CPU
+//! where it can't -- or when the `effects` config section says not to.
+//!
+//! The windows themselves are drawn into textures first (see [`offscreen`]),
+//! with their corners cleared by a [`Effect::CornerMask`] drawn over them,
+//! and then onto the output translucent. Renderers that can't do that
+//! draw windows as they are -- square and opaque, without shadows.
+//!
+
+mod fallback;
+pub mod offscreen;
+mod shaders;
+
+pub use offscreen::OffscreenElement;
+pub use shaders::Shaders;
+
+use std::mem::discriminant;
+
+use smithay::{
+    backend::renderer::{
+        element::{texture::TextureRenderElement, CommitCounter, Element, Id, RenderElement},
+        gles2::{element::PixelShaderElement, Gles2Renderer, Uniform},
+        ImportMem, Renderer, Texture,
+    },
+    desktop::Space,
+    output::Output,
+    utils::{Buffer, Logical, Physical, Point, Rectangle, Scale, Transform},
+};
+
+use crate::config::sections::effects::Effects;
+
+use super::shell::{AppliedRules, AvWindow};
+
+///
+/// A window's drop shadow.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    ///
+    /// How far (in logical pixels) it
+    /// spreads out from under the window.
+    ///
+    pub size: i32,
+
+    ///
+    /// Not premultiplied.
+    ///
+    pub color: [f32; 4],
+}
+
+///
+/// The user's effect preferences, read
+/// from the `effects` config section.
+///
+#[derive(Debug, Clone)]
+pub struct EffectsPolicy {
+    pub corner_radius: i32,
+    pub shadow: Option<Shadow>,
+    pub use_shaders: bool,
+}
+
+impl Default for EffectsPolicy {
+    fn default() -> Self {
+        Self {
+            corner_radius: 8,
+            shadow: Some(Shadow {
+                size: 24,
+                color: [0.0, 0.0, 0.0, 0.4],
+            }),
+            use_shaders: true,
+        }
+    }
+}
+
+impl EffectsPolicy {
+    pub fn from_config(effects: &Effects, _log: &slog::Logger) -> Self {
+        let color = effects.shadowColor.0;
+        let size = effects.shadowSize.clamp(0, i32::MAX as i64) as i32;
+
+        Self {
+            corner_radius: effects.cornerRadius.clamp(0, i32::MAX as i64) as i32,
+            shadow: (effects.shadows && size > 0).then_some(Shadow { size, color }),
+            use_shaders: effects.useShaders,
+        }
+    }
+
+    ///
+    /// The effects for a window, with its window rules applied.
+    ///
+    pub fn for_window(&self, rules: &AppliedRules) -> WindowEffects {
+        WindowEffects {
+            corner_radius: self.corner_radius,
+            shadow: self.shadow,
+            opacity: rules.opacity.unwrap_or(1.0),
+            use_shaders: self.use_shaders,
+        }
+    }
+}
+
+///
+/// How a single window is drawn -- kept around
+/// in its user data (see [`AvWindow::effects`]).
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowEffects {
+    pub corner_radius: i32,
+    pub shadow: Option<Shadow>,
+    pub opacity: f32,
+    pub use_shaders: bool,
+}
+
+///
+/// No effects at all -- for windows that
+/// haven't had their window rules applied yet.
+///
+impl Default for WindowEffects {
+    fn default() -> Self {
+        Self {
+            corner_radius: 0,
+            shadow: None,
+            opacity: 1.0,
+            use_shaders: true,
+        }
+    }
+}
+
+///
+/// Something to draw, over a whole element.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    ///
+    /// A shadow around a rounded rectangle,
+    /// inset from the element's edges by `spread`.
+    ///
+    Shadow {
+        radius: f32,
+        spread: f32,
+        color: [f32; 4],
+    },
+
+    ///
+    /// A rounded rectangle filling the element.
+    ///
+    RoundedRect { radius: f32, color: [f32; 4] },
+
+    ///
+    /// What's outside a rounded rectangle filling the element
+    /// -- drawn over a window to clear its corners.
+    ///
+    CornerMask { radius: f32 },
+}
+
+impl Effect {
+    ///
+    /// The same effect, measured in physical pixels.
+    ///
+    fn to_physical(self, scale: f64) -> Self {
+        let scale = scale as f32;
+
+        match self {
+            Self::Shadow {
+                radius,
+                spread,
+                color,
+            } => Self::Shadow {
+                radius: radius * scale,
+                spread: spread * scale,
+                color,
+            },
+            Self::RoundedRect { radius, color } => Self::RoundedRect {
+                radius: radius * scale,
+                color,
+            },
+            Self::CornerMask { radius } => Self::CornerMask {
+                radius: radius * scale,
+            },
+        }
+    }
+
+    ///
+    /// Whether `other` is drawn by the same shader
+    /// -- that is, can be updated into this one.
+    ///
+    fn same_kind(&self, other: &Self) -> bool {
+        discriminant(self) == discriminant(other)
+    }
+
+    ///
+    /// What to hand the effect's shader.
+    ///
+    fn uniforms(&self, opacity: f32) -> Vec<Uniform<'static>> {
+        match *self {
+            Self::Shadow {
+                radius,
+                spread,
+                color,
+            } => vec![
+                Uniform::new("color", color),
+                Uniform::new("radius", radius),
+                Uniform::new("spread", spread),
+                Uniform::new("opacity", opacity),
+            ],
+            Self::RoundedRect { radius, color } => vec![
+                Uniform::new("color", color),
+                Uniform::new("radius", radius),
+                Uniform::new("opacity", opacity),
+            ],
+            Self::CornerMask { radius } => vec![Uniform::new("radius", radius)],
+        }
+    }
+}
+
+///
+/// A renderer that may (or may not) be able to draw effects with shaders.
+///
+pub trait EffectsRenderer: Renderer + ImportMem {
+    ///
+    /// The effect shaders, compiled for this renderer
+    /// -- `None` if it can't run them.
+    ///
+    fn effect_shaders(&mut self, _log: &slog::Logger) -> Option<Shaders> {
+        None
+    }
+
+    ///
+    /// Draw a shader element, made with
+    /// the shaders from [`Self::effect_shaders`].
+    ///
+    fn draw_shader<'frame>(
+        frame: &mut <Self as Renderer>::Frame<'frame>,
+        element: &PixelShaderElement,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+        log: &slog::Logger,
+    ) -> Result<(), <Self as Renderer>::Error>;
+
+    ///
+    /// The renderer to draw windows into textures with (see the `offscreen` module)
+    /// -- `None` if there isn't one, and windows are drawn as they are.
+    ///
+    fn offscreen_renderer(&mut self) -> Option<&mut Gles2Renderer> {
+        None
+    }
+
+    ///
+    /// Draw a window's texture, made with [`Self::offscreen_renderer`].
+    ///
+    fn draw_offscreen<'frame>(
+        frame: &mut <Self as Renderer>::Frame<'frame>,
+        element: &OffscreenElement,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+        log: &slog::Logger,
+    ) -> Result<(), <Self as Renderer>::Error>;
+}
+
+///
+/// Draw the windows on `output` into their textures, if `renderer` can
+/// -- before the output's framebuffer is bound, as that unbinds it.
+///
+pub fn prepare_output<R: EffectsRenderer>(
+    renderer: &mut R,
+    space: &Space<AvWindow>,
+    output: &Output,
+    log: &slog::Logger,
+) {
+    let Some(gles) = renderer.offscreen_renderer() else {
+        return;
+    };
+
+    let scale = Scale::from(output.current_scale().fractional_scale());
+
+    for window in space.elements_for_output(output) {
+        if let Err(err) = offscreen::update(gles, window, scale, log) {
+            slog::warn!(log, "Failed to draw window offscreen: {:?}", err);
+        }
+    }
+}
+
+///
+/// An effect drawn by one of the [`Shaders`] -- only
+/// ever made by renderers that can draw it.
+///
+#[derive(Debug, Clone)]
+pub struct ShaderElement(PixelShaderElement);
+
+impl Element for ShaderElement {
+    fn id(&self) -> &Id {
+        self.0.id()
+    }
+
+    fn current_commit(&self) -> CommitCounter {
+        self.0.current_commit()
+    }
+
+    fn src(&self) -> Rectangle<f64, Buffer> {
+        self.0.src()
+    }
+
+    fn geometry(&self, scale: Scale<f64>) -> Rectangle<i32, Physical> {
+        self.0.geometry(scale)
+    }
+
+    fn location(&self, scale: Scale<f64>) -> Point<i32, Physical> {
+        self.0.location(scale)
+    }
+
+    fn transform(&self) -> Transform {
+        self.0.transform()
+    }
+
+    fn damage_since(
+        &self,
+        scale: Scale<f64>,
+        commit: Option<CommitCounter>,
+    ) -> Vec<Rectangle<i32, Physical>> {
+        self.0.damage_since(scale, commit)
+    }
+
+    fn opaque_regions(&self, scale: Scale<f64>) -> Vec<Rectangle<i32, Physical>> {
+        self.0.opaque_regions(scale)
+    }
+}
+
+impl<R: EffectsRenderer> RenderElement<R> for ShaderElement {
+    fn draw<'frame>(
+        &self,
+        frame: &mut <R as Renderer>::Frame<'frame>,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+        log: &slog::Logger,
+    ) -> Result<(), <R as Renderer>::Error> {
+        R::draw_shader(frame, &self.0, src, dst, damage, log)
+    }
+}
+
+///
+/// `effect`, drawn over `rect` (relative to the output) for `window`
+/// -- with shaders if the window and renderer allow it, from a texture if not.
+///
+/// The element is kept around in the window's user data
+/// (one per kind of effect), so it's only redrawn when it changes.
+///
+pub fn effect_element<R, C>(
+    renderer: &mut R,
+    window: &AvWindow,
+    effect: Effect,
+    rect: Rectangle<i32, Logical>,
+    opacity: f32,
+    scale: Scale<f64>,
+    log: &slog::Logger,
+) -> Option<C>
+where
+    R: EffectsRenderer,
+    <R as Renderer>::TextureId: Texture + Clone + 'static,
+    C: From<ShaderElement> + From<TextureRenderElement<<R as Renderer>::TextureId>>,
+{
+    if rect.is_empty() {
+        return None;
+    }
+
+    let shaders = window
+        .effects()
+        .use_shaders
+        .then(|| renderer.effect_shaders(log))
+        .flatten();
+
+    match shaders {
+        Some(shaders) => Some(C::from(
+            shaders.element(window, effect, rect, opacity, scale),
+        )),
+        None => fallback::element(renderer, window, effect, rect, opacity, scale, log).map(C::from),
+    }
+}
@@ -0,0 +1,406 @@
+//!
+//! Windows drawn into textures of their own first, then from there
+//! onto the output -- so they can have their corners rounded off, be
+//! drawn translucent, and still be drawn (from their last texture)
+//! while they animate closed.
+//!
+//! Only the window's own surface tree goes in the texture;
+//! its popups are drawn on their own, as usual.
+//!
+
+use std::cell::RefCell;
+
+use smithay::{
+    backend::renderer::{
+        element::{
+            surface::{render_elements_from_surface_tree, WaylandSurfaceRenderElement},
+            texture::TextureRenderElement,
+            CommitCounter, Element, Id, RenderElement,
+        },
+        gles2::{Gles2Error, Gles2Frame, Gles2Renderer, Gles2Texture},
+        Bind, Frame, Offscreen, Renderer, Unbind,
+    },
+    desktop::{space::SpaceElement, utils::bbox_from_surface_tree},
+    utils::{Buffer, Logical, Physical, Point, Rectangle, Scale, Size, Transform},
+};
+
+use crate::compositor::{animation::RenderTransform, shell::AvWindow};
+
+use super::{fallback, Effect, EffectsRenderer, ShaderElement, Shaders};
+
+///
+/// A window's surfaces, as they were last drawn.
+///
+#[derive(Debug)]
+struct Snapshot {
+    texture: Gles2Texture,
+
+    ///
+    /// The window's geometry when it was drawn.
+    ///
+    geometry: Rectangle<i32, Logical>,
+
+    ///
+    /// What the texture covers: the window's whole surface tree,
+    /// subsurfaces reaching past the geometry included.
+    ///
+    bbox: Rectangle<i32, Logical>,
+
+    scale: Scale<f64>,
+
+    ///
+    /// How far (in logical pixels) its corners were rounded off.
+    ///
+    radius: i32,
+
+    ///
+    /// What was drawn into the texture: each surface's
+    /// element, commit and where it went.
+    ///
+    surfaces: Vec<(Id, CommitCounter, Rectangle<i32, Physical>)>,
+
+    id: Id,
+    commit: CommitCounter,
+
+    ///
+    /// What the texture was last drawn onto the output with
+    /// -- a change means it has to be drawn again.
+    ///
+    alpha: f32,
+}
+
+#[derive(Debug, Default)]
+struct SnapshotCache(RefCell<Option<Snapshot>>);
+
+///
+/// What clears a window's corners, drawn over its surfaces.
+///
+enum Mask {
+    Shader(ShaderElement),
+    Texture(TextureRenderElement<Gles2Texture>),
+}
+
+///
+/// Draw `window`'s surfaces into its texture, at `scale`, with its
+/// corners rounded off -- unless nothing changed since they last were.
+///
+/// Has to be called before binding the output's framebuffer.
+///
+pub fn update(
+    renderer: &mut Gles2Renderer,
+    window: &AvWindow,
+    scale: Scale<f64>,
+    log: &slog::Logger,
+) -> Result<(), Gles2Error> {
+    let Some(surface) = window.wl_surface() else {
+        return Ok(());
+    };
+
+    let geometry = window.geometry();
+    let bbox = bbox_from_surface_tree(&surface, (0, 0));
+    let size = bbox.size.to_physical_precise_round(scale);
+    if size.w <= 0 || size.h <= 0 {
+        return Ok(());
+    }
+
+    // With the surface tree's top-left at the texture's.
+    let location: Point<i32, Physical> =
+        Point::from((0, 0)) - bbox.loc.to_physical_precise_round(scale);
+    let elements: Vec<WaylandSurfaceRenderElement<Gles2Renderer>> =
+        render_elements_from_surface_tree(renderer, &surface, location, scale, None);
+
+    let surfaces = elements
+        .iter()
+        .map(|e| (e.id().clone(), e.current_commit(), e.geometry(scale)))
+        .collect::<Vec<_>>();
+
+    window.user_data().insert_if_missing(SnapshotCache::default);
+    let mut cache = window
+        .user_data()
+        .get::<SnapshotCache>()
+        .unwrap()
+        .0
+        .borrow_mut();
+
+    let radius = window.corner_radius(geometry.size);
+
+    let reusable = cache
+        .as_ref()
+        .filter(|s| s.bbox.size == bbox.size && s.scale == scale);
+
+    if reusable.map_or(false, |s| {
+        s.surfaces == surfaces && s.radius == radius && s.geometry == geometry
+    }) {
+        return Ok(());
+    }
+
+    // Only the geometry's corners are cut off -- it's
+    // the same distance into the texture as the tree's in.
+    let rect = Rectangle::from_loc_and_size(geometry.loc - bbox.loc, geometry.size);
+    let mask = match radius > 0 {
+        true => mask(renderer, window, radius, rect, scale, log),
+        false => None,
+    };
+
+    let texture = match reusable {
+        Some(s) => s.texture.clone(),
+        None => Offscreen::<Gles2Texture>::create_buffer(renderer, Size::from((size.w, size.h)))?,
+    };
+
+    renderer.bind(texture.clone())?;
+    let drawn = draw(renderer, &elements, mask.as_ref(), size, scale, log);
+    renderer.unbind()?;
+    drawn?;
+
+    match cache.as_mut() {
+        Some(snapshot) => {
+            snapshot.texture = texture;
+            snapshot.geometry = geometry;
+            snapshot.bbox = bbox;
+            snapshot.scale = scale;
+            snapshot.radius = radius;
+            snapshot.surfaces = surfaces;
+            snapshot.commit.increment();
+        }
+        None => {
+            *cache = Some(Snapshot {
+                texture,
+                geometry,
+                bbox,
+                scale,
+                radius,
+                surfaces,
+                id: Id::new(),
+                commit: CommitCounter::default(),
+                alpha: 1.0,
+            })
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// The mask for a window's geometry at `rect`, with its corners rounded off
+/// by `radius` -- drawn with shaders if the window allows it, on the CPU if not.
+///
+fn mask(
+    renderer: &mut Gles2Renderer,
+    window: &AvWindow,
+    radius: i32,
+    rect: Rectangle<i32, Logical>,
+    scale: Scale<f64>,
+    log: &slog::Logger,
+) -> Option<Mask> {
+    let effect = Effect::CornerMask {
+        radius: radius as f32,
+    };
+
+    let shaders = window
+        .effects()
+        .use_shaders
+        .then(|| Shaders::get(renderer, log))
+        .flatten();
+
+    match shaders {
+        Some(shaders) => Some(Mask::Shader(
+            shaders.element(window, effect, rect, 1.0, scale),
+        )),
+        None => {
+            fallback::element(renderer, window, effect, rect, 1.0, scale, log).map(Mask::Texture)
+        }
+    }
+}
+
+///
+/// Draw `elements` (topmost first) into the bound
+/// texture, and `mask` over them.
+///
+fn draw(
+    renderer: &mut Gles2Renderer,
+    elements: &[WaylandSurfaceRenderElement<Gles2Renderer>],
+    mask: Option<&Mask>,
+    size: Size<i32, Physical>,
+    scale: Scale<f64>,
+    log: &slog::Logger,
+) -> Result<(), Gles2Error> {
+    let mut frame = renderer.render(size, Transform::Normal)?;
+    frame.clear([0.0; 4], &[Rectangle::from_loc_and_size((0, 0), size)])?;
+
+    for element in elements.iter().rev() {
+        draw_element(&mut frame, element, scale, log)?;
+    }
+
+    match mask {
+        Some(Mask::Shader(element)) => draw_element(&mut frame, element, scale, log)?,
+        Some(Mask::Texture(element)) => draw_element(&mut frame, element, scale, log)?,
+        None => {}
+    }
+
+    frame.finish()?;
+    Ok(())
+}
+
+///
+/// Draw the whole of `element`.
+///
+fn draw_element<E: RenderElement<Gles2Renderer>>(
+    frame: &mut Gles2Frame<'_>,
+    element: &E,
+    scale: Scale<f64>,
+    log: &slog::Logger,
+) -> Result<(), Gles2Error> {
+    let dst = element.geometry(scale);
+    let damage = [Rectangle::from_loc_and_size((0, 0), dst.size)];
+    element.draw(frame, element.src(), dst, &damage, log)
+}
+
+///
+/// Whether `window` has been drawn into a texture.
+///
+pub fn has_snapshot(window: &AvWindow) -> bool {
+    window
+        .user_data()
+        .get::<SnapshotCache>()
+        .map_or(false, |cache| cache.0.borrow().is_some())
+}
+
+///
+/// `window`'s texture, with the top-left of its geometry at `origin`
+/// (moved and scaled by `transform`) -- `None` if it hasn't got one.
+///
+pub fn element(
+    window: &AvWindow,
+    origin: Point<i32, Physical>,
+    transform: Option<RenderTransform>,
+    alpha: f32,
+    scale: Scale<f64>,
+) -> Option<OffscreenElement> {
+    let cache = window.user_data().get::<SnapshotCache>()?;
+    let mut cache = cache.0.borrow_mut();
+    let snapshot = cache.as_mut()?;
+
+    if snapshot.alpha != alpha {
+        snapshot.alpha = alpha;
+        snapshot.commit.increment();
+    }
+
+    // The texture's top-left, from the geometry's.
+    let mut offset = (snapshot.bbox.loc - snapshot.geometry.loc).to_f64();
+    let mut size = snapshot.bbox.size.to_f64();
+    let mut loc = origin;
+
+    if let Some(transform) = transform {
+        offset = offset.upscale(transform.scale);
+        size = size.upscale(transform.scale);
+        loc += transform.offset.to_physical_precise_round(scale);
+    }
+    loc += offset.to_physical_precise_round(scale);
+
+    let texture_size = snapshot.bbox.size.to_physical_precise_round(snapshot.scale);
+
+    Some(OffscreenElement {
+        id: snapshot.id.clone(),
+        commit: snapshot.commit,
+        texture: snapshot.texture.clone(),
+        src: Rectangle::from_loc_and_size(
+            (0.0, 0.0),
+            (texture_size.w as f64, texture_size.h as f64),
+        ),
+        geometry: Rectangle::from_loc_and_size(loc, size.to_physical(scale).to_i32_round()),
+        alpha,
+    })
+}
+
+///
+/// A window's texture, drawn onto the output.
+///
+#[derive(Debug, Clone)]
+pub struct OffscreenElement {
+    id: Id,
+    commit: CommitCounter,
+    texture: Gles2Texture,
+    src: Rectangle<f64, Buffer>,
+    geometry: Rectangle<i32, Physical>,
+    alpha: f32,
+}
+
+impl OffscreenElement {
+    ///
+    /// Draw the texture, onto a frame of the
+    /// renderer it was drawn with.
+    ///
+    pub fn draw_gles(
+        &self,
+        frame: &mut Gles2Frame<'_>,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+    ) -> Result<(), Gles2Error> {
+        frame.render_texture_from_to(
+            &self.texture,
+            src,
+            dst,
+            damage,
+            self.transform(),
+            self.alpha,
+        )
+    }
+}
+
+impl Element for OffscreenElement {
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn current_commit(&self) -> CommitCounter {
+        self.commit
+    }
+
+    fn src(&self) -> Rectangle<f64, Buffer> {
+        self.src
+    }
+
+    fn geometry(&self, _scale: Scale<f64>) -> Rectangle<i32, Physical> {
+        self.geometry
+    }
+
+    fn location(&self, _scale: Scale<f64>) -> Point<i32, Physical> {
+        self.geometry.loc
+    }
+
+    ///
+    /// GL draws into textures bottom row first.
+    ///
+    fn transform(&self) -> Transform {
+        Transform::Flipped180
+    }
+
+    fn damage_since(
+        &self,
+        _scale: Scale<f64>,
+        commit: Option<CommitCounter>,
+    ) -> Vec<Rectangle<i32, Physical>> {
+        match commit == Some(self.commit) {
+            true => vec![],
+            false => vec![Rectangle::from_loc_and_size((0, 0), self.geometry.size)],
+        }
+    }
+
+    fn opaque_regions(&self, _scale: Scale<f64>) -> Vec<Rectangle<i32, Physical>> {
+        vec![]
+    }
+}
+
+impl<R: EffectsRenderer> RenderElement<R> for OffscreenElement {
+    fn draw<'frame>(
+        &self,
+        frame: &mut <R as Renderer>::Frame<'frame>,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+        log: &slog::Logger,
+    ) -> Result<(), <R as Renderer>::Error> {
+        R::draw_offscreen(frame, self, src, dst, damage, log)
+    }
+}
@@ -0,0 +1,189 @@
+//!
+//! Effects drawn on the GPU, with GLES pixel shaders.
+//!
+
+use std::cell::RefCell;
+
+use smithay::{
+    backend::renderer::{
+        element::RenderElement,
+        gles2::{
+            element::PixelShaderElement, Gles2Error, Gles2Frame, Gles2PixelProgram, Gles2Renderer,
+            UniformName, UniformType,
+        },
+    },
+    utils::{Buffer, Logical, Physical, Rectangle, Scale},
+};
+
+use crate::compositor::shell::AvWindow;
+
+use super::{Effect, EffectsRenderer, OffscreenElement, ShaderElement};
+
+static SHADOW_SHADER: &str = include_str!("../../../resources/shaders/shadow.frag");
+static ROUNDED_RECT_SHADER: &str = include_str!("../../../resources/shaders/rounded_rect.frag");
+static CORNER_MASK_SHADER: &str = include_str!("../../../resources/shaders/corner_mask.frag");
+
+///
+/// The compiled effect shaders, for a single GL context.
+///
+#[derive(Debug, Clone)]
+pub struct Shaders {
+    shadow: Gles2PixelProgram,
+    rounded_rect: Gles2PixelProgram,
+    corner_mask: Gles2PixelProgram,
+}
+
+///
+/// The shader elements last drawn for a window, one per kind of effect.
+///
+#[derive(Debug, Default)]
+struct ShaderCache(RefCell<Vec<(Effect, PixelShaderElement)>>);
+
+impl Shaders {
+    ///
+    /// The shaders for `renderer`'s context -- compiled the first time
+    /// they're asked for, and kept in the context's user data.
+    ///
+    /// `None` (with a warning, once) if they don't compile.
+    ///
+    pub fn get(renderer: &mut Gles2Renderer, log: &slog::Logger) -> Option<Self> {
+        if let Some(shaders) = renderer.egl_context().user_data().get::<Option<Self>>() {
+            return shaders.clone();
+        }
+
+        let shaders = Self::compile(renderer)
+            .map_err(|err| {
+                slog::warn!(
+                    log,
+                    "Failed to compile effect shaders, drawing effects on the CPU: {:?}",
+                    err
+                );
+            })
+            .ok();
+
+        renderer
+            .egl_context()
+            .user_data()
+            .insert_if_missing(|| shaders.clone());
+
+        shaders
+    }
+
+    fn compile(renderer: &mut Gles2Renderer) -> Result<Self, Gles2Error> {
+        let shadow = renderer.compile_custom_pixel_shader(
+            SHADOW_SHADER,
+            &[
+                UniformName::new("color", UniformType::_4f),
+                UniformName::new("radius", UniformType::_1f),
+                UniformName::new("spread", UniformType::_1f),
+                UniformName::new("opacity", UniformType::_1f),
+            ],
+        )?;
+
+        let rounded_rect = renderer.compile_custom_pixel_shader(
+            ROUNDED_RECT_SHADER,
+            &[
+                UniformName::new("color", UniformType::_4f),
+                UniformName::new("radius", UniformType::_1f),
+                UniformName::new("opacity", UniformType::_1f),
+            ],
+        )?;
+
+        let corner_mask = renderer.compile_custom_pixel_shader(
+            CORNER_MASK_SHADER,
+            &[UniformName::new("radius", UniformType::_1f)],
+        )?;
+
+        Ok(Self {
+            shadow,
+            rounded_rect,
+            corner_mask,
+        })
+    }
+
+    fn program(&self, effect: &Effect) -> &Gles2PixelProgram {
+        match effect {
+            Effect::Shadow { .. } => &self.shadow,
+            Effect::RoundedRect { .. } => &self.rounded_rect,
+            Effect::CornerMask { .. } => &self.corner_mask,
+        }
+    }
+
+    ///
+    /// `effect` over `rect`, reusing (and updating) the element
+    /// last drawn for `window` -- so it keeps its damage history.
+    ///
+    pub(super) fn element(
+        &self,
+        window: &AvWindow,
+        effect: Effect,
+        rect: Rectangle<i32, Logical>,
+        opacity: f32,
+        scale: Scale<f64>,
+    ) -> ShaderElement {
+        let uniforms = effect.to_physical(scale.x).uniforms(opacity);
+
+        window.user_data().insert_if_missing(ShaderCache::default);
+        let mut cache = window
+            .user_data()
+            .get::<ShaderCache>()
+            .unwrap()
+            .0
+            .borrow_mut();
+
+        match cache
+            .iter_mut()
+            .find(|(cached, _)| cached.same_kind(&effect))
+        {
+            Some((cached, element)) => {
+                *cached = effect;
+                element.resize(rect, None);
+                element.update_uniforms(uniforms);
+                ShaderElement(element.clone())
+            }
+            None => {
+                let element = PixelShaderElement::new(
+                    self.program(&effect).clone(),
+                    rect,
+                    None,
+                    1.0,
+                    uniforms,
+                );
+                cache.push((effect, element.clone()));
+                ShaderElement(element)
+            }
+        }
+    }
+}
+
+impl EffectsRenderer for Gles2Renderer {
+    fn effect_shaders(&mut self, log: &slog::Logger) -> Option<Shaders> {
+        Shaders::get(self, log)
+    }
+
+    fn draw_shader<'frame>(
+        frame: &mut Gles2Frame<'frame>,
+        element: &PixelShaderElement,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+        log: &slog::Logger,
+    ) -> Result<(), Gles2Error> {
+        RenderElement::<Gles2Renderer>::draw(element, frame, src, dst, damage, log)
+    }
+
+    fn offscreen_renderer(&mut self) -> Option<&mut Gles2Renderer> {
+        Some(self)
+    }
+
+    fn draw_offscreen<'frame>(
+        frame: &mut Gles2Frame<'frame>,
+        element: &OffscreenElement,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+        _log: &slog::Logger,
+    ) -> Result<(), Gles2Error> {
+        element.draw_gles(frame, src, dst, damage)
+    }
+}
@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use smithay::{
     backend::input::KeyState,
-    desktop::{LayerSurface, PopupKind},
+    desktop::{space::SpaceElement, LayerSurface, PopupKind, Space},
     input::{
         keyboard::{self, KeyboardTarget, KeysymHandle},
         pointer::{self, PointerTarget},
@@ -10,12 +12,167 @@ use smithay::{
         wayland_server::{backend::ObjectId, protocol::wl_surface::WlSurface, Resource},
         winit::event::ModifiersState,
     },
-    utils::{IsAlive, Serial},
-    wayland::seat::WaylandFocus,
+    utils::{IsAlive, Logical, Rectangle, Serial},
+    wayland::{seat::WaylandFocus, xdg_activation::XdgActivationTokenData},
 };
 
+use crate::config::{sections::focus::Focus, templating::Direction};
+
 use super::{backend::Backend, shell::AvWindow, state::Navda};
 
+///
+/// How the keyboard focus follows the pointer.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusMode {
+    ///
+    /// Windows only gain focus when clicked.
+    ///
+    Click,
+
+    ///
+    /// The window under the pointer has focus;
+    /// hovering over the desktop clears it.
+    ///
+    FollowsMouse,
+
+    ///
+    /// Like `FollowsMouse`, but focus stays with the last
+    /// window when the pointer moves over the desktop.
+    ///
+    Sloppy,
+}
+
+impl<'a> TryFrom<&'a str> for FocusMode {
+    type Error = String;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        match value {
+            "click" => Ok(Self::Click),
+            "followsMouse" => Ok(Self::FollowsMouse),
+            "sloppy" => Ok(Self::Sloppy),
+            _ => Err(value.to_string()),
+        }
+    }
+}
+
+///
+/// How picky we are about clients taking focus
+/// for themselves (through xdg-activation).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StealingPrevention {
+    ///
+    /// Any valid token activates its window.
+    ///
+    Off,
+
+    ///
+    /// Only the focused client (or anyone, when nothing is focused)
+    /// may pass focus on.
+    ///
+    Smart,
+
+    ///
+    /// Like `Smart`, but the token must also come from user input.
+    ///
+    Strict,
+}
+
+impl<'a> TryFrom<&'a str> for StealingPrevention {
+    type Error = String;
+
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        match value {
+            "off" => Ok(Self::Off),
+            "smart" => Ok(Self::Smart),
+            "strict" => Ok(Self::Strict),
+            _ => Err(value.to_string()),
+        }
+    }
+}
+
+///
+/// The user's focus preferences, read
+/// from the `focus` config section.
+///
+#[derive(Debug, Clone)]
+pub struct FocusPolicy {
+    pub mode: FocusMode,
+    pub raise_on_click: bool,
+    pub raise_on_focus: bool,
+    pub stealing_prevention: StealingPrevention,
+    pub activation_timeout: Duration,
+}
+
+impl Default for FocusPolicy {
+    fn default() -> Self {
+        Self {
+            mode: FocusMode::Click,
+            raise_on_click: true,
+            raise_on_focus: false,
+            stealing_prevention: StealingPrevention::Smart,
+            activation_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl FocusPolicy {
+    pub fn from_config(focus: &Focus, log: &slog::Logger) -> Self {
+        let default = Self::default();
+
+        let mode = FocusMode::try_from(focus.mode.as_str()).unwrap_or_else(|v| {
+            slog::warn!(log, "Unknown focus mode `{}`, using `click`.", v);
+            default.mode
+        });
+
+        let stealing_prevention = StealingPrevention::try_from(focus.stealingPrevention.as_str())
+            .unwrap_or_else(|v| {
+                slog::warn!(
+                    log,
+                    "Unknown focus stealing prevention `{}`, using `smart`.",
+                    v
+                );
+                default.stealing_prevention
+            });
+
+        Self {
+            mode,
+            raise_on_click: focus.raiseOnClick,
+            raise_on_focus: focus.raiseOnFocus,
+            stealing_prevention,
+            activation_timeout: focus.activationTimeout.into(),
+        }
+    }
+
+    ///
+    /// Should this xdg-activation request be granted,
+    /// given what currently has keyboard focus?
+    ///
+    pub fn allows_activation(
+        &self,
+        token_data: &XdgActivationTokenData,
+        current_focus: Option<&FocusTarget>,
+    ) -> bool {
+        if token_data.timestamp.elapsed() >= self.activation_timeout {
+            return false;
+        }
+
+        let from_focused = match (current_focus, token_data.surface.as_ref()) {
+            // Nothing to steal from.
+            (None, _) => true,
+            (Some(focus), Some(requester)) => focus.same_client_as(&requester.id()),
+            (Some(_), None) => false,
+        };
+
+        match self.stealing_prevention {
+            StealingPrevention::Off => true,
+            StealingPrevention::Smart => from_focused,
+            StealingPrevention::Strict => from_focused && token_data.serial.is_some(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum FocusTarget {
     Window(AvWindow),
@@ -202,3 +359,112 @@ impl From<PopupKind> for FocusTarget {
         FocusTarget::Popup(p)
     }
 }
+
+///
+/// Windows in the order they last had keyboard focus,
+/// most recent first.
+///
+#[derive(Debug, Default)]
+pub struct FocusHistory(Vec<AvWindow>);
+
+impl FocusHistory {
+    ///
+    /// Mark `window` as the most recently focused.
+    ///
+    pub fn touch(&mut self, window: &AvWindow) {
+        self.0.retain(|w| w != window && w.alive());
+        self.0.insert(0, window.clone());
+    }
+
+    ///
+    /// Every window mapped in `space`, most recently focused first.
+    ///
+    /// Windows which never had focus come last,
+    /// in top-to-bottom stacking order.
+    ///
+    pub fn windows(&self, space: &Space<AvWindow>) -> Vec<AvWindow> {
+        let mapped = space.elements().collect::<Vec<_>>();
+
+        let mut windows = self
+            .0
+            .iter()
+            .filter(|w| w.alive() && mapped.contains(w))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for w in mapped.into_iter().rev() {
+            if !windows.contains(w) {
+                windows.push(w.clone());
+            }
+        }
+
+        windows
+    }
+}
+
+impl<BEnd: Backend> Navda<BEnd> {
+    ///
+    /// Give `window` keyboard focus, raising it
+    /// above the others if `raise` is set.
+    ///
+    pub fn focus_window(&mut self, window: &AvWindow, raise: bool, serial: Serial) {
+        if raise {
+            self.space.raise_element(window, true);
+
+            if let AvWindow::X11(surf) = window {
+                if let Some(xwm) = self.xwm.as_mut() {
+                    let _ = xwm.raise_window(surf);
+                }
+            }
+        } else {
+            for w in self.space.elements() {
+                w.set_activate(w == window);
+            }
+        }
+
+        let keyboard = self.seat.get_keyboard().unwrap();
+        keyboard.set_focus(self, Some(window.clone().into()), serial);
+    }
+
+    ///
+    /// Focus (and raise) the nearest window in `direction`
+    /// from the focused one, going by their centers.
+    ///
+    pub fn focus_direction(&mut self, direction: Direction, serial: Serial) {
+        let keyboard = self.seat.get_keyboard().unwrap();
+        let Some(FocusTarget::Window(current)) = keyboard.current_focus() else {
+            return;
+        };
+        let Some(from) = self.space.element_bbox(&current) else {
+            return;
+        };
+
+        let center = |r: Rectangle<i32, Logical>| (r.loc.x + r.size.w / 2, r.loc.y + r.size.h / 2);
+        let (x, y) = center(from);
+
+        let nearest = self
+            .space
+            .elements()
+            .filter(|w| **w != current)
+            .filter_map(|w| {
+                let (wx, wy) = center(self.space.element_bbox(w)?);
+
+                // How far ahead the window is, and how far off to the side.
+                let (ahead, aside) = match direction {
+                    Direction::Left => (x - wx, wy - y),
+                    Direction::Right => (wx - x, wy - y),
+                    Direction::Up => (y - wy, wx - x),
+                    Direction::Down => (wy - y, wx - x),
+                };
+
+                // Straying sideways counts for more than going further.
+                (ahead > 0).then(|| (w.clone(), ahead + 2 * aside.abs()))
+            })
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(w, _)| w);
+
+        if let Some(window) = nearest {
+            self.focus_window(&window, true, serial);
+        }
+    }
+}
@@ -18,9 +18,12 @@ use smithay::{
     delegate_primary_selection, delegate_seat, delegate_tablet_manager,
     delegate_text_input_manager, delegate_virtual_keyboard_manager,
     input::{pointer::CursorImageStatus, Seat, SeatHandler, SeatState},
-    reexports::wayland_server::{
-        protocol::{wl_data_source::WlDataSource, wl_surface::WlSurface},
-        Resource,
+    reexports::{
+        wayland_protocols::wp::primary_selection::zv1::server::zwp_primary_selection_source_v1::ZwpPrimarySelectionSourceV1,
+        wayland_server::{
+            protocol::{wl_data_source::WlDataSource, wl_surface::WlSurface},
+            Resource,
+        },
     },
     wayland::{
         data_device::{
@@ -36,7 +39,12 @@ use smithay::{
     },
 };
 
-use crate::compositor::{backend::Backend, focus::FocusTarget, state::Navda};
+use crate::compositor::{
+    backend::Backend,
+    clipboard::{SelectionKind, SelectionSource},
+    focus::FocusTarget,
+    state::Navda,
+};
 
 impl<BEnd: Backend> SeatHandler for Navda<BEnd> {
     type KeyboardFocus = FocusTarget;
@@ -47,6 +55,10 @@ impl<BEnd: Backend> SeatHandler for Navda<BEnd> {
     }
 
     fn focus_changed(&mut self, seat: &Seat<Self>, target: Option<&FocusTarget>) {
+        if let Some(FocusTarget::Window(window)) = target {
+            self.focus_history.touch(window);
+        }
+
         let dh = &self.display_handle;
 
         let focus = target
@@ -62,12 +74,15 @@ impl<BEnd: Backend> SeatHandler for Navda<BEnd> {
 delegate_seat!(@<BEnd: Backend + 'static> Navda<BEnd>);
 
 // Drag-and-drop
-impl<BEnd: Backend> DataDeviceHandler for Navda<BEnd> {
+impl<BEnd: Backend + 'static> DataDeviceHandler for Navda<BEnd> {
     fn data_device_state(&self) -> &DataDeviceState {
         &self.data_device_state
     }
-    fn send_selection(&mut self, _mime_type: String, _fd: OwnedFd) {
-        todo!("Navda doesn't do server-side selections");
+    fn new_selection(&mut self, source: Option<WlDataSource>) {
+        self.selection_changed(SelectionKind::Clipboard, source.map(SelectionSource::Data));
+    }
+    fn send_selection(&mut self, mime_type: String, fd: OwnedFd) {
+        self.write_selection(SelectionKind::Clipboard, mime_type, fd);
     }
 }
 impl<BEnd: Backend> ClientDndGrabHandler for Navda<BEnd> {
@@ -94,10 +109,16 @@ delegate_data_device!(@<BEnd: Backend + 'static> Navda<BEnd>);
 
 // Primary Selection
 
-impl<BackendData: Backend> PrimarySelectionHandler for Navda<BackendData> {
+impl<BackendData: Backend + 'static> PrimarySelectionHandler for Navda<BackendData> {
     fn primary_selection_state(&self) -> &PrimarySelectionState {
         &self.primary_selection_state
     }
+    fn new_selection(&mut self, source: Option<ZwpPrimarySelectionSourceV1>) {
+        self.selection_changed(SelectionKind::Primary, source.map(SelectionSource::Primary));
+    }
+    fn send_selection(&mut self, mime_type: String, fd: OwnedFd) {
+        self.write_selection(SelectionKind::Primary, mime_type, fd);
+    }
 }
 
 delegate_primary_selection!(@<BEnd: Backend + 'static> Navda<BEnd>);
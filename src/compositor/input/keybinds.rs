@@ -0,0 +1,276 @@
+//!
+//! Window management shortcuts, read
+//! from the `keybinds` config section.
+//!
+
+use smithay::input::keyboard::ModifiersState;
+use xkbcommon::xkb::{self, keysyms::KEY_NoSymbol};
+
+use crate::{
+    config::{
+        sections::keybinds::Keybinds,
+        templating::{MacroArgument, MacroParameter},
+    },
+    core::keyboard::{avkeys::Arguments, AvKey, AvKeys, AvKeysList},
+};
+
+use super::KeyAction;
+
+///
+/// How far (in logical pixels) a single
+/// nudge moves or resizes a window.
+///
+const STEP: i32 = 32;
+
+///
+/// A key, along with the exact
+/// modifiers that have to be held for it.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Binding {
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    logo: bool,
+    keysym: u32,
+}
+
+impl Binding {
+    ///
+    /// `None` (with a warning) if any part of `keys`
+    /// isn't a modifier or a known key name.
+    ///
+    fn parse(name: &str, keys: &AvKeys, log: &slog::Logger) -> Option<Self> {
+        let mut binding = Self::default();
+
+        for key in &keys.0 {
+            let key = match key {
+                AvKey::Key(key) => key,
+                AvKey::Parameter(_) => {
+                    slog::warn!(
+                        log,
+                        "Keybind `{}` can't take parameters, ignoring it.",
+                        name
+                    );
+                    return None;
+                }
+            };
+
+            match key.to_lowercase().as_str() {
+                "ctrl" | "control" => binding.ctrl = true,
+                "alt" => binding.alt = true,
+                "shift" => binding.shift = true,
+                "logo" | "meta" | "super" => binding.logo = true,
+                _ => {
+                    let keysym = xkb::keysym_from_name(key, xkb::KEYSYM_CASE_INSENSITIVE).raw();
+                    if keysym == KEY_NoSymbol {
+                        slog::warn!(
+                            log,
+                            "Unknown key `{}` in keybind `{}`, ignoring it.",
+                            key,
+                            name
+                        );
+                        return None;
+                    }
+                    binding.keysym = keysym;
+                }
+            }
+        }
+
+        if binding.keysym == KEY_NoSymbol {
+            slog::warn!(
+                log,
+                "Keybind `{}` has no key besides modifiers, ignoring it.",
+                name
+            );
+            return None;
+        }
+
+        Some(binding)
+    }
+
+    ///
+    /// Are all of this binding's modifiers (and maybe others) held?
+    /// `false` if it hasn't got any.
+    ///
+    fn modifiers_held(&self, modifiers: &ModifiersState) -> bool {
+        (self.ctrl || self.alt || self.shift || self.logo)
+            && (!self.ctrl || modifiers.ctrl)
+            && (!self.alt || modifiers.alt)
+            && (!self.shift || modifiers.shift)
+            && (!self.logo || modifiers.logo)
+    }
+
+    fn matches(&self, modifiers: &ModifiersState, raw_syms: &[u32]) -> bool {
+        self.ctrl == modifiers.ctrl
+            && self.alt == modifiers.alt
+            && self.shift == modifiers.shift
+            && self.logo == modifiers.logo
+            && raw_syms.contains(&self.keysym)
+    }
+}
+
+///
+/// Every configured window management shortcut.
+///
+#[derive(Debug, Default)]
+pub struct KeyBindings(Vec<(Binding, KeyAction)>);
+
+impl KeyBindings {
+    ///
+    /// Bindings that don't parse are skipped (with a warning).
+    ///
+    pub fn from_config(keybinds: &Keybinds, log: &slog::Logger) -> Self {
+        let bindings = [
+            (
+                "moveWindowLeft",
+                &keybinds.moveWindowLeft,
+                KeyAction::MoveWindow { dx: -STEP, dy: 0 },
+            ),
+            (
+                "moveWindowRight",
+                &keybinds.moveWindowRight,
+                KeyAction::MoveWindow { dx: STEP, dy: 0 },
+            ),
+            (
+                "moveWindowUp",
+                &keybinds.moveWindowUp,
+                KeyAction::MoveWindow { dx: 0, dy: -STEP },
+            ),
+            (
+                "moveWindowDown",
+                &keybinds.moveWindowDown,
+                KeyAction::MoveWindow { dx: 0, dy: STEP },
+            ),
+            (
+                "growWindowWidth",
+                &keybinds.growWindowWidth,
+                KeyAction::ResizeWindow { dw: STEP, dh: 0 },
+            ),
+            (
+                "shrinkWindowWidth",
+                &keybinds.shrinkWindowWidth,
+                KeyAction::ResizeWindow { dw: -STEP, dh: 0 },
+            ),
+            (
+                "growWindowHeight",
+                &keybinds.growWindowHeight,
+                KeyAction::ResizeWindow { dw: 0, dh: STEP },
+            ),
+            (
+                "shrinkWindowHeight",
+                &keybinds.shrinkWindowHeight,
+                KeyAction::ResizeWindow { dw: 0, dh: -STEP },
+            ),
+            (
+                "centerWindow",
+                &keybinds.centerWindow,
+                KeyAction::CenterWindow,
+            ),
+            (
+                "toggleMaximize",
+                &keybinds.toggleMaximize,
+                KeyAction::ToggleMaximize,
+            ),
+            (
+                "toggleFullscreen",
+                &keybinds.toggleFullscreen,
+                KeyAction::ToggleFullscreen,
+            ),
+            (
+                "minimizeWindow",
+                &keybinds.minimizeWindow,
+                KeyAction::MinimizeWindow,
+            ),
+            (
+                "restoreMinimized",
+                &keybinds.restoreMinimized,
+                KeyAction::RestoreMinimized,
+            ),
+            ("closeWindow", &keybinds.closeWindow, KeyAction::CloseWindow),
+            (
+                "cycleClipboard",
+                &keybinds.cycleClipboard,
+                KeyAction::CycleClipboard,
+            ),
+            (
+                "switchWindows",
+                &keybinds.switchWindows,
+                KeyAction::SwitchWindow { backwards: false },
+            ),
+            (
+                "switchWindowsBackwards",
+                &keybinds.switchWindowsBackwards,
+                KeyAction::SwitchWindow { backwards: true },
+            ),
+        ];
+
+        // Macros with parameters are bound once for each
+        // key they expand to, like `Logo+Left`, `Logo+Right`, ...
+        let parameterized: [(&str, &AvKeysList, fn(&Arguments) -> Option<KeyAction>); 2] = [
+            ("focus(dir)", &keybinds.focus, |args| {
+                match args.get(&MacroParameter::Direction)? {
+                    MacroArgument::Direction(d) => Some(KeyAction::FocusDirection(*d)),
+                    _ => None,
+                }
+            }),
+            ("focusOutput(o)", &keybinds.focusOutput, |args| {
+                match args.get(&MacroParameter::OutputIndex)? {
+                    MacroArgument::Output(i) => Some(KeyAction::Screen(*i)),
+                    _ => None,
+                }
+            }),
+        ];
+
+        let expanded = parameterized.into_iter().flat_map(|(name, list, action)| {
+            list.0
+                .iter()
+                .flat_map(AvKeys::expand)
+                .filter_map(move |(args, keys)| Some((name, keys, action(&args)?)))
+        });
+
+        // Every action can have several shortcuts.
+        let bindings = bindings.into_iter().flat_map(|(name, list, action)| {
+            list.0
+                .iter()
+                .map(move |keys| (name, keys.clone(), action.clone()))
+        });
+
+        Self(
+            bindings
+                .chain(expanded)
+                .filter_map(|(name, keys, action)| {
+                    Some((Binding::parse(name, &keys, log)?, action))
+                })
+                .collect(),
+        )
+    }
+
+    ///
+    /// The action bound to a key press, if any.
+    ///
+    /// Modifiers have to match exactly, and the key is compared
+    /// unmodified -- so `Shift+Left` is `Left` with Shift held.
+    ///
+    pub(super) fn action_for(
+        &self,
+        modifiers: &ModifiersState,
+        raw_syms: &[u32],
+    ) -> Option<KeyAction> {
+        self.0
+            .iter()
+            .find(|(binding, _)| binding.matches(modifiers, raw_syms))
+            .map(|(_, action)| action.clone())
+    }
+
+    ///
+    /// Are the modifiers of any of the window switcher's shortcuts still
+    /// held? Once they aren't, the switcher switches to its selection.
+    ///
+    pub(super) fn switch_held(&self, modifiers: &ModifiersState) -> bool {
+        self.0
+            .iter()
+            .filter(|(_, action)| matches!(action, KeyAction::SwitchWindow { .. }))
+            .any(|(binding, _)| binding.modifiers_held(modifiers))
+    }
+}
@@ -10,14 +10,14 @@ use smithay::{
         },
         session::Session,
     },
-    desktop::{layer_map_for_output, WindowSurfaceType},
+    desktop::{layer_map_for_output, space::SpaceElement, WindowSurfaceType},
     input::{
         keyboard::{FilterResult, ModifiersState},
         pointer::{AxisFrame, ButtonEvent, MotionEvent},
     },
-    output::{self, Output},
+    output::Output,
     reexports::wayland_server::{protocol::wl_pointer, DisplayHandle},
-    utils::{Logical, Point, Serial, Transform, SERIAL_COUNTER as SCOUNTER},
+    utils::{Logical, Point, Rectangle, Serial, Transform, SERIAL_COUNTER as SCOUNTER},
     wayland::{
         compositor::with_states,
         input_method::InputMethodSeat,
@@ -30,13 +30,22 @@ use smithay::{
 use xkbcommon::xkb::{
     self, Keysym,
     keysyms::{
-        KEY_BackSpace, KEY_q, KEY_XF86Switch_VT_1, KEY_XF86Switch_VT_12, KEY_Return, KEY_1, KEY_9, KEY_M, KEY_P, KEY_W, KEY_R
+        KEY_BackSpace, KEY_q, KEY_XF86Switch_VT_1, KEY_XF86Switch_VT_12, KEY_Return, KEY_1, KEY_9, KEY_M, KEY_P, KEY_W, KEY_R,
+        KEY_Escape, KEY_Left, KEY_Right, KEY_Up, KEY_Down,
     }
 };
 
+mod keybinds;
+
+pub use keybinds::KeyBindings;
+
+use crate::config::templating::Direction;
+
 use super::{
     backend::{Backend, UdevData},
-    focus::FocusTarget,
+    components::{Grid, Overview, WindowSwitcher},
+    focus::{FocusMode, FocusTarget},
+    scale::{round_scale, SCALE_STEP},
     shell::{self, AvWindow, FullscreenSurface},
     state::Navda,
 };
@@ -68,6 +77,52 @@ impl<BEnd: Backend> Navda<BEnd> {
                     );
                 }
             }
+            KeyAction::SwitchWindow { backwards } => match self.switcher.as_mut() {
+                Some(switcher) => switcher.step(backwards),
+                None => {
+                    let windows = self.focus_history.windows(&self.space);
+                    self.switcher = WindowSwitcher::new(windows, backwards);
+                }
+            },
+
+            KeyAction::CancelSwitch => self.switcher = None,
+
+            KeyAction::MinimizeWindow => {
+                if let Some(FocusTarget::Window(window)) =
+                    self.seat.get_keyboard().unwrap().current_focus()
+                {
+                    self.minimize_window(&window);
+                }
+            }
+
+            KeyAction::RestoreMinimized => self.restore_last_minimized(),
+
+            KeyAction::MoveWindow { .. }
+            | KeyAction::ResizeWindow { .. }
+            | KeyAction::CenterWindow
+            | KeyAction::ToggleMaximize
+            | KeyAction::ToggleFullscreen
+            | KeyAction::CloseWindow => {
+                if let Some(FocusTarget::Window(window)) =
+                    self.seat.get_keyboard().unwrap().current_focus()
+                {
+                    self.window_key_action(&window, action);
+                }
+            }
+
+            KeyAction::FocusDirection(direction) => {
+                self.focus_direction(direction, SCOUNTER.next_serial())
+            }
+
+            KeyAction::TogglePreview => self.toggle_overview(),
+
+            KeyAction::OverviewMove { dx, dy } => self.overview_move(dx, dy),
+
+            KeyAction::OverviewActivate => {
+                let selected = self.overview.as_ref().and_then(|o| o.selected().cloned());
+                self.overview_pick(selected, SCOUNTER.next_serial());
+            }
+
             _ => unreachable!(
                 "Common key action handler encountered backend specific action {:?}",
                 action
@@ -75,6 +130,102 @@ impl<BEnd: Backend> Navda<BEnd> {
         }
     }
 
+    ///
+    /// Move, resize, ... `window` from the keyboard.
+    ///
+    fn window_key_action(&mut self, window: &AvWindow, action: KeyAction) {
+        let Some(location) = self.space.element_location(window) else { return };
+        let geometry = Rectangle::from_loc_and_size(location, window.geometry().size);
+
+        // Maximized and fullscreen windows are
+        // laid out by us, so leave them be.
+        let laid_out = window.is_maximized() || window.is_fullscreen();
+
+        let from = self.drawn_rect(window);
+
+        match action {
+            KeyAction::MoveWindow { dx, dy } if !laid_out => {
+                let geometry =
+                    Rectangle::from_loc_and_size(location + Point::from((dx, dy)), geometry.size);
+                self.set_window_geometry(window, geometry);
+                self.animate_from(window, from, false);
+            }
+
+            KeyAction::ResizeWindow { dw, dh } if !laid_out => {
+                let size = ((geometry.size.w + dw).max(1), (geometry.size.h + dh).max(1));
+                self.set_window_geometry(window, Rectangle::from_loc_and_size(location, size));
+                self.animate_from(window, from, false);
+            }
+
+            KeyAction::CenterWindow if !laid_out => {
+                let usable = self
+                    .space
+                    .outputs_for_element(window)
+                    .first()
+                    .or_else(|| self.space.output_under(self.pointer_location).next())
+                    .and_then(|output| shell::usable_geometry(&self.space, output));
+
+                if let Some(usable) = usable {
+                    let loc = usable.loc
+                        + Point::from((
+                            (usable.size.w - geometry.size.w) / 2,
+                            (usable.size.h - geometry.size.h) / 2,
+                        ));
+                    let geometry = Rectangle::from_loc_and_size(loc, geometry.size);
+                    self.set_window_geometry(window, geometry);
+                    self.animate_from(window, from, false);
+                }
+            }
+
+            KeyAction::ToggleMaximize => self.maximize_window(window, !window.is_maximized()),
+
+            KeyAction::ToggleFullscreen => {
+                self.fullscreen_window(window, None, !window.is_fullscreen())
+            }
+
+            // Hung windows won't close by asking --
+            // so asking again kills them instead.
+            KeyAction::CloseWindow if window.is_unresponsive() => self.kill_window_client(window),
+            KeyAction::CloseWindow => window.send_close(),
+
+            _ => {}
+        }
+    }
+
+    ///
+    /// Start a compositor-initiated move (left button) or resize
+    /// (right button) of the window under the pointer.
+    ///
+    /// `false` if there's nothing to grab.
+    ///
+    fn start_window_drag(&mut self, button: u32, serial: Serial) -> bool {
+        const BTN_LEFT: u32 = 0x110;
+        const BTN_RIGHT: u32 = 0x111;
+
+        if self.seat.get_pointer().unwrap().is_grabbed() {
+            return false;
+        }
+
+        let window = match self.space.element_under(self.pointer_location) {
+            Some((window, _)) if !window.is_fullscreen() => window.clone(),
+            _ => return false,
+        };
+
+        match button {
+            BTN_LEFT => {
+                self.focus_window(&window, true, serial);
+                self.start_move_grab(window, button, serial);
+            }
+            BTN_RIGHT => {
+                self.focus_window(&window, true, serial);
+                self.start_resize_grab(window, button, serial);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
     fn keyboard_key_to_action<B: InputBackend>(&mut self, evt: B::KeyboardKeyEvent) -> KeyAction {
         let keycode = evt.key_code();
         let state = evt.state();
@@ -117,6 +268,8 @@ impl<BEnd: Backend> Navda<BEnd> {
             .map(|inhibitor| inhibitor.is_active())
             .unwrap_or(false);
 
+        let mut commit_switch = false;
+
         let action = keyboard
             .input(
                 self,
@@ -124,8 +277,9 @@ impl<BEnd: Backend> Navda<BEnd> {
                 state,
                 serial,
                 time,
-                |_, modifiers, handle| {
+                |data, modifiers, handle| {
                     let keysym = handle.modified_sym();
+                    data.modifiers = *modifiers;
 
                     slog::debug!(log, "keysym";
                         "state" => format!("{:?}", state),
@@ -140,7 +294,16 @@ impl<BEnd: Backend> Navda<BEnd> {
                     // should be forwarded to the client or not.
                     if let KeyState::Pressed = state {
                         if !inhibited {
-                            let action = process_keyboard_shortcut(*modifiers, keysym);
+                            let action = if data.switcher.is_some() && keysym == KEY_Escape {
+                                Some(KeyAction::CancelSwitch)
+                            } else if data.overview_open() {
+                                process_overview_shortcut(keysym)
+                                    .or_else(|| process_keyboard_shortcut(*modifiers, keysym))
+                            } else {
+                                data.key_bindings
+                                    .action_for(modifiers, handle.raw_syms())
+                                    .or_else(|| process_keyboard_shortcut(*modifiers, keysym))
+                            };
 
                             if action.is_some() {
                                 suppressed_keys.push(keysym);
@@ -153,6 +316,11 @@ impl<BEnd: Backend> Navda<BEnd> {
                             FilterResult::Forward
                         }
                     } else {
+                        // Letting go of the switcher's modifiers picks whatever it has selected.
+                        if data.switcher.is_some() && !data.key_bindings.switch_held(modifiers) {
+                            commit_switch = true;
+                        }
+
                         let suppressed = suppressed_keys.contains(&keysym);
                         if suppressed {
                             suppressed_keys.retain(|k| *k != keysym);
@@ -166,15 +334,148 @@ impl<BEnd: Backend> Navda<BEnd> {
             .unwrap_or(KeyAction::None);
 
         self.suppressed_keys = suppressed_keys;
+
+        if commit_switch {
+            self.commit_switch(serial);
+        }
+
         action
     }
 
+    ///
+    /// Is the overview open (and not on its way out)?
+    ///
+    fn overview_open(&self) -> bool {
+        self.overview
+            .as_ref()
+            .map(|o| !o.is_closing())
+            .unwrap_or(false)
+    }
+
+    fn toggle_overview(&mut self) {
+        match self.overview.as_mut() {
+            Some(overview) if overview.is_closing() => overview.open(),
+            Some(overview) => overview.close(),
+            None => {
+                let mut overview = Overview::default();
+
+                // Start from whatever has focus.
+                if let Some(FocusTarget::Window(window)) =
+                    self.seat.get_keyboard().unwrap().current_focus()
+                {
+                    overview.select(Some(window));
+                }
+
+                self.overview = Some(overview);
+            }
+        }
+    }
+
+    ///
+    /// Close the overview, focusing
+    /// and raising `window`, if any.
+    ///
+    fn overview_pick(&mut self, window: Option<AvWindow>, serial: Serial) {
+        if let Some(overview) = self.overview.as_mut() {
+            overview.select(window.clone());
+            overview.close();
+        }
+
+        if let Some(window) = window {
+            self.focus_window(&window, true, serial);
+        }
+    }
+
+    ///
+    /// Move the overview's selection around
+    /// the grid of the output it's on.
+    ///
+    fn overview_move(&mut self, dx: i32, dy: i32) {
+        let selected = match self.overview.as_ref() {
+            Some(overview) => overview.selected().cloned(),
+            None => return,
+        };
+
+        let output = selected
+            .as_ref()
+            .and_then(|w| self.space.outputs_for_element(w).into_iter().next())
+            .or_else(|| self.space.output_under(self.pointer_location).next().cloned());
+
+        let grid = match output.and_then(|o| Grid::new(&self.space, &o)) {
+            Some(grid) if !grid.tiles.is_empty() => grid,
+            _ => return,
+        };
+
+        let last = grid.tiles.len() as i32 - 1;
+        let next = match grid.tiles.iter().position(|(w, _)| Some(w) == selected.as_ref()) {
+            Some(i) => (i as i32 + dx + dy * grid.columns as i32).clamp(0, last),
+            None => 0,
+        };
+
+        if let Some(overview) = self.overview.as_mut() {
+            overview.select(Some(grid.tiles[next as usize].0.clone()));
+        }
+    }
+
+    ///
+    /// Drop the overview once it's done animating closed.
+    ///
+    pub fn refresh_overview(&mut self) {
+        if self.overview.as_ref().map(Overview::is_finished).unwrap_or(false) {
+            self.overview = None;
+        }
+    }
+
+    ///
+    /// Close the window switcher,
+    /// focusing and raising the selected window.
+    ///
+    fn commit_switch(&mut self, serial: Serial) {
+        let selected = self
+            .switcher
+            .take()
+            .and_then(|switcher| switcher.selected().cloned());
+
+        if let Some(window) = selected {
+            self.focus_window(&window, true, serial);
+        }
+    }
+
     fn on_pointer_button<B: InputBackend>(&mut self, evt: B::PointerButtonEvent) {
         let serial = SCOUNTER.next_serial();
         let button = evt.button_code();
 
         let state = wl_pointer::ButtonState::from(evt.state());
 
+        // Clicks pick a window in the overview,
+        // instead of going through to clients.
+        if self.overview_open() {
+            if wl_pointer::ButtonState::Pressed == state {
+                let window = self
+                    .space
+                    .output_under(self.pointer_location)
+                    .next()
+                    .cloned()
+                    .and_then(|output| {
+                        let output_geo = self.space.output_geometry(&output)?;
+                        Grid::new(&self.space, &output)?
+                            .window_under(self.pointer_location - output_geo.loc.to_f64())
+                            .cloned()
+                    });
+                self.overview_pick(window, serial);
+            }
+            return;
+        }
+
+        // Logo + left-drag moves any window, and Logo + right-drag
+        // resizes it -- wherever it's grabbed, without the client asking.
+        if wl_pointer::ButtonState::Pressed == state
+            && self.modifiers.logo
+            && self.start_window_drag(button, serial)
+        {
+            return;
+        }
+
         if wl_pointer::ButtonState::Pressed == state {
             self.update_keyboard_focus(serial);
         };
@@ -253,13 +554,8 @@ impl<BEnd: Backend> Navda<BEnd> {
                 .element_under(self.pointer_location)
                 .map(|(w, p)| (w.clone(), p))
             {
-                self.space.raise_element(&window, true);
                 input_method.set_point(&point);
-                keyboard.set_focus(self, Some(window.clone().into()), serial);
-                #[cfg(feature = "xwayland")]
-                if let WindowElement::X11(surf) = &window {
-                    self.xwm.as_mut().unwrap().raise_window(surf).unwrap();
-                }
+                self.focus_window(&window, self.focus_policy.raise_on_click, serial);
                 return;
             }
 
@@ -286,7 +582,49 @@ impl<BEnd: Backend> Navda<BEnd> {
         }
     }
 
+    ///
+    /// Moves keyboard focus to whatever window the pointer
+    /// has just moved onto -- a no-op in click-to-focus mode.
+    ///
+    /// Under `followsMouse` moving onto the desktop also drops focus,
+    /// whereas `sloppy` keeps the last window focused.
+    ///
+    fn update_focus_on_motion(&mut self, serial: Serial) {
+        if self.focus_policy.mode == FocusMode::Click || self.overview.is_some() {
+            return;
+        }
+
+        let pointer = self.seat.get_pointer().unwrap();
+        let keyboard = self.seat.get_keyboard().unwrap();
+        if pointer.is_grabbed() || keyboard.is_grabbed() {
+            return;
+        }
+
+        let current = keyboard.current_focus();
+        match self.surface_under() {
+            Some((FocusTarget::Window(window), _)) => {
+                if current.as_ref() != Some(&FocusTarget::Window(window.clone())) {
+                    self.focus_window(&window, self.focus_policy.raise_on_focus, serial);
+                }
+            }
+            None if self.focus_policy.mode == FocusMode::FollowsMouse => {
+                if let Some(FocusTarget::Window(_)) = current {
+                    keyboard.set_focus(self, None, serial);
+                }
+            }
+            // Layer surfaces (panels, the desktop background, ...)
+            // only ever take focus through a click.
+            _ => {}
+        }
+    }
+
     pub fn surface_under(&self) -> Option<(FocusTarget, Point<i32, Logical>)> {
+        // Windows aren't where they look to be
+        // while the overview is up.
+        if self.overview.is_some() {
+            return None;
+        }
+
         let pos = self.pointer_location;
         let output = self.space.outputs().find(|o| {
             let geometry = self.space.output_geometry(o).unwrap();
@@ -353,7 +691,7 @@ impl<BEnd: Backend> Navda<BEnd> {
     }
 }
 
-impl<BEnd: Backend> Navda<BEnd> {
+impl<BEnd: Backend + 'static> Navda<BEnd> {
     pub fn process_input_event_windowed<B: InputBackend>(
         &mut self,
         dh: &DisplayHandle,
@@ -371,16 +709,7 @@ impl<BEnd: Backend> Navda<BEnd> {
                         .clone();
 
                     let current_scale = output.current_scale().fractional_scale();
-                    let new_scale = current_scale + 0.25;
-                    output.change_current_state(
-                        None,
-                        None,
-                        Some(output::Scale::Fractional(new_scale)),
-                        None,
-                    );
-
-                    shell::fixup_positions(&mut self.space);
-                    self.backend_data.reset_buffers(&output);
+                    self.set_output_scale(&output, current_scale + SCALE_STEP);
                 }
 
                 KeyAction::ScaleDown => {
@@ -392,16 +721,7 @@ impl<BEnd: Backend> Navda<BEnd> {
                         .clone();
 
                     let current_scale = output.current_scale().fractional_scale();
-                    let new_scale = f64::max(1.0, current_scale - 0.25);
-                    output.change_current_state(
-                        None,
-                        None,
-                        Some(output::Scale::Fractional(new_scale)),
-                        None,
-                    );
-
-                    shell::fixup_positions(&mut self.space);
-                    self.backend_data.reset_buffers(&output);
+                    self.set_output_scale(&output, current_scale - SCALE_STEP);
                 }
 
                 KeyAction::RotateOutput => {
@@ -429,7 +749,23 @@ impl<BEnd: Backend> Navda<BEnd> {
                     KeyAction::None
                     | KeyAction::Quit
                     | KeyAction::Run(_)
-                    | KeyAction::TogglePreview => self.process_common_key_action(action),
+                    | KeyAction::TogglePreview
+                    | KeyAction::SwitchWindow { .. }
+                    | KeyAction::CancelSwitch
+                    | KeyAction::OverviewMove { .. }
+                    | KeyAction::OverviewActivate
+                    | KeyAction::MinimizeWindow
+                    | KeyAction::RestoreMinimized
+                    | KeyAction::MoveWindow { .. }
+                    | KeyAction::ResizeWindow { .. }
+                    | KeyAction::CenterWindow
+                    | KeyAction::ToggleMaximize
+                    | KeyAction::ToggleFullscreen
+                    | KeyAction::CloseWindow
+                    | KeyAction::FocusDirection(_) => self.process_common_key_action(action),
+
+                    // Needs a `'static` backend, for the clipboard's event sources.
+                    KeyAction::CycleClipboard => self.cycle_clipboard(),
 
                     _ => slog::warn!(
                         self.log,
@@ -477,6 +813,7 @@ impl<BEnd: Backend> Navda<BEnd> {
                 time: evt.time_msec(),
             },
         );
+        self.update_focus_on_motion(serial);
     }
 }
 
@@ -520,13 +857,7 @@ impl Navda<UdevData> {
                             self.space.output_geometry(&output).unwrap().loc,
                             output.current_scale().fractional_scale(),
                         );
-                        let new_scale = scale + 0.25;
-                        output.change_current_state(
-                            None,
-                            None,
-                            Some(output::Scale::Fractional(new_scale)),
-                            None,
-                        );
+                        let new_scale = round_scale(scale + SCALE_STEP);
 
                         let rescale = scale as f64 / new_scale as f64;
                         let output_location = output_location.to_f64();
@@ -535,7 +866,7 @@ impl Navda<UdevData> {
                         pointer_output_location.y *= rescale;
                         self.pointer_location = output_location + pointer_output_location;
 
-                        shell::fixup_positions(&mut self.space);
+                        self.set_output_scale(&output, new_scale);
                         let under = self.surface_under();
                         if let Some(ptr) = self.seat.get_pointer() {
                             ptr.motion(
@@ -548,7 +879,6 @@ impl Navda<UdevData> {
                                 },
                             );
                         }
-                        self.backend_data.reset_buffers(&output);
                     }
                 }
                 KeyAction::ScaleDown => {
@@ -564,13 +894,7 @@ impl Navda<UdevData> {
                             self.space.output_geometry(&output).unwrap().loc,
                             output.current_scale().fractional_scale(),
                         );
-                        let new_scale = f64::max(1.0, scale - 0.25);
-                        output.change_current_state(
-                            None,
-                            None,
-                            Some(output::Scale::Fractional(new_scale)),
-                            None,
-                        );
+                        let new_scale = round_scale(scale - SCALE_STEP);
 
                         let rescale = scale as f64 / new_scale as f64;
                         let output_location = output_location.to_f64();
@@ -579,7 +903,7 @@ impl Navda<UdevData> {
                         pointer_output_location.y *= rescale;
                         self.pointer_location = output_location + pointer_output_location;
 
-                        shell::fixup_positions(&mut self.space);
+                        self.set_output_scale(&output, new_scale);
                         let under = self.surface_under();
                         if let Some(ptr) = self.seat.get_pointer() {
                             ptr.motion(
@@ -592,7 +916,6 @@ impl Navda<UdevData> {
                                 },
                             );
                         }
-                        self.backend_data.reset_buffers(&output);
                     }
                 }
                 KeyAction::RotateOutput => {
@@ -622,7 +945,22 @@ impl Navda<UdevData> {
                     KeyAction::None
                     | KeyAction::Quit
                     | KeyAction::Run(_)
-                    | KeyAction::TogglePreview => self.process_common_key_action(action),
+                    | KeyAction::TogglePreview
+                    | KeyAction::SwitchWindow { .. }
+                    | KeyAction::CancelSwitch
+                    | KeyAction::OverviewMove { .. }
+                    | KeyAction::OverviewActivate
+                    | KeyAction::MinimizeWindow
+                    | KeyAction::RestoreMinimized
+                    | KeyAction::MoveWindow { .. }
+                    | KeyAction::ResizeWindow { .. }
+                    | KeyAction::CenterWindow
+                    | KeyAction::ToggleMaximize
+                    | KeyAction::ToggleFullscreen
+                    | KeyAction::CloseWindow
+                    | KeyAction::FocusDirection(_) => self.process_common_key_action(action),
+
+                    KeyAction::CycleClipboard => self.cycle_clipboard(),
 
                     _ => unreachable!(),
                 },
@@ -688,6 +1026,7 @@ impl Navda<UdevData> {
                 },
             );
         }
+        self.update_focus_on_motion(serial);
     }
 
     fn on_pointer_move_absolute<B: InputBackend>(
@@ -727,6 +1066,7 @@ impl Navda<UdevData> {
                 },
             );
         }
+        self.update_focus_on_motion(serial);
     }
 
     fn on_tablet_tool_axis<B: InputBackend>(&mut self, evt: B::TabletToolAxisEvent) {
@@ -883,7 +1223,7 @@ impl Navda<UdevData> {
 }
 
 /// Possible results of a keyboard action
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum KeyAction {
     /// Quit the compositor
     Quit,
@@ -897,10 +1237,53 @@ enum KeyAction {
     ScaleDown,
     TogglePreview,
     RotateOutput,
+    /// Step through windows in the window switcher
+    SwitchWindow { backwards: bool },
+    /// Close the window switcher without switching
+    CancelSwitch,
+    /// Minimize the focused window
+    MinimizeWindow,
+    /// Bring back the most recently minimized window
+    RestoreMinimized,
+    /// Move the overview's selection around its grid
+    OverviewMove { dx: i32, dy: i32 },
+    /// Close the overview, focusing the selected window
+    OverviewActivate,
+    /// Nudge the focused window by this much
+    MoveWindow { dx: i32, dy: i32 },
+    /// Grow (or shrink) the focused window by this much
+    ResizeWindow { dw: i32, dh: i32 },
+    /// Center the focused window on its output
+    CenterWindow,
+    /// Maximize the focused window, or restore it
+    ToggleMaximize,
+    /// Make the focused window fullscreen, or leave fullscreen
+    ToggleFullscreen,
+    /// Ask the focused window to close (or kill it, if it is hung)
+    CloseWindow,
+    /// Put the previous clipboard entry back on the clipboard
+    CycleClipboard,
+    /// Focus the nearest window in a direction
+    FocusDirection(Direction),
     /// Do nothing more
     None,
 }
 
+///
+/// Keys with a special meaning while the overview is open.
+///
+fn process_overview_shortcut(keysym: u32) -> Option<KeyAction> {
+    match keysym {
+        KEY_Left => Some(KeyAction::OverviewMove { dx: -1, dy: 0 }),
+        KEY_Right => Some(KeyAction::OverviewMove { dx: 1, dy: 0 }),
+        KEY_Up => Some(KeyAction::OverviewMove { dx: 0, dy: -1 }),
+        KEY_Down => Some(KeyAction::OverviewMove { dx: 0, dy: 1 }),
+        KEY_Return => Some(KeyAction::OverviewActivate),
+        KEY_Escape => Some(KeyAction::TogglePreview),
+        _ => None,
+    }
+}
+
 fn process_keyboard_shortcut(modifiers: ModifiersState, keysym: u32) -> Option<KeyAction> {
     match (modifiers, keysym) {
         (ModifiersState { ctrl: true, alt: true, .. }, KEY_BackSpace)
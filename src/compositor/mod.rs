@@ -2,13 +2,18 @@ use slog::{o, Drain, Logger};
 
 use self::backend::{run_udev, run_winit};
 
+mod animation;
 mod backend;
+mod clipboard;
 mod components;
 mod drawing;
+mod effects;
 mod focus;
 mod handlers;
 mod input;
+mod protocols;
 mod render;
+mod scale;
 mod shell;
 mod state;
 
@@ -0,0 +1,314 @@
+//!
+//! Lets clipboard tools (`wl-clipboard`, clipboard history managers, ...)
+//! read and set the selections without having a focused window:
+//!
+//! * [wlr Data Control](https://wayland.app/protocols/wlr-data-control-unstable-v1)
+//!
+//! The selections themselves are kept track of in [`ClipboardState`].
+//!
+//! That's a lot to trust any program with, so the global is only shown
+//! to the ones the `clipboard.dataControl` setting lets through --
+//! decided once, when they connect (see [`ClientState`]).
+//!
+//! [`ClientState`]: crate::compositor::state::ClientState
+//! [`ClipboardState`]: crate::compositor::clipboard::ClipboardState
+//!
+
+use std::{
+    fs,
+    os::unix::{net::UnixStream, prelude::AsRawFd},
+    sync::Mutex,
+};
+
+use smithay::reexports::{
+    nix::sys::socket::{getsockopt, sockopt::PeerCredentials},
+    wayland_protocols_wlr::data_control::v1::server::{
+        zwlr_data_control_device_v1::{self, ZwlrDataControlDeviceV1},
+        zwlr_data_control_manager_v1::{self, ZwlrDataControlManagerV1},
+        zwlr_data_control_offer_v1::{self, ZwlrDataControlOfferV1},
+        zwlr_data_control_source_v1::{self, ZwlrDataControlSourceV1},
+    },
+    wayland_server::{
+        backend::{ClientId, GlobalId, ObjectId},
+        Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New, Resource,
+    },
+};
+
+use crate::compositor::{
+    backend::Backend,
+    clipboard::{SelectionKind, SelectionSource},
+    state::{ClientState, Navda},
+};
+
+const VERSION: u32 = 2;
+
+///
+/// The first version with the primary selection.
+///
+const PRIMARY_SELECTION_VERSION: u32 = 2;
+
+///
+/// The programs `tools` lets at the clipboard, by executable name.
+///
+const CLIPBOARD_TOOLS: [&str; 7] = [
+    "wl-copy",
+    "wl-paste",
+    "wl-clip-persist",
+    "cliphist",
+    "clipman",
+    "clipse",
+    "copyq",
+];
+
+///
+/// Which clients get to see the global.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataControlAccess {
+    ///
+    /// Only known clipboard tools ([`CLIPBOARD_TOOLS`]).
+    ///
+    Tools,
+    All,
+    None,
+}
+
+impl From<&str> for DataControlAccess {
+    fn from(value: &str) -> Self {
+        match value {
+            "all" => Self::All,
+            "none" => Self::None,
+            _ => Self::Tools,
+        }
+    }
+}
+
+impl DataControlAccess {
+    ///
+    /// Whether the program on the other end of
+    /// `stream` (just connected) gets to see the global.
+    ///
+    pub fn allows(&self, stream: &UnixStream) -> bool {
+        match self {
+            Self::All => true,
+            Self::None => false,
+            Self::Tools => {
+                program_name(stream).map_or(false, |name| CLIPBOARD_TOOLS.contains(&name.as_str()))
+            }
+        }
+    }
+}
+
+///
+/// The executable's name of the program on the other end of `stream`.
+///
+fn program_name(stream: &UnixStream) -> Option<String> {
+    let credentials = getsockopt(stream.as_raw_fd(), PeerCredentials).ok()?;
+    let exe = fs::read_link(format!("/proc/{}/exe", credentials.pid())).ok()?;
+
+    Some(exe.file_name()?.to_string_lossy().into_owned())
+}
+
+///
+/// A clipboard tool's data source -- what it has to offer.
+///
+#[derive(Debug, Default)]
+pub struct DataControlSource {
+    mime_types: Mutex<Vec<String>>,
+}
+
+impl DataControlSource {
+    pub fn mime_types(&self) -> Vec<String> {
+        self.mime_types.lock().unwrap().clone()
+    }
+}
+
+///
+/// Which selection an offer was made for.
+///
+#[derive(Debug)]
+pub struct DataControlOffer {
+    kind: SelectionKind,
+    serial: u64,
+}
+
+///
+/// Every clipboard tool's data device.
+///
+#[derive(Debug)]
+pub struct DataControlState {
+    _global: GlobalId,
+    devices: Vec<ZwlrDataControlDeviceV1>,
+}
+
+impl DataControlState {
+    pub fn new<D>(dh: &DisplayHandle) -> Self
+    where
+        D: GlobalDispatch<ZwlrDataControlManagerV1, ()> + 'static,
+    {
+        Self {
+            _global: dh.create_global::<D, ZwlrDataControlManagerV1, _>(VERSION, ()),
+            devices: Vec::new(),
+        }
+    }
+}
+
+impl<BEnd: Backend + 'static> Navda<BEnd> {
+    ///
+    /// Tell every clipboard tool about the current `kind` selection.
+    ///
+    pub fn offer_selection_to_data_control(&self, kind: SelectionKind) {
+        for device in &self.data_control_state.devices {
+            self.offer_selection(device, kind);
+        }
+    }
+
+    fn offer_selection(&self, device: &ZwlrDataControlDeviceV1, kind: SelectionKind) {
+        if kind == SelectionKind::Primary && device.version() < PRIMARY_SELECTION_VERSION {
+            return;
+        }
+
+        let (serial, source) = self.clipboard_state.current(kind);
+        let offer = source.zip(device.client()).and_then(|(source, client)| {
+            let offer = client
+                .create_resource::<ZwlrDataControlOfferV1, _, Self>(
+                    &self.display_handle,
+                    device.version(),
+                    DataControlOffer { kind, serial },
+                )
+                .ok()?;
+
+            device.data_offer(&offer);
+            for mime_type in source.mime_types() {
+                offer.offer(mime_type);
+            }
+
+            Some(offer)
+        });
+
+        match kind {
+            SelectionKind::Clipboard => device.selection(offer.as_ref()),
+            SelectionKind::Primary => device.primary_selection(offer.as_ref()),
+        }
+    }
+}
+
+impl<BEnd: Backend + 'static> GlobalDispatch<ZwlrDataControlManagerV1, ()> for Navda<BEnd> {
+    fn can_view(client: Client, _global_data: &()) -> bool {
+        client
+            .get_data::<ClientState>()
+            .map_or(false, |state| state.data_control)
+    }
+
+    fn bind(
+        _state: &mut Self,
+        _dh: &DisplayHandle,
+        _client: &Client,
+        resource: New<ZwlrDataControlManagerV1>,
+        _global_data: &(),
+        data_init: &mut DataInit<'_, Self>,
+    ) {
+        data_init.init(resource, ());
+    }
+}
+
+impl<BEnd: Backend + 'static> Dispatch<ZwlrDataControlManagerV1, ()> for Navda<BEnd> {
+    fn request(
+        state: &mut Self,
+        _client: &Client,
+        _resource: &ZwlrDataControlManagerV1,
+        request: zwlr_data_control_manager_v1::Request,
+        _data: &(),
+        _dh: &DisplayHandle,
+        data_init: &mut DataInit<'_, Self>,
+    ) {
+        use zwlr_data_control_manager_v1::Request;
+
+        match request {
+            Request::CreateDataSource { id } => {
+                data_init.init(id, DataControlSource::default());
+            }
+            // There's only the one seat.
+            Request::GetDataDevice { id, .. } => {
+                let device = data_init.init(id, ());
+
+                state.offer_selection(&device, SelectionKind::Clipboard);
+                state.offer_selection(&device, SelectionKind::Primary);
+                state.data_control_state.devices.push(device);
+            }
+            Request::Destroy => {}
+            _ => {}
+        }
+    }
+}
+
+impl<BEnd: Backend + 'static> Dispatch<ZwlrDataControlDeviceV1, ()> for Navda<BEnd> {
+    fn request(
+        state: &mut Self,
+        _client: &Client,
+        _resource: &ZwlrDataControlDeviceV1,
+        request: zwlr_data_control_device_v1::Request,
+        _data: &(),
+        _dh: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Self>,
+    ) {
+        use zwlr_data_control_device_v1::Request;
+
+        match request {
+            Request::SetSelection { source } => state.set_selection(
+                SelectionKind::Clipboard,
+                source.map(SelectionSource::DataControl),
+            ),
+            Request::SetPrimarySelection { source } => state.set_selection(
+                SelectionKind::Primary,
+                source.map(SelectionSource::DataControl),
+            ),
+            Request::Destroy => {}
+            _ => {}
+        }
+    }
+
+    fn destroyed(state: &mut Self, _client: ClientId, resource: ObjectId, _data: &()) {
+        state
+            .data_control_state
+            .devices
+            .retain(|d| d.id() != resource);
+    }
+}
+
+impl<BEnd: Backend + 'static> Dispatch<ZwlrDataControlSourceV1, DataControlSource> for Navda<BEnd> {
+    fn request(
+        _state: &mut Self,
+        _client: &Client,
+        _resource: &ZwlrDataControlSourceV1,
+        request: zwlr_data_control_source_v1::Request,
+        data: &DataControlSource,
+        _dh: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Self>,
+    ) {
+        if let zwlr_data_control_source_v1::Request::Offer { mime_type } = request {
+            data.mime_types.lock().unwrap().push(mime_type);
+        }
+    }
+
+    // Once it's gone, `refresh_clipboard` takes over its selection.
+}
+
+impl<BEnd: Backend + 'static> Dispatch<ZwlrDataControlOfferV1, DataControlOffer> for Navda<BEnd> {
+    fn request(
+        state: &mut Self,
+        _client: &Client,
+        _resource: &ZwlrDataControlOfferV1,
+        request: zwlr_data_control_offer_v1::Request,
+        offer: &DataControlOffer,
+        _dh: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Self>,
+    ) {
+        if let zwlr_data_control_offer_v1::Request::Receive { mime_type, fd } = request {
+            // Offers for old selections get nothing -- dropping `fd` closes it.
+            if state.clipboard_state.current(offer.kind).0 == offer.serial {
+                state.write_selection(offer.kind, mime_type, fd);
+            }
+        }
+    }
+}
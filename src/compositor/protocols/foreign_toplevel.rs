@@ -0,0 +1,526 @@
+//!
+//! Lets taskbars and docks list every window,
+//! and control them:
+//!
+//! * [wlr Foreign Toplevel Management](https://wayland.app/protocols/wlr-foreign-toplevel-management-unstable-v1)
+//! * [Foreign Toplevel List](https://wayland.app/protocols/ext-foreign-toplevel-list-v1) (list only)
+//!
+
+use smithay::{
+    output::Output,
+    reexports::{
+        wayland_protocols_wlr::foreign_toplevel::v1::server::{
+            zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+            zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+        },
+        wayland_server::{
+            backend::{ClientId, GlobalId, ObjectId},
+            Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New, Resource,
+        },
+    },
+    utils::{IsAlive, SERIAL_COUNTER},
+};
+
+use crate::compositor::{backend::Backend, focus::FocusTarget, shell::AvWindow, state::Navda};
+
+use super::ext_foreign_toplevel_list::{
+    ext_foreign_toplevel_handle_v1::{self, ExtForeignToplevelHandleV1},
+    ext_foreign_toplevel_list_v1::{self, ExtForeignToplevelListV1},
+};
+
+const WLR_VERSION: u32 = 3;
+const EXT_VERSION: u32 = 1;
+
+///
+/// Everything a taskbar gets told about a window.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ToplevelInfo {
+    title: String,
+    app_id: String,
+    maximized: bool,
+    minimized: bool,
+    activated: bool,
+    fullscreen: bool,
+    outputs: Vec<Output>,
+}
+
+impl ToplevelInfo {
+    ///
+    /// The `state` array of the wlr protocol.
+    ///
+    fn wlr_states(&self) -> Vec<u8> {
+        use zwlr_foreign_toplevel_handle_v1::State;
+
+        [
+            (self.maximized, State::Maximized),
+            (self.minimized, State::Minimized),
+            (self.activated, State::Activated),
+            (self.fullscreen, State::Fullscreen),
+        ]
+        .into_iter()
+        .filter(|(set, _)| *set)
+        .flat_map(|(_, state)| (state as u32).to_ne_bytes())
+        .collect()
+    }
+}
+
+#[derive(Debug)]
+struct Toplevel {
+    window: AvWindow,
+
+    ///
+    /// Unique for the whole session -- also
+    /// the ext protocol's `identifier`.
+    ///
+    id: u64,
+
+    ///
+    /// What clients were last told.
+    ///
+    info: ToplevelInfo,
+
+    wlr_handles: Vec<ZwlrForeignToplevelHandleV1>,
+    ext_handles: Vec<ExtForeignToplevelHandleV1>,
+}
+
+impl Toplevel {
+    ///
+    /// Tell a wlr client about changes since `old`
+    /// (or everything, for a fresh handle).
+    ///
+    fn send_wlr(
+        handle: &ZwlrForeignToplevelHandleV1,
+        info: &ToplevelInfo,
+        old: Option<&ToplevelInfo>,
+    ) {
+        if old.map(|o| o.title != info.title).unwrap_or(true) {
+            handle.title(info.title.clone());
+        }
+        if old.map(|o| o.app_id != info.app_id).unwrap_or(true) {
+            handle.app_id(info.app_id.clone());
+        }
+
+        if let Some(client) = handle.client() {
+            let old_outputs = old.map(|o| o.outputs.as_slice()).unwrap_or_default();
+
+            for output in old_outputs.iter().filter(|o| !info.outputs.contains(o)) {
+                for wl_output in output.client_outputs(&client) {
+                    handle.output_leave(&wl_output);
+                }
+            }
+            for output in info.outputs.iter().filter(|o| !old_outputs.contains(o)) {
+                for wl_output in output.client_outputs(&client) {
+                    handle.output_enter(&wl_output);
+                }
+            }
+        }
+
+        if old
+            .map(|o| o.wlr_states() != info.wlr_states())
+            .unwrap_or(true)
+        {
+            handle.state(info.wlr_states());
+        }
+
+        handle.done();
+    }
+
+    ///
+    /// Same as `send_wlr` -- the ext protocol only has
+    /// the title and app_id to update though.
+    ///
+    fn send_ext(
+        handle: &ExtForeignToplevelHandleV1,
+        info: &ToplevelInfo,
+        old: Option<&ToplevelInfo>,
+    ) {
+        let changed = match old {
+            None => true,
+            Some(o) => o.title != info.title || o.app_id != info.app_id,
+        };
+
+        if !changed {
+            return;
+        }
+
+        if old.map(|o| o.title != info.title).unwrap_or(true) {
+            handle.title(info.title.clone());
+        }
+        if old.map(|o| o.app_id != info.app_id).unwrap_or(true) {
+            handle.app_id(info.app_id.clone());
+        }
+
+        handle.done();
+    }
+
+    fn announce_wlr<BEnd: Backend + 'static>(
+        &mut self,
+        dh: &DisplayHandle,
+        manager: &ZwlrForeignToplevelManagerV1,
+    ) {
+        let handle = manager.client().and_then(|client| {
+            client
+                .create_resource::<ZwlrForeignToplevelHandleV1, _, Navda<BEnd>>(
+                    dh,
+                    manager.version(),
+                    self.id,
+                )
+                .ok()
+        });
+
+        if let Some(handle) = handle {
+            manager.toplevel(&handle);
+            Self::send_wlr(&handle, &self.info, None);
+            self.wlr_handles.push(handle);
+        }
+    }
+
+    fn announce_ext<BEnd: Backend + 'static>(
+        &mut self,
+        dh: &DisplayHandle,
+        list: &ExtForeignToplevelListV1,
+    ) {
+        let handle = list.client().and_then(|client| {
+            client
+                .create_resource::<ExtForeignToplevelHandleV1, _, Navda<BEnd>>(
+                    dh,
+                    list.version(),
+                    self.id,
+                )
+                .ok()
+        });
+
+        if let Some(handle) = handle {
+            list.toplevel(&handle);
+            handle.identifier(format!("{:016x}", self.id));
+            Self::send_ext(&handle, &self.info, None);
+            self.ext_handles.push(handle);
+        }
+    }
+
+    fn closed(&self) {
+        for handle in &self.wlr_handles {
+            handle.closed();
+        }
+        for handle in &self.ext_handles {
+            handle.closed();
+        }
+    }
+}
+
+///
+/// Clients bound to either protocol,
+/// and the windows they've been told about.
+///
+#[derive(Debug)]
+pub struct ForeignToplevelState {
+    _wlr_global: GlobalId,
+    _ext_global: GlobalId,
+    wlr_managers: Vec<ZwlrForeignToplevelManagerV1>,
+    ext_lists: Vec<ExtForeignToplevelListV1>,
+    toplevels: Vec<Toplevel>,
+    next_id: u64,
+}
+
+impl ForeignToplevelState {
+    pub fn new<D>(dh: &DisplayHandle) -> Self
+    where
+        D: GlobalDispatch<ZwlrForeignToplevelManagerV1, ()>
+            + GlobalDispatch<ExtForeignToplevelListV1, ()>
+            + 'static,
+    {
+        Self {
+            _wlr_global: dh.create_global::<D, ZwlrForeignToplevelManagerV1, _>(WLR_VERSION, ()),
+            _ext_global: dh.create_global::<D, ExtForeignToplevelListV1, _>(EXT_VERSION, ()),
+            wlr_managers: Vec::new(),
+            ext_lists: Vec::new(),
+            toplevels: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    fn window(&self, id: u64) -> Option<AvWindow> {
+        self.toplevels
+            .iter()
+            .find(|t| t.id == id)
+            .map(|t| t.window.clone())
+    }
+}
+
+impl<BEnd: Backend + 'static> Navda<BEnd> {
+    fn toplevel_info(&self, window: &AvWindow, focus: Option<&FocusTarget>) -> ToplevelInfo {
+        ToplevelInfo {
+            title: window.title().unwrap_or_default(),
+            app_id: window.app_id().unwrap_or_default(),
+            maximized: window.is_maximized(),
+            minimized: window.is_minimized(),
+            activated: focus == Some(&FocusTarget::Window(window.clone())),
+            fullscreen: window.is_fullscreen(),
+            outputs: self.space.outputs_for_element(window),
+        }
+    }
+
+    ///
+    /// Bring taskbars up to date with the current windows
+    /// -- called once per event loop iteration.
+    ///
+    pub fn refresh_foreign_toplevels(&mut self) {
+        let dh = self.display_handle.clone();
+        let focus = self.seat.get_keyboard().unwrap().current_focus();
+
+        // Override-redirect windows are menus, tooltips
+        // and the like -- not something for a taskbar.
+        let windows = self
+            .space
+            .elements()
+            .chain(self.minimized.iter())
+            .filter(|w| !matches!(w, AvWindow::X11(x) if x.is_override_redirect()))
+            .filter(|w| w.alive())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let infos = windows
+            .iter()
+            .map(|w| self.toplevel_info(w, focus.as_ref()))
+            .collect::<Vec<_>>();
+
+        let ForeignToplevelState {
+            wlr_managers,
+            ext_lists,
+            toplevels,
+            next_id,
+            ..
+        } = &mut self.foreign_toplevel_state;
+
+        toplevels.retain(|toplevel| {
+            let keep = windows.contains(&toplevel.window);
+            if !keep {
+                toplevel.closed();
+            }
+            keep
+        });
+
+        for (window, info) in windows.into_iter().zip(infos) {
+            match toplevels.iter_mut().find(|t| t.window == window) {
+                Some(toplevel) if toplevel.info != info => {
+                    for handle in &toplevel.wlr_handles {
+                        Toplevel::send_wlr(handle, &info, Some(&toplevel.info));
+                    }
+                    for handle in &toplevel.ext_handles {
+                        Toplevel::send_ext(handle, &info, Some(&toplevel.info));
+                    }
+                    toplevel.info = info;
+                }
+                Some(_) => {}
+                None => {
+                    let mut toplevel = Toplevel {
+                        window,
+                        id: *next_id,
+                        info,
+                        wlr_handles: Vec::new(),
+                        ext_handles: Vec::new(),
+                    };
+                    *next_id += 1;
+
+                    for manager in wlr_managers.iter() {
+                        toplevel.announce_wlr::<BEnd>(&dh, manager);
+                    }
+                    for list in ext_lists.iter() {
+                        toplevel.announce_ext::<BEnd>(&dh, list);
+                    }
+
+                    toplevels.push(toplevel);
+                }
+            }
+        }
+    }
+}
+
+impl<BEnd: Backend + 'static> GlobalDispatch<ZwlrForeignToplevelManagerV1, ()> for Navda<BEnd> {
+    fn bind(
+        state: &mut Self,
+        dh: &DisplayHandle,
+        _client: &Client,
+        resource: New<ZwlrForeignToplevelManagerV1>,
+        _global_data: &(),
+        data_init: &mut DataInit<'_, Self>,
+    ) {
+        let manager = data_init.init(resource, ());
+
+        let ForeignToplevelState {
+            wlr_managers,
+            toplevels,
+            ..
+        } = &mut state.foreign_toplevel_state;
+
+        for toplevel in toplevels.iter_mut() {
+            toplevel.announce_wlr::<BEnd>(dh, &manager);
+        }
+        wlr_managers.push(manager);
+    }
+}
+
+impl<BEnd: Backend + 'static> Dispatch<ZwlrForeignToplevelManagerV1, ()> for Navda<BEnd> {
+    fn request(
+        state: &mut Self,
+        _client: &Client,
+        resource: &ZwlrForeignToplevelManagerV1,
+        request: zwlr_foreign_toplevel_manager_v1::Request,
+        _data: &(),
+        _dh: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Self>,
+    ) {
+        if let zwlr_foreign_toplevel_manager_v1::Request::Stop = request {
+            state
+                .foreign_toplevel_state
+                .wlr_managers
+                .retain(|m| m != resource);
+            resource.finished();
+        }
+    }
+
+    fn destroyed(state: &mut Self, _client: ClientId, resource: ObjectId, _data: &()) {
+        state
+            .foreign_toplevel_state
+            .wlr_managers
+            .retain(|m| m.id() != resource);
+    }
+}
+
+impl<BEnd: Backend + 'static> Dispatch<ZwlrForeignToplevelHandleV1, u64> for Navda<BEnd> {
+    fn request(
+        state: &mut Self,
+        _client: &Client,
+        _resource: &ZwlrForeignToplevelHandleV1,
+        request: zwlr_foreign_toplevel_handle_v1::Request,
+        id: &u64,
+        _dh: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Self>,
+    ) {
+        use zwlr_foreign_toplevel_handle_v1::Request;
+
+        let window = match state.foreign_toplevel_state.window(*id) {
+            Some(window) if window.alive() => window,
+            _ => return,
+        };
+
+        // Maximizing, fullscreening, ... needs the window on screen.
+        if matches!(
+            request,
+            Request::SetMaximized
+                | Request::UnsetMaximized
+                | Request::SetFullscreen { .. }
+                | Request::UnsetFullscreen
+        ) && window.is_minimized()
+        {
+            state.restore_window(&window);
+        }
+
+        match request {
+            Request::SetMaximized => state.maximize_window(&window, true),
+            Request::UnsetMaximized => state.maximize_window(&window, false),
+            Request::SetMinimized => state.minimize_window(&window),
+            Request::UnsetMinimized => state.restore_window(&window),
+            Request::Activate { .. } if window.is_minimized() => state.restore_window(&window),
+            Request::Activate { .. } => {
+                state.focus_window(&window, true, SERIAL_COUNTER.next_serial())
+            }
+            Request::Close => window.send_close(),
+            Request::SetFullscreen { output } => state.fullscreen_window(&window, output, true),
+            Request::UnsetFullscreen => state.fullscreen_window(&window, None, false),
+            // We don't animate minimizing (yet),
+            // so there's no use for the taskbar's rectangle.
+            Request::SetRectangle { .. } | Request::Destroy => {}
+            _ => {}
+        }
+    }
+
+    fn destroyed(state: &mut Self, _client: ClientId, resource: ObjectId, id: &u64) {
+        if let Some(toplevel) = state
+            .foreign_toplevel_state
+            .toplevels
+            .iter_mut()
+            .find(|t| t.id == *id)
+        {
+            toplevel.wlr_handles.retain(|h| h.id() != resource);
+        }
+    }
+}
+
+impl<BEnd: Backend + 'static> GlobalDispatch<ExtForeignToplevelListV1, ()> for Navda<BEnd> {
+    fn bind(
+        state: &mut Self,
+        dh: &DisplayHandle,
+        _client: &Client,
+        resource: New<ExtForeignToplevelListV1>,
+        _global_data: &(),
+        data_init: &mut DataInit<'_, Self>,
+    ) {
+        let list = data_init.init(resource, ());
+
+        let ForeignToplevelState {
+            ext_lists,
+            toplevels,
+            ..
+        } = &mut state.foreign_toplevel_state;
+
+        for toplevel in toplevels.iter_mut() {
+            toplevel.announce_ext::<BEnd>(dh, &list);
+        }
+        ext_lists.push(list);
+    }
+}
+
+impl<BEnd: Backend + 'static> Dispatch<ExtForeignToplevelListV1, ()> for Navda<BEnd> {
+    fn request(
+        state: &mut Self,
+        _client: &Client,
+        resource: &ExtForeignToplevelListV1,
+        request: ext_foreign_toplevel_list_v1::Request,
+        _data: &(),
+        _dh: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Self>,
+    ) {
+        if let ext_foreign_toplevel_list_v1::Request::Stop = request {
+            state
+                .foreign_toplevel_state
+                .ext_lists
+                .retain(|l| l != resource);
+            resource.finished();
+        }
+    }
+
+    fn destroyed(state: &mut Self, _client: ClientId, resource: ObjectId, _data: &()) {
+        state
+            .foreign_toplevel_state
+            .ext_lists
+            .retain(|l| l.id() != resource);
+    }
+}
+
+impl<BEnd: Backend + 'static> Dispatch<ExtForeignToplevelHandleV1, u64> for Navda<BEnd> {
+    fn request(
+        _state: &mut Self,
+        _client: &Client,
+        _resource: &ExtForeignToplevelHandleV1,
+        request: ext_foreign_toplevel_handle_v1::Request,
+        _id: &u64,
+        _dh: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Self>,
+    ) {
+        // Only `destroy`, handled below.
+        let _ = request;
+    }
+
+    fn destroyed(state: &mut Self, _client: ClientId, resource: ObjectId, id: &u64) {
+        if let Some(toplevel) = state
+            .foreign_toplevel_state
+            .toplevels
+            .iter_mut()
+            .find(|t| t.id == *id)
+        {
+            toplevel.ext_handles.retain(|h| h.id() != resource);
+        }
+    }
+}
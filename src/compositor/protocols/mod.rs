@@ -0,0 +1,31 @@
+//!
+//! Wayland protocols Navda implements itself,
+//! rather than through smithay.
+//!
+
+pub mod data_control;
+pub mod foreign_toplevel;
+
+///
+/// Server-side bindings for `ext-foreign-toplevel-list-v1`,
+/// which our version of `wayland-protocols` predates.
+///
+#[allow(dead_code, non_camel_case_types, unused_unsafe, unused_variables)]
+#[allow(non_upper_case_globals, non_snake_case, unused_imports)]
+#[allow(missing_docs, clippy::all)]
+pub mod ext_foreign_toplevel_list {
+    use smithay::reexports::wayland_server::{self, protocol::*};
+
+    pub mod __interfaces {
+        use smithay::reexports::wayland_server::{
+            backend as wayland_backend, protocol::__interfaces::*,
+        };
+
+        wayland_scanner::generate_interfaces!(
+            "resources/protocols/ext-foreign-toplevel-list-v1.xml"
+        );
+    }
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_server_code!("resources/protocols/ext-foreign-toplevel-list-v1.xml");
+}
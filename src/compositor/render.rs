@@ -2,36 +2,68 @@ use smithay::{
     backend::renderer::{
         damage::{DamageTrackedRenderer, DamageTrackedRendererError, DamageTrackedRendererMode},
         element::{
-            surface::WaylandSurfaceRenderElement, utils::CropRenderElement, AsRenderElements,
-            RenderElementStates,
+            surface::WaylandSurfaceRenderElement, texture::TextureRenderElement,
+            utils::CropRenderElement, AsRenderElements, RenderElementStates,
         },
         ImportAll, ImportMem, Renderer,
     },
-    desktop::{self, Space},
+    desktop::{
+        layer_map_for_output,
+        space::{space_render_elements, SpaceElement, SpaceRenderElements},
+        Space,
+    },
     output::Output,
     render_elements,
-    utils::{Physical, Rectangle},
+    utils::{Logical, Physical, Rectangle},
+    wayland::shell::wlr_layer::Layer as WlrLayer,
 };
 
 use super::{
+    animation::ClosingWindow,
+    components::{
+        tile_preview_elements, ConfigBanner, Overview, OverviewRenderElement,
+        SwitcherRenderElement, Wallpaper, WindowSwitcher,
+    },
     drawing::{PointerRenderElement, CLEAR_COLOR},
+    effects::EffectsRenderer,
     shell::{AvWindow, AvWindowRenderElement, FullscreenSurface},
 };
 
 render_elements! {
     pub CustomRenderElements<R> where
-        R: ImportAll + ImportMem;
+        R: ImportAll + ImportMem + EffectsRenderer;
     Pointer=PointerRenderElement<R>,
     Surface=WaylandSurfaceRenderElement<R>,
     Window=AvWindowRenderElement<R>,
+    Switcher=SwitcherRenderElement<R>,
+    Overview=OverviewRenderElement<R>,
+    Texture=TextureRenderElement<<R as Renderer>::TextureId>,
 }
 
 render_elements! {
     pub OutputRenderElements<'a, R> where
-        R: ImportAll + ImportMem;
+        R: ImportAll + ImportMem + EffectsRenderer;
+    Space=SpaceRenderElements<R, AvWindowRenderElement<R>>,
     Custom=&'a CustomRenderElements<R>,
 }
 
+///
+/// Everything drawn besides the space itself
+/// -- the state of a single frame.
+///
+pub struct RenderExtras<'a, R>
+where
+    R: ImportAll + ImportMem + EffectsRenderer,
+{
+    pub custom_elements: &'a [CustomRenderElements<R>],
+    pub switcher: Option<&'a WindowSwitcher>,
+    pub overview: Option<&'a Overview>,
+    pub tile_preview: Option<Rectangle<i32, Logical>>,
+    pub closing_windows: &'a [ClosingWindow],
+    pub wallpaper: &'a Wallpaper,
+    pub banner: Option<&'a ConfigBanner>,
+}
+
 ///
 /// Big boi render function -- render everything:
 /// all elements; to the output.
@@ -39,25 +71,150 @@ render_elements! {
 pub fn render_output<'a, R>(
     output: &Output,
     space: &'a Space<AvWindow>,
-    custom_elements: &'a [CustomRenderElements<R>],
+    extras: RenderExtras<'a, R>,
     renderer: &mut R,
     damage_tracked_renderer: &mut DamageTrackedRenderer,
     age: usize,
-    // NOTE: review code for window preview for the
-    // "App view" thing.
-    // show_window_preview: bool,
     log: &slog::Logger,
 ) -> Result<
     (Option<Vec<Rectangle<i32, Physical>>>, RenderElementStates),
     DamageTrackedRendererError<R>,
 >
 where
-    R: Renderer + ImportAll + ImportMem,
+    R: Renderer + ImportAll + ImportMem + EffectsRenderer,
     R::TextureId: Clone + 'static,
 {
     let output_scale = output.current_scale().fractional_scale().into();
+    let RenderExtras {
+        custom_elements,
+        switcher,
+        overview,
+        tile_preview,
+        closing_windows,
+        wallpaper,
+        banner,
+    } = extras;
+
+    // Drawn over everything but the pointer -- the banner on top.
+    let mut overlay_elements = banner
+        .zip(space.output_geometry(output))
+        .map(|(banner, output_geo)| {
+            banner.render_elements::<R, CustomRenderElements<R>>(
+                renderer,
+                output_geo.size,
+                output_scale,
+                log,
+            )
+        })
+        .unwrap_or_default();
 
-    if let Some(window) = output
+    overlay_elements.extend(
+        switcher
+            .zip(space.output_geometry(output))
+            .map(|(switcher, output_geo)| {
+                switcher.render_elements::<R, CustomRenderElements<R>>(
+                    renderer,
+                    output_geo.size,
+                    output_scale,
+                    log,
+                )
+            })
+            .unwrap_or_default(),
+    );
+
+    if let Some((preview, output_geo)) = tile_preview.zip(space.output_geometry(output)) {
+        if preview.overlaps(output_geo) {
+            overlay_elements.extend(tile_preview_elements::<R, CustomRenderElements<R>>(
+                renderer,
+                Rectangle::from_loc_and_size(preview.loc - output_geo.loc, preview.size),
+                output_scale,
+                log,
+            ));
+        }
+    }
+
+    // Windows on their way out, over the ones still around
+    // -- drawn from the textures they were last drawn into.
+    let closing_elements = space
+        .output_geometry(output)
+        .map(|output_geo| {
+            closing_windows
+                .iter()
+                .filter(|closing| closing.geometry.overlaps(output_geo))
+                .flat_map(|closing| {
+                    let loc = closing.geometry.loc - closing.window.geometry().loc - output_geo.loc;
+                    AsRenderElements::<R>::render_elements::<CustomRenderElements<R>>(
+                        &closing.window,
+                        renderer,
+                        loc.to_physical_precise_round(output_scale),
+                        output_scale,
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    // Beneath everything -- unless a layer-shell background takes its place.
+    let has_background = layer_map_for_output(output)
+        .layers_on(WlrLayer::Background)
+        .next()
+        .is_some();
+
+    let wallpaper_elements = match space.output_geometry(output) {
+        Some(output_geo) if !has_background => wallpaper
+            .render_elements::<R, CustomRenderElements<R>>(
+                renderer,
+                output_geo.size,
+                output_scale,
+                log,
+            ),
+        _ => Vec::new(),
+    };
+
+    if let Some(overview) = overview {
+        let overview_elements = overview.render_elements::<R, CustomRenderElements<R>>(
+            renderer,
+            space,
+            output,
+            output_scale,
+            log,
+        );
+
+        // Only the desktop stays put behind the overview.
+        let layer_elements = {
+            let layer_map = layer_map_for_output(output);
+            [WlrLayer::Bottom, WlrLayer::Background]
+                .into_iter()
+                .flat_map(|layer| layer_map.layers_on(layer).rev())
+                .flat_map(|surface| {
+                    let loc = layer_map.layer_geometry(surface).unwrap().loc;
+                    AsRenderElements::<R>::render_elements::<CustomRenderElements<R>>(
+                        surface,
+                        renderer,
+                        loc.to_physical_precise_round(output_scale),
+                        output_scale,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let render_elements = custom_elements
+            .iter()
+            .chain(overlay_elements.iter())
+            .chain(closing_elements.iter())
+            .chain(overview_elements.iter())
+            .chain(layer_elements.iter())
+            .chain(wallpaper_elements.iter())
+            .collect::<Vec<_>>();
+
+        damage_tracked_renderer.render_output(
+            renderer,
+            age,
+            &render_elements,
+            CLEAR_COLOR,
+            log.clone(),
+        )
+    } else if let Some(window) = output
         .user_data()
         .get::<FullscreenSurface>()
         .and_then(|f| f.get())
@@ -71,7 +228,10 @@ where
 
         let render_elements = custom_elements
             .iter()
+            .chain(overlay_elements.iter())
+            .chain(closing_elements.iter())
             .chain(window_render_elements.iter())
+            .chain(wallpaper_elements.iter())
             .collect::<Vec<_>>();
 
         damage_tracked_renderer.render_output(
@@ -82,20 +242,25 @@ where
             log.clone(),
         )
     } else {
-        let output_render_elements = custom_elements
+        if let DamageTrackedRendererMode::Auto(renderer_output) = damage_tracked_renderer.mode() {
+            assert!(renderer_output == output);
+        }
+
+        let space_elements = space_render_elements(renderer, [space], output);
+
+        let render_elements = custom_elements
             .iter()
+            .chain(overlay_elements.iter())
+            .chain(closing_elements.iter())
             .map(OutputRenderElements::from)
+            .chain(space_elements.into_iter().map(OutputRenderElements::from))
+            .chain(wallpaper_elements.iter().map(OutputRenderElements::from))
             .collect::<Vec<_>>();
 
-        // SNIP : Window Previews
-
-        desktop::space::render_output(
-            output,
+        damage_tracked_renderer.render_output(
             renderer,
             age,
-            [space],
-            &output_render_elements,
-            damage_tracked_renderer,
+            &render_elements,
             CLEAR_COLOR,
             log.clone(),
         )
@@ -0,0 +1,192 @@
+//!
+//! Output scales -- picked from each output's DPI when it's
+//! connected, and passed on to every surface on it when they change.
+//!
+//! Clients that bind `wp_fractional_scale_v1` are sent the exact scale;
+//! the rest only see `wl_output.scale`, rounded up. (`wl_surface.preferred_buffer_scale`
+//! needs `wl_compositor` v6, which our version of smithay doesn't have.)
+//!
+//! XWayland can't scale its clients, so X11 windows draw at scale 1
+//! and get stretched -- blurry, but the right size.
+//!
+
+use smithay::{
+    desktop::{layer_map_for_output, utils::surface_primary_scanout_output},
+    output::{Mode, Output, Scale as OutputScale},
+    reexports::wayland_server::protocol::wl_surface::WlSurface,
+    utils::{Physical, Raw, Size},
+    wayland::{compositor::SurfaceData, fractional_scale::with_fractional_scale},
+};
+
+use crate::config::sections::scaling::Scaling;
+
+use super::{
+    backend::Backend,
+    shell::{self, AvWindow, FullscreenSurface},
+    state::Navda,
+};
+
+///
+/// Scales are kept to multiples of this
+/// -- anything finer just gives blurry edges.
+///
+pub const SCALE_STEP: f64 = 0.25;
+
+const MIN_SCALE: f64 = 1.0;
+const MAX_SCALE: f64 = 3.0;
+
+///
+/// What projectors and TVs tend to put in their EDID instead
+/// of a real size (in mm) -- just their aspect ratio.
+///
+const ASPECT_RATIO_SIZES: [(i32, i32); 3] = [(160, 90), (160, 100), (160, 120)];
+
+const MM_PER_INCH: f64 = 25.4;
+
+///
+/// Round `scale` to a [`SCALE_STEP`],
+/// between 1x and 3x.
+///
+pub fn round_scale(scale: f64) -> f64 {
+    ((scale / SCALE_STEP).round() * SCALE_STEP).clamp(MIN_SCALE, MAX_SCALE)
+}
+
+///
+/// How outputs are scaled when they're connected,
+/// read from the `scaling` config section.
+///
+#[derive(Debug, Clone)]
+pub struct ScalePolicy {
+    pub auto: bool,
+    pub target_dpi: f64,
+}
+
+impl Default for ScalePolicy {
+    fn default() -> Self {
+        Self {
+            auto: true,
+            target_dpi: 96.0,
+        }
+    }
+}
+
+impl ScalePolicy {
+    pub fn from_config(scaling: &Scaling, log: &slog::Logger) -> Self {
+        let default = Self::default();
+
+        let target_dpi = match scaling.targetDpi {
+            dpi if dpi > 0 => dpi as f64,
+            dpi => {
+                slog::warn!(
+                    log,
+                    "Invalid target DPI `{}`, using {}.",
+                    dpi,
+                    default.target_dpi
+                );
+                default.target_dpi
+            }
+        };
+
+        Self {
+            auto: scaling.auto,
+            target_dpi,
+        }
+    }
+
+    ///
+    /// The scale for an output `physical` (mm) across, showing `mode`
+    /// -- 1x if it's turned off, or the output's size is unknown (or made up).
+    ///
+    pub fn scale_for(&self, physical: Size<i32, Raw>, mode: Size<i32, Physical>) -> f64 {
+        if !self.auto
+            || physical.w <= 0
+            || physical.h <= 0
+            || ASPECT_RATIO_SIZES.contains(&(physical.w, physical.h))
+        {
+            return MIN_SCALE;
+        }
+
+        let dpi = mode.w as f64 / (physical.w as f64 / MM_PER_INCH);
+        round_scale(dpi / self.target_dpi)
+    }
+
+    ///
+    /// [`Self::scale_for`] `output`, in `mode`.
+    ///
+    pub fn scale_for_output(&self, output: &Output, mode: Mode) -> f64 {
+        self.scale_for(output.physical_properties().size, mode.size)
+    }
+}
+
+impl<BEnd: Backend> Navda<BEnd> {
+    ///
+    /// Change `output`'s scale (rounded with [`round_scale`]),
+    /// and let everything on it know.
+    ///
+    pub fn set_output_scale(&mut self, output: &Output, scale: f64) {
+        let scale = round_scale(scale);
+        if output.current_scale().fractional_scale() == scale {
+            return;
+        }
+
+        // Sends `wl_output.scale` (rounded up) to clients that bound the output.
+        output.change_current_state(None, None, Some(OutputScale::Fractional(scale)), None);
+        shell::fixup_positions(&mut self.space);
+        self.send_preferred_scale(output);
+
+        // The output's logical size just changed, so whatever fills it has to follow.
+        let fullscreen = output
+            .user_data()
+            .get::<FullscreenSurface>()
+            .and_then(|f| f.get());
+        if let Some(window) = fullscreen {
+            self.fullscreen_window(&window, None, true);
+        }
+        self.refit_maximized_windows();
+        self.reconfigure_x11_windows();
+
+        self.backend_data.reset_buffers(output);
+    }
+
+    ///
+    /// Send `output`'s scale to every surface shown (mostly) on it -- those
+    /// that haven't been shown anywhere yet go by where their window is.
+    ///
+    fn send_preferred_scale(&self, output: &Output) {
+        let scale = output.current_scale().fractional_scale();
+        let send = |on_output: bool| {
+            move |surface: &WlSurface, states: &SurfaceData| {
+                let primary = surface_primary_scanout_output(surface, states);
+                if primary.map(|o| o == *output).unwrap_or(on_output) {
+                    with_fractional_scale(states, |fractional_scale| {
+                        fractional_scale.set_preferred_scale(scale);
+                    });
+                }
+            }
+        };
+
+        for window in self.space.elements().chain(self.minimized.iter()) {
+            let on_output = self.space.outputs_for_element(window).first() == Some(output);
+            window.with_surfaces(send(on_output));
+        }
+
+        for layer in layer_map_for_output(output).layers() {
+            layer.with_surfaces(send(true));
+        }
+    }
+
+    ///
+    /// Tell X11 windows where they are -- [`shell::fixup_positions`]
+    /// only moves them around the `Space`, and X11 clients keep track
+    /// of their own (root window) position.
+    ///
+    fn reconfigure_x11_windows(&self) {
+        for window in self.space.elements() {
+            if let AvWindow::X11(surface) = window {
+                if let Err(err) = surface.configure(self.space.element_bbox(window)) {
+                    slog::warn!(self.log, "Failed to reconfigure X11 window: {}", err);
+                }
+            }
+        }
+    }
+}
@@ -10,7 +10,12 @@ use std::time::Duration;
 
 use smithay::{
     backend::renderer::{
-        element::{surface::WaylandSurfaceRenderElement, AsRenderElements},
+        element::{
+            surface::{render_elements_from_surface_tree, WaylandSurfaceRenderElement},
+            texture::TextureRenderElement,
+            utils::{Relocate, RelocateRenderElement, RescaleRenderElement},
+            AsRenderElements,
+        },
         ImportAll, ImportMem, Renderer, Texture,
     },
     desktop::{
@@ -19,7 +24,7 @@ use smithay::{
             send_frames_surface_tree, take_presentation_feedback_surface_tree,
             under_from_surface_tree, with_surfaces_surface_tree, OutputPresentationFeedback,
         },
-        Window, WindowSurfaceType,
+        PopupManager, Window, WindowSurfaceType,
     },
     input::{
         keyboard::{KeyboardTarget, KeysymHandle},
@@ -28,18 +33,36 @@ use smithay::{
     },
     output::Output,
     reexports::{
-        wayland_protocols::wp::presentation_time::server::wp_presentation_feedback,
+        wayland_protocols::{
+            wp::presentation_time::server::wp_presentation_feedback,
+            xdg::shell::server::xdg_toplevel,
+        },
         wayland_server::protocol::wl_surface::WlSurface,
     },
     render_elements,
-    utils::{user_data::UserDataMap, IsAlive, Logical, Physical, Point, Rectangle, Scale, Serial},
+    utils::{
+        user_data::UserDataMap, IsAlive, Logical, Physical, Point, Rectangle, Scale, Serial, Size,
+    },
     wayland::{
-        compositor::SurfaceData as WlSurfaceData, seat::WaylandFocus, shell::xdg::ToplevelSurface,
+        compositor::{with_states, SurfaceData as WlSurfaceData},
+        seat::WaylandFocus,
+        shell::xdg::{ToplevelSurface, XdgToplevelSurfaceData},
     },
     xwayland::X11Surface,
 };
 
-use crate::compositor::{backend::Backend, state::Navda};
+use crate::compositor::{
+    backend::Backend,
+    effects::{
+        effect_element, offscreen, Effect, EffectsRenderer, OffscreenElement, ShaderElement,
+    },
+    state::Navda,
+};
+
+///
+/// Laid over windows whose client has stopped responding.
+///
+const UNRESPONSIVE_SHADE: [f32; 4] = [0.1, 0.1, 0.1, 0.5];
 
 ///
 /// Abstraction over X11 and Wayland Windows.
@@ -179,6 +202,40 @@ impl AvWindow {
         }
     }
 
+    ///
+    /// The window's popups, with the window at `location`
+    /// -- X11 windows' menus are windows of their own.
+    ///
+    fn popup_render_elements<R>(
+        &self,
+        renderer: &mut R,
+        location: Point<i32, Physical>,
+        scale: Scale<f64>,
+    ) -> Vec<WaylandSurfaceRenderElement<R>>
+    where
+        R: Renderer + ImportAll,
+        <R as Renderer>::TextureId: Texture + 'static,
+    {
+        let Self::Wayland(w) = self else {
+            return Vec::new();
+        };
+
+        PopupManager::popups_for_surface(w.toplevel().wl_surface())
+            .flat_map(|(popup, popup_offset)| {
+                let offset = (w.geometry().loc + popup_offset - popup.geometry().loc)
+                    .to_physical_precise_round(scale);
+
+                render_elements_from_surface_tree(
+                    renderer,
+                    popup.wl_surface(),
+                    location + offset,
+                    scale,
+                    None,
+                )
+            })
+            .collect()
+    }
+
     ///
     /// Shows user_map data &mdash; a type-based
     /// key-value system for storing arbitrary info
@@ -190,6 +247,78 @@ impl AvWindow {
             Self::X11(w) => w.user_data(),
         }
     }
+
+    ///
+    /// The window's title, as set by the client.
+    ///
+    pub fn title(&self) -> Option<String> {
+        match self {
+            Self::Wayland(w) => with_states(w.toplevel().wl_surface(), |states| {
+                states
+                    .data_map
+                    .get::<XdgToplevelSurfaceData>()?
+                    .lock()
+                    .unwrap()
+                    .title
+                    .clone()
+            }),
+            Self::X11(w) => Some(w.title()).filter(|t| !t.is_empty()),
+        }
+    }
+
+    ///
+    /// The application this window belongs to:
+    /// its `app_id` under Wayland, its `WM_CLASS` under X11.
+    ///
+    pub fn app_id(&self) -> Option<String> {
+        match self {
+            Self::Wayland(w) => with_states(w.toplevel().wl_surface(), |states| {
+                states
+                    .data_map
+                    .get::<XdgToplevelSurfaceData>()?
+                    .lock()
+                    .unwrap()
+                    .app_id
+                    .clone()
+            }),
+            Self::X11(w) => Some(w.class()).filter(|c| !c.is_empty()),
+        }
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        match self {
+            Self::Wayland(w) => w
+                .toplevel()
+                .current_state()
+                .states
+                .contains(xdg_toplevel::State::Maximized),
+            Self::X11(w) => w.is_maximized(),
+        }
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        match self {
+            Self::Wayland(w) => w
+                .toplevel()
+                .current_state()
+                .states
+                .contains(xdg_toplevel::State::Fullscreen),
+            Self::X11(w) => w.is_fullscreen(),
+        }
+    }
+
+    ///
+    /// Ask the client to close this window
+    /// -- it may still refuse, or ask the user first.
+    ///
+    pub fn send_close(&self) {
+        match self {
+            Self::Wayland(w) => w.toplevel().send_close(),
+            Self::X11(w) => {
+                let _ = w.close();
+            }
+        }
+    }
 }
 
 ///
@@ -446,14 +575,18 @@ impl SpaceElement for AvWindow {
 }
 
 render_elements!(
-    pub AvWindowRenderElement<R> where R : ImportAll + ImportMem;
+    pub AvWindowRenderElement<R> where R : ImportAll + ImportMem + EffectsRenderer;
     Window=WaylandSurfaceRenderElement<R>,
+    Animated=RelocateRenderElement<RescaleRenderElement<WaylandSurfaceRenderElement<R>>>,
+    Shader=ShaderElement,
+    Offscreen=OffscreenElement,
+    Texture=TextureRenderElement<<R as Renderer>::TextureId>,
 );
 
 impl<R> AsRenderElements<R> for AvWindow
 where
-    R: Renderer + ImportAll + ImportMem,
-    <R as Renderer>::TextureId: Texture + 'static,
+    R: Renderer + ImportAll + ImportMem + EffectsRenderer,
+    <R as Renderer>::TextureId: Texture + Clone + 'static,
 {
     type RenderElement = AvWindowRenderElement<R>;
 
@@ -469,15 +602,109 @@ where
 
         let width = window_geo.size.w;
 
-        let mut vec = match self {
-            Self::Wayland(xdg) => {
+        let effects = self.effects();
+        let transform = self.render_transform();
+        let opacity = effects.opacity * transform.map_or(1.0, |t| t.opacity);
+
+        // The window's own surfaces, from its texture if it's been drawn
+        // into one -- with its popups drawn as usual, over it.
+        let origin = location + window_geo.loc.to_physical_precise_round(scale);
+        let snapshot = offscreen::element(self, origin, transform, opacity, scale);
+
+        // Windows drawn as they are keep their square corners,
+        // so they don't get a rounded shadow (or shade) either.
+        let radius = match snapshot.is_some() {
+            // Scaled along with the texture they're cut out of.
+            true => {
+                self.corner_radius(window_geo.size) as f64 * transform.map_or(1.0, |t| t.scale.x)
+            }
+            false => 0.0,
+        } as f32;
+        let shadow = effects.shadow.filter(|_| snapshot.is_some());
+
+        let surfaces: Vec<WaylandSurfaceRenderElement<R>> = match (self, &snapshot) {
+            (_, Some(_)) => self.popup_render_elements(renderer, location, scale),
+            (Self::Wayland(xdg), None) => {
                 AsRenderElements::<R>::render_elements(xdg, renderer, location, scale)
             }
-            Self::X11(xdg) => {
+            (Self::X11(xdg), None) => {
                 AsRenderElements::<R>::render_elements(xdg, renderer, location, scale)
             }
         };
 
+        // Mid-animation, scale from the top-left of the
+        // window's geometry, then shift it into place.
+        let mut vec: Vec<AvWindowRenderElement<R>> = match transform {
+            Some(transform) => {
+                let offset = transform.offset.to_physical_precise_round(scale);
+
+                surfaces
+                    .into_iter()
+                    .map(|surface| {
+                        AvWindowRenderElement::Animated(RelocateRenderElement::from_element(
+                            RescaleRenderElement::from_element(surface, origin, transform.scale),
+                            offset,
+                            Relocate::Relative,
+                        ))
+                    })
+                    .collect()
+            }
+            None => surfaces
+                .into_iter()
+                .map(AvWindowRenderElement::Window)
+                .collect(),
+        };
+
+        vec.extend(snapshot.map(AvWindowRenderElement::Offscreen));
+
+        // Maximized and fullscreen windows sit flush against
+        // the output's edges -- nothing to round or shadow there.
+        let laid_out = self.is_maximized() || self.is_fullscreen();
+
+        // Where the window's geometry is drawn, relative to the output.
+        let mut frame = Rectangle::from_loc_and_size(
+            location.to_f64().to_logical(scale).to_i32_round() + window_geo.loc,
+            window_geo.size,
+        );
+
+        if let Some(transform) = transform {
+            frame.loc += transform.offset.to_i32_round();
+            frame.size = frame.size.to_f64().upscale(transform.scale).to_i32_round();
+        }
+
+        let log = self.logger();
+
+        // Grey out hung windows.
+        if self.is_unresponsive() {
+            let shade = Effect::RoundedRect {
+                radius,
+                color: UNRESPONSIVE_SHADE,
+            };
+
+            if let Some(shade) = effect_element(renderer, self, shade, frame, opacity, scale, &log)
+            {
+                vec.insert(0, shade);
+            }
+        }
+
+        if let Some(shadow) = shadow.filter(|_| !laid_out) {
+            let spread = shadow.size;
+            let rect = Rectangle::from_loc_and_size(
+                frame.loc - Point::from((spread, spread)),
+                frame.size + Size::from((2 * spread, 2 * spread)),
+            );
+
+            let shadow = Effect::Shadow {
+                radius,
+                spread: spread as f32,
+                color: shadow.color,
+            };
+
+            vec.extend(effect_element(
+                renderer, self, shadow, rect, opacity, scale, &log,
+            ));
+        }
+
         // TODO(@Sammy99jsp)
         // Custom rendering logic here for window decorations.
 
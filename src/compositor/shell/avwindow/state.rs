@@ -1 +1,283 @@
+//!
+//! Window state the compositor keeps for itself,
+//! stored in each window's `user_data`.
+//!
 
+use std::cell::RefCell;
+
+use smithay::utils::{Logical, Point, Rectangle, Size};
+
+use crate::compositor::{
+    animation::{Animation, RenderTransform},
+    effects::WindowEffects,
+};
+
+use super::{super::rules::AppliedRules, AvWindow};
+
+///
+/// The compositor's logger, for what's
+/// logged from where there's none at hand.
+///
+#[derive(Debug)]
+struct LoggerState(slog::Logger);
+
+impl AvWindow {
+    ///
+    /// Logs nowhere until [`AvWindow::set_logger`] is called.
+    ///
+    pub fn logger(&self) -> slog::Logger {
+        self.user_data()
+            .get::<LoggerState>()
+            .map(|state| state.0.clone())
+            .unwrap_or_else(|| slog::Logger::root(slog::Discard, slog::o!()))
+    }
+
+    pub fn set_logger(&self, log: &slog::Logger) {
+        self.user_data()
+            .insert_if_missing(|| LoggerState(log.clone()));
+    }
+}
+
+///
+/// Where a minimized window was before it was
+/// unmapped from the `Space` -- `None` if it isn't minimized.
+///
+#[derive(Debug, Default)]
+struct MinimizedState(RefCell<Option<Point<i32, Logical>>>);
+
+impl AvWindow {
+    ///
+    /// Is this window minimized (hidden, but still alive) ?
+    ///
+    pub fn is_minimized(&self) -> bool {
+        self.user_data()
+            .get::<MinimizedState>()
+            .map(|state| state.0.borrow().is_some())
+            .unwrap_or(false)
+    }
+
+    ///
+    /// Mark this window as minimized, remembering
+    /// the `location` to restore it to.
+    ///
+    pub fn set_minimized(&self, location: Point<i32, Logical>) {
+        self.user_data().insert_if_missing(MinimizedState::default);
+        *self
+            .user_data()
+            .get::<MinimizedState>()
+            .unwrap()
+            .0
+            .borrow_mut() = Some(location);
+    }
+
+    ///
+    /// Clear the minimized state, returning
+    /// where the window should go back to.
+    ///
+    pub fn take_minimized(&self) -> Option<Point<i32, Logical>> {
+        self.user_data()
+            .get::<MinimizedState>()
+            .and_then(|state| state.0.borrow_mut().take())
+    }
+}
+
+///
+/// The window rules matched when the window first appeared
+/// -- `None` if they haven't been applied yet.
+///
+#[derive(Debug, Default)]
+struct WindowRulesState(RefCell<Option<AppliedRules>>);
+
+impl AvWindow {
+    pub fn window_rules(&self) -> Option<AppliedRules> {
+        self.user_data()
+            .get::<WindowRulesState>()
+            .and_then(|state| state.0.borrow().clone())
+    }
+
+    pub fn set_window_rules(&self, rules: AppliedRules) {
+        self.user_data()
+            .insert_if_missing(WindowRulesState::default);
+        *self
+            .user_data()
+            .get::<WindowRulesState>()
+            .unwrap()
+            .0
+            .borrow_mut() = Some(rules);
+    }
+}
+
+///
+/// Whether the window's been animated open -- which waits
+/// for its first buffer, rather than its first commit.
+///
+#[derive(Debug, Default)]
+struct OpenedState(RefCell<bool>);
+
+impl AvWindow {
+    pub fn has_opened(&self) -> bool {
+        self.user_data()
+            .get::<OpenedState>()
+            .map(|state| *state.0.borrow())
+            .unwrap_or(false)
+    }
+
+    pub fn set_opened(&self) {
+        self.user_data().insert_if_missing(OpenedState::default);
+        *self
+            .user_data()
+            .get::<OpenedState>()
+            .unwrap()
+            .0
+            .borrow_mut() = true;
+    }
+}
+
+///
+/// Where a window was before it was maximized.
+///
+#[derive(Debug, Default)]
+struct OldGeometry(RefCell<Option<Rectangle<i32, Logical>>>);
+
+impl AvWindow {
+    ///
+    /// Remember `geo` to restore this window to when it's unmaximized.
+    ///
+    /// Doesn't overwrite an earlier one -- re-maximizing an
+    /// already maximized window (to fit around new panels, say)
+    /// still restores to where it was first.
+    ///
+    pub fn save_geometry(&self, geo: Rectangle<i32, Logical>) {
+        self.user_data().insert_if_missing(OldGeometry::default);
+        self.user_data()
+            .get::<OldGeometry>()
+            .unwrap()
+            .0
+            .borrow_mut()
+            .get_or_insert(geo);
+    }
+
+    pub fn take_saved_geometry(&self) -> Option<Rectangle<i32, Logical>> {
+        self.user_data()
+            .get::<OldGeometry>()
+            .and_then(|old| old.0.borrow_mut().take())
+    }
+}
+
+///
+/// Whether the window's client has stopped answering pings.
+///
+#[derive(Debug, Default)]
+struct UnresponsiveState(RefCell<bool>);
+
+impl AvWindow {
+    ///
+    /// Has this window's client stopped answering pings
+    /// (see [`Navda::ping_clients`](crate::compositor::state::Navda::ping_clients)) ?
+    ///
+    pub fn is_unresponsive(&self) -> bool {
+        self.user_data()
+            .get::<UnresponsiveState>()
+            .map(|state| *state.0.borrow())
+            .unwrap_or(false)
+    }
+
+    pub fn set_unresponsive(&self, unresponsive: bool) {
+        self.user_data()
+            .insert_if_missing(UnresponsiveState::default);
+        *self
+            .user_data()
+            .get::<UnresponsiveState>()
+            .unwrap()
+            .0
+            .borrow_mut() = unresponsive;
+    }
+}
+
+///
+/// The animation the window is part-way through, if any.
+///
+#[derive(Debug, Default)]
+struct AnimationState(RefCell<Option<Animation>>);
+
+///
+/// Where (and how) the window's drawn this frame,
+/// relative to where it really is -- `None` to draw it as is.
+///
+#[derive(Debug, Default)]
+struct RenderTransformState(RefCell<Option<RenderTransform>>);
+
+impl AvWindow {
+    pub fn animation(&self) -> Option<Animation> {
+        self.user_data()
+            .get::<AnimationState>()
+            .and_then(|state| state.0.borrow().clone())
+    }
+
+    pub fn set_animation(&self, animation: Option<Animation>) {
+        self.user_data().insert_if_missing(AnimationState::default);
+        *self
+            .user_data()
+            .get::<AnimationState>()
+            .unwrap()
+            .0
+            .borrow_mut() = animation;
+    }
+
+    pub fn render_transform(&self) -> Option<RenderTransform> {
+        self.user_data()
+            .get::<RenderTransformState>()
+            .and_then(|state| *state.0.borrow())
+    }
+
+    pub fn set_render_transform(&self, transform: Option<RenderTransform>) {
+        self.user_data()
+            .insert_if_missing(RenderTransformState::default);
+        *self
+            .user_data()
+            .get::<RenderTransformState>()
+            .unwrap()
+            .0
+            .borrow_mut() = transform;
+    }
+}
+
+///
+/// How the window's drawn (see [`WindowEffects`]).
+///
+#[derive(Debug, Default)]
+struct EffectsState(RefCell<WindowEffects>);
+
+impl AvWindow {
+    ///
+    /// No effects, until the window rules are applied.
+    ///
+    pub fn effects(&self) -> WindowEffects {
+        self.user_data()
+            .get::<EffectsState>()
+            .map(|state| *state.0.borrow())
+            .unwrap_or_default()
+    }
+
+    pub fn set_effects(&self, effects: WindowEffects) {
+        self.user_data().insert_if_missing(EffectsState::default);
+        *self
+            .user_data()
+            .get::<EffectsState>()
+            .unwrap()
+            .0
+            .borrow_mut() = effects;
+    }
+
+    ///
+    /// How far the window's corners are rounded off, at `size` -- not
+    /// at all while it sits flush against the output's edges.
+    ///
+    pub fn corner_radius(&self, size: Size<i32, Logical>) -> i32 {
+        if self.is_maximized() || self.is_fullscreen() {
+            return 0;
+        }
+
+        self.effects().corner_radius.min(size.w / 2).min(size.h / 2)
+    }
+}
@@ -1,12 +1,20 @@
 use smithay::{
+    desktop::space::SpaceElement,
     input::{
-        pointer::{self, GrabStartData as PointerGrabStartData, PointerGrab},
+        pointer::{self, Focus, GrabStartData as PointerGrabStartData, PointerGrab},
         SeatHandler,
     },
-    utils::{Logical, Point},
+    utils::{Logical, Point, Rectangle, Serial},
 };
 
-use crate::compositor::{backend::Backend, shell::avwindow::AvWindow, state::Navda};
+use crate::compositor::{
+    backend::Backend,
+    shell::{
+        avwindow::AvWindow,
+        snapping::{snap_location, tile_target},
+    },
+    state::Navda,
+};
 
 pub struct MoveSurfaceGrab<B: Backend + 'static> {
     pub start_data: PointerGrabStartData<Navda<B>>,
@@ -14,6 +22,25 @@ pub struct MoveSurfaceGrab<B: Backend + 'static> {
     pub initial_window_location: Point<i32, Logical>,
 }
 
+impl<BEnd: Backend> MoveSurfaceGrab<BEnd> {
+    ///
+    /// Shrink the window back to `old_geo`'s size, keeping
+    /// the pointer at the same spot across the window.
+    ///
+    fn untile(&mut self, data: &mut Navda<BEnd>, old_geo: Rectangle<i32, Logical>) {
+        let width = self.window.geometry().size.w.max(1);
+        let grab_x = self.start_data.location.x - self.initial_window_location.x as f64;
+        let grab_x = grab_x * old_geo.size.w as f64 / width as f64;
+
+        self.initial_window_location.x = (self.start_data.location.x - grab_x).round() as i32;
+
+        data.set_window_geometry(
+            &self.window,
+            Rectangle::from_loc_and_size(self.initial_window_location, old_geo.size),
+        );
+    }
+}
+
 impl<BEnd: Backend> PointerGrab<Navda<BEnd>> for MoveSurfaceGrab<BEnd> {
     fn motion(
         &mut self,
@@ -28,11 +55,34 @@ impl<BEnd: Backend> PointerGrab<Navda<BEnd>> for MoveSurfaceGrab<BEnd> {
         // While grab is active, no client has pointer focus.
         handle.motion(data, None, event);
 
+        // Dragging a tiled window out of its tile
+        // gives it back its old size.
+        if !self.window.is_maximized() {
+            if let Some(old_geo) = self.window.take_saved_geometry() {
+                self.untile(data, old_geo);
+            }
+        }
+
         let delta = event.location - self.start_data.location;
-        let new_location = self.initial_window_location.to_f64() + delta;
+        let new_location = (self.initial_window_location.to_f64() + delta).to_i32_round();
+
+        let policy = data.snap_policy.clone();
 
-        data.space
-            .map_element(self.window.clone(), new_location.to_i32_round(), true);
+        data.tile_preview = if policy.edge_tiling {
+            tile_target(&data.space, event.location).map(|(_, geometry)| geometry)
+        } else {
+            None
+        };
+
+        // No snapping while about to tile -- the window's going elsewhere anyway.
+        let location = if data.tile_preview.is_some() {
+            new_location
+        } else {
+            let rect = Rectangle::from_loc_and_size(new_location, self.window.geometry().size);
+            snap_location(&data.space, &self.window, rect, policy.threshold)
+        };
+
+        data.space.map_element(self.window.clone(), location, true);
     }
 
     fn button(
@@ -45,6 +95,13 @@ impl<BEnd: Backend> PointerGrab<Navda<BEnd>> for MoveSurfaceGrab<BEnd> {
         if handle.current_pressed().is_empty() {
             // No more buttons are pressed, release grab.
             handle.unset_grab(data, event.serial, event.time);
+
+            if data.tile_preview.take().is_some() {
+                if let Some((zone, geometry)) = tile_target(&data.space, handle.current_location())
+                {
+                    data.tile_window(&self.window, zone, geometry);
+                }
+            }
         }
     }
 
@@ -76,3 +133,35 @@ impl<BEnd: Backend> PointerGrab<Navda<BEnd>> for MoveSurfaceGrab<BEnd> {
         // TODO(Sammy99jsp) possibly find another use for this.
     }
 }
+
+impl<BEnd: Backend> Navda<BEnd> {
+    ///
+    /// Start dragging `window` around, without the client
+    /// having asked for it (e.g. Logo + left-drag).
+    ///
+    pub fn start_move_grab(&mut self, window: AvWindow, button: u32, serial: Serial) {
+        let pointer = self.seat.get_pointer().unwrap();
+        let Some(mut initial_window_location) = self.space.element_location(&window) else {
+            return;
+        };
+
+        // Maximized windows come out at their
+        // old size, right under the pointer.
+        if window.is_maximized() {
+            self.maximize_window(&window, false);
+            initial_window_location = pointer.current_location().to_i32_round();
+        }
+
+        let grab = MoveSurfaceGrab {
+            start_data: PointerGrabStartData {
+                focus: None,
+                button,
+                location: pointer.current_location(),
+            },
+            window,
+            initial_window_location,
+        };
+
+        pointer.set_grab(self, grab, serial, Focus::Clear);
+    }
+}
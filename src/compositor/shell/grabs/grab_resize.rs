@@ -7,7 +7,7 @@ use std::cell::RefCell;
 use smithay::{
     desktop::{space::SpaceElement, Space},
     input::{
-        pointer::{self, GrabStartData as PointerGrabStartData, PointerGrab},
+        pointer::{self, Focus, GrabStartData as PointerGrabStartData, PointerGrab},
         SeatHandler,
     },
     reexports::{
@@ -83,6 +83,20 @@ impl From<xwayland::xwm::ResizeEdge> for ResizeEdge {
     }
 }
 
+impl ResizeEdge {
+    ///
+    /// The corner of `rect` closest to `point`.
+    ///
+    pub fn nearest_corner(rect: Rectangle<i32, Logical>, point: Point<f64, Logical>) -> Self {
+        let center = rect.loc.to_f64() + rect.size.to_f64().downscale(2.0).to_point();
+
+        let horizontal = if point.x < center.x { Self::LEFT } else { Self::RIGHT };
+        let vertical = if point.y < center.y { Self::TOP } else { Self::BOTTOM };
+
+        horizontal | vertical
+    }
+}
+
 //
 /// Data associated with resize grabbing
 ///
@@ -319,6 +333,52 @@ impl<BEnd: Backend> PointerGrab<Navda<BEnd>> for ResizeSurfaceGrab<BEnd> {
     }
 }
 
+impl<BEnd: Backend> Navda<BEnd> {
+    ///
+    /// Start resizing `window` from the corner nearest the pointer,
+    /// without the client having asked for it (e.g. Logo + right-drag).
+    ///
+    pub fn start_resize_grab(&mut self, window: AvWindow, button: u32, serial: Serial) {
+        // Maximized windows have nowhere to grow.
+        if window.is_maximized() {
+            return;
+        }
+
+        let pointer = self.seat.get_pointer().unwrap();
+        let Some(location) = self.space.element_location(&window) else { return };
+        let Some(surface) = window.wl_surface() else { return };
+
+        let initial_rect = Rectangle::from_loc_and_size(location, window.geometry().size);
+        let edges = ResizeEdge::nearest_corner(initial_rect, pointer.current_location());
+
+        compositor::with_states(&surface, |states| {
+            states
+                .data_map
+                .get::<RefCell<SurfaceData>>()
+                .unwrap()
+                .borrow_mut()
+                .resize_state = ResizeState::Resizing(ResizeData {
+                edges,
+                initial_rect,
+            });
+        });
+
+        let grab = ResizeSurfaceGrab {
+            start_data: PointerGrabStartData {
+                focus: None,
+                button,
+                location: pointer.current_location(),
+            },
+            window,
+            edges,
+            initial_rect,
+            last_window_size: initial_rect.size,
+        };
+
+        pointer.set_grab(self, grab, serial, Focus::Clear);
+    }
+}
+
 ///
 /// Apply any location fixes to the
 /// window before it is drawn
@@ -4,12 +4,12 @@ use smithay::{
     backend::renderer::utils::on_commit_buffer_handler,
     delegate_layer_shell, delegate_shm, delegate_viewporter,
     desktop::{
-        layer_map_for_output, space::SpaceElement, LayerSurface, PopupKind, PopupManager, Space,
-        WindowSurfaceType,
+        layer_map_for_output, space::SpaceElement, utils::bbox_from_surface_tree, LayerSurface,
+        PopupKind, PopupManager, Space, WindowSurfaceType,
     },
     output::Output,
     reexports::wayland_server::protocol::{wl_buffer::WlBuffer, wl_output, wl_surface::WlSurface},
-    utils::{Logical, Point, Rectangle, Size},
+    utils::{IsAlive, Logical, Point, Rectangle, Size, SERIAL_COUNTER},
     wayland::{
         buffer::BufferHandler,
         compositor::{
@@ -21,7 +21,7 @@ use smithay::{
                 Layer, LayerSurface as WlrLayerSurface, LayerSurfaceData, WlrLayerShellHandler,
                 WlrLayerShellState,
             },
-            xdg::{XdgPopupSurfaceData, XdgToplevelSurfaceData},
+            xdg::{XdgPopupSurfaceData, XdgShellHandler, XdgToplevelSurfaceData},
         },
         shm::ShmHandler,
     },
@@ -30,16 +30,24 @@ use smithay::{
 
 pub use self::avwindow::{AvWindow, AvWindowRenderElement};
 pub use self::grabs::handle_commit;
+pub use self::rules::{AppliedRules, WindowRules};
+pub use self::snapping::SnapPolicy;
+pub use self::xconn::XConnection;
 use self::grabs::ResizeState;
 
 use super::{
     backend::Backend,
+    focus::FocusTarget,
     state::{CalloopData, Navda},
 };
 
 mod avwindow;
 mod grabs;
+mod ping;
+mod rules;
+mod snapping;
 mod x11;
+mod xconn;
 mod xdg;
 
 #[derive(Default)]
@@ -89,6 +97,26 @@ impl FullscreenSurface {
     }
 }
 
+///
+/// What an output's usable area was when we last looked,
+/// to notice panels coming, going or resizing.
+///
+#[derive(Default)]
+struct UsableArea(RefCell<Option<Rectangle<i32, Logical>>>);
+
+///
+/// The part of `output` not taken by panels (exclusive zones)
+/// -- where maximized windows go.
+///
+pub fn usable_geometry(
+    space: &Space<AvWindow>,
+    output: &Output,
+) -> Option<Rectangle<i32, Logical>> {
+    let geo = space.output_geometry(output)?;
+    let zone = layer_map_for_output(output).non_exclusive_zone();
+    Some(Rectangle::from_loc_and_size(geo.loc + zone.loc, zone.size))
+}
+
 impl<BEnd: Backend> BufferHandler for Navda<BEnd> {
     fn buffer_destroyed(&mut self, _buffer: &WlBuffer) {}
 }
@@ -115,6 +143,20 @@ impl<BEnd: Backend> CompositorHandler for Navda<BEnd> {
         self.popups.commit(surface);
         handle_commit(&mut self.space, surface);
 
+        if let Some(window @ AvWindow::Wayland(_)) = self.window_for_surface(surface) {
+            // By the first commit, the app_id and title are set.
+            if window.window_rules().is_none() {
+                self.apply_window_rules(&window);
+            }
+
+            // There's nothing to animate until a buffer is attached.
+            let size = bbox_from_surface_tree(surface, (0, 0)).size;
+            if size.w > 0 && size.h > 0 && !window.has_opened() {
+                window.set_opened();
+                self.animate_open(&window);
+            }
+        }
+
         ensure_initial_configure(surface, &self.space, &mut self.popups)
     }
 }
@@ -144,12 +186,168 @@ impl<BEnd: Backend> WlrLayerShellHandler for Navda<BEnd> {
 delegate_layer_shell!(@<BEnd : Backend + 'static> Navda<BEnd>);
 
 impl<BEnd: Backend> Navda<BEnd> {
+    ///
+    /// Find the window for `surface`,
+    /// including minimized ones.
+    ///
     pub fn window_for_surface(&self, surface: &WlSurface) -> Option<AvWindow> {
         self.space
             .elements()
+            .chain(self.minimized.iter())
             .find(|window| window.wl_surface().map(|s| s == *surface).unwrap_or(false))
             .cloned()
     }
+
+    ///
+    /// Unmap `window` from the space,
+    /// keeping it alive until it's restored.
+    ///
+    pub fn minimize_window(&mut self, window: &AvWindow) {
+        let location = match self.space.element_location(window) {
+            Some(location) => location,
+            None => return,
+        };
+
+        window.set_minimized(location);
+        if let AvWindow::X11(surface) = window {
+            let _ = surface.set_minimized(true);
+        }
+
+        self.space.unmap_elem(window);
+        self.minimized.push(window.clone());
+
+        // Hand focus back to whatever was used before it.
+        let keyboard = self.seat.get_keyboard().unwrap();
+        if keyboard.current_focus() == Some(FocusTarget::Window(window.clone())) {
+            let serial = SERIAL_COUNTER.next_serial();
+            match self.focus_history.windows(&self.space).first().cloned() {
+                Some(next) => self.focus_window(&next, true, serial),
+                None => keyboard.set_focus(self, None, serial),
+            }
+        }
+    }
+
+    ///
+    /// Map a minimized `window` back where it was,
+    /// and focus it.
+    ///
+    pub fn restore_window(&mut self, window: &AvWindow) {
+        self.minimized.retain(|w| w != window && w.alive());
+
+        let location = match window.take_minimized() {
+            Some(location) => location,
+            None => return,
+        };
+
+        if let AvWindow::X11(surface) = window {
+            let _ = surface.set_minimized(false);
+        }
+
+        self.space.map_element(window.clone(), location, true);
+        self.focus_window(window, true, SERIAL_COUNTER.next_serial());
+    }
+
+    ///
+    /// (Un)maximize `window`, whichever kind it is.
+    ///
+    pub fn maximize_window(&mut self, window: &AvWindow, maximized: bool) {
+        let from = self.drawn_rect(window);
+
+        match (window, maximized) {
+            (AvWindow::Wayland(w), true) => self.maximize_request(w.toplevel().clone()),
+            (AvWindow::Wayland(w), false) => self.unmaximize_request(w.toplevel().clone()),
+            (AvWindow::X11(w), true) => self.maximize_request_x11(w),
+            (AvWindow::X11(w), false) => self.unmaximize_request_x11(w),
+        }
+
+        self.animate_from(window, from, true);
+    }
+
+    ///
+    /// Make `window` (or stop it being) fullscreen,
+    /// on `output` if given.
+    ///
+    pub fn fullscreen_window(
+        &mut self,
+        window: &AvWindow,
+        output: Option<wl_output::WlOutput>,
+        fullscreen: bool,
+    ) {
+        match (window, fullscreen) {
+            (AvWindow::Wayland(w), true) => self.fullscreen_request(w.toplevel().clone(), output),
+            (AvWindow::Wayland(w), false) => self.unfullscreen_request(w.toplevel().clone()),
+            (AvWindow::X11(w), true) => self.fullscreen_request_x11(w),
+            (AvWindow::X11(w), false) => self.unfullscreen_request_x11(w),
+        }
+    }
+
+    ///
+    /// Move and resize `window` to `geometry`.
+    ///
+    pub fn set_window_geometry(&mut self, window: &AvWindow, geometry: Rectangle<i32, Logical>) {
+        match window {
+            AvWindow::Wayland(w) => {
+                w.toplevel().with_pending_state(|state| {
+                    state.size = Some(geometry.size);
+                });
+                w.toplevel().send_configure();
+            }
+            AvWindow::X11(w) => {
+                let _ = w.configure(geometry);
+            }
+        }
+
+        self.space.map_element(window.clone(), geometry.loc, true);
+    }
+
+    ///
+    /// Fit maximized windows back into the usable area of
+    /// any output whose panels have changed since last time.
+    ///
+    pub fn refit_maximized_windows(&mut self) {
+        for output in self.space.outputs().cloned().collect::<Vec<_>>() {
+            let usable = match usable_geometry(&self.space, &output) {
+                Some(usable) => usable,
+                None => continue,
+            };
+
+            output.user_data().insert_if_missing(UsableArea::default);
+            let last = output
+                .user_data()
+                .get::<UsableArea>()
+                .unwrap()
+                .0
+                .replace(Some(usable));
+
+            // Nothing to do the first time round, either.
+            if last.map(|last| last == usable).unwrap_or(true) {
+                continue;
+            }
+
+            let maximized = self
+                .space
+                .elements()
+                .filter(|w| w.is_maximized())
+                .filter(|w| self.space.outputs_for_element(w).first() == Some(&output))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            for window in maximized {
+                self.maximize_window(&window, true);
+            }
+        }
+    }
+
+    ///
+    /// Restore whichever window was minimized last.
+    ///
+    pub fn restore_last_minimized(&mut self) {
+        self.minimized.retain(|w| w.alive());
+
+        if let Some(window) = self.minimized.last().cloned() {
+            self.restore_window(&window);
+        }
+    }
 }
 
 fn ensure_initial_configure(
@@ -242,14 +440,15 @@ fn ensure_initial_configure(
                 .initial_configure_sent
         });
 
-        // send the initial configure if relevant
-        if !initial_configure_sent {
-            let mut map = layer_map_for_output(output);
+        let mut map = layer_map_for_output(output);
 
-            // arrange the layers before sending the initial configure
-            // to respect any size the client may have sent
-            map.arrange();
+        // arrange the layers before sending the initial configure
+        // to respect any size the client may have sent
+        // -- and after any later commit, in case the exclusive zone changed.
+        map.arrange();
 
+        // send the initial configure if relevant
+        if !initial_configure_sent {
             let layer = map
                 .layer_for_surface(surface, WindowSurfaceType::TOPLEVEL)
                 .unwrap();
@@ -272,20 +471,20 @@ delegate_viewporter!(@<BEnd : Backend + 'static> Navda<BEnd>);
 ///
 /// Decides where to place a new window.
 ///
-fn place_new_window(space: &mut Space<AvWindow>, window: &AvWindow, activate: bool) {
+fn place_new_window(
+    space: &mut Space<AvWindow>,
+    window: &AvWindow,
+    output: Option<Output>,
+    activate: bool,
+) {
     // TODO(Sammy99jsp): Change from `anvil`'s default below for a more fancy impl:
-    // place the window at a random location on the primary output
+    // place the window at a random location on `output` (or the primary output)
     // or if there is not output in a [0;800]x[0;800] square
     use rand::distributions::{Distribution, Uniform};
 
-    let output = space.outputs().next().cloned();
+    let output = output.or_else(|| space.outputs().next().cloned());
     let output_geometry = output
-        .and_then(|o| {
-            let geo = space.output_geometry(&o)?;
-            let map = layer_map_for_output(&o);
-            let zone = map.non_exclusive_zone();
-            Some(Rectangle::from_loc_and_size(geo.loc + zone.loc, zone.size))
-        })
+        .and_then(|o| usable_geometry(space, &o))
         .unwrap_or_else(|| Rectangle::from_loc_and_size((0, 0), (800, 800)));
 
     let max_x = output_geometry.loc.x + (((output_geometry.size.w as f32) / 3.0) * 2.0) as i32;
@@ -316,12 +515,7 @@ pub fn fixup_positions(space: &mut Space<AvWindow>) {
     let mut orphaned_windows = Vec::new();
     let outputs = space
         .outputs()
-        .flat_map(|o| {
-            let geo = space.output_geometry(o)?;
-            let map = layer_map_for_output(o);
-            let zone = map.non_exclusive_zone();
-            Some(Rectangle::from_loc_and_size(geo.loc + zone.loc, zone.size))
-        })
+        .flat_map(|o| usable_geometry(space, o))
         .collect::<Vec<_>>();
     for window in space.elements() {
         let window_location = match space.element_location(window) {
@@ -335,6 +529,6 @@ pub fn fixup_positions(space: &mut Space<AvWindow>) {
         }
     }
     for window in orphaned_windows.into_iter() {
-        place_new_window(space, &window, false);
+        place_new_window(space, &window, None, false);
     }
 }
@@ -0,0 +1,258 @@
+//!
+//! Spotting hung clients -- by pinging them every so often,
+//! and seeing who takes too long to answer.
+//!
+//! Wayland clients are pinged through `xdg_wm_base`, and X11
+//! windows with `_NET_WM_PING` (if they support it).
+//!
+
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant},
+};
+
+use smithay::{
+    reexports::wayland_server::Resource, utils::SERIAL_COUNTER, wayland::shell::xdg::ShellClient,
+    xwayland::X11Surface,
+};
+
+use crate::compositor::{backend::Backend, state::Navda};
+
+use super::{AvWindow, XConnection};
+
+///
+/// How long to wait after a pong before pinging again.
+///
+const PING_INTERVAL: Duration = Duration::from_secs(2);
+
+///
+/// How long a client has to answer a
+/// ping before it's considered hung.
+///
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+///
+/// Pings to a single client, kept in its `xdg_wm_base` user data
+/// -- or to a single X11 window, in its own user data.
+///
+#[derive(Debug)]
+struct PingState {
+    ///
+    /// When the unanswered ping was sent, if there is one.
+    ///
+    pending: Option<Instant>,
+
+    ///
+    /// When the client last answered.
+    ///
+    last_pong: Instant,
+}
+
+impl Default for PingState {
+    fn default() -> Self {
+        Self {
+            pending: None,
+            last_pong: Instant::now(),
+        }
+    }
+}
+
+impl PingState {
+    ///
+    /// Whether the client's unresponsive at `now`
+    /// -- or `None` if it's time to ping it again.
+    ///
+    fn check(&self, now: Instant) -> Option<bool> {
+        match self.pending {
+            Some(sent) => Some(now.duration_since(sent) >= PING_TIMEOUT),
+            None if now.duration_since(self.last_pong) >= PING_INTERVAL => None,
+            None => Some(false),
+        }
+    }
+
+    fn pong(&mut self) {
+        self.pending = None;
+        self.last_pong = Instant::now();
+    }
+}
+
+///
+/// Whether an X11 window lists `_NET_WM_PING` in its `WM_PROTOCOLS`
+/// -- looked up once, in its user data.
+///
+#[derive(Debug)]
+struct PingSupport(bool);
+
+///
+/// Note that `client` answered its ping.
+///
+pub fn pong_received(client: &ShellClient) {
+    let _ = client.with_data(|data| {
+        if let Some(state) = data.get::<RefCell<PingState>>() {
+            state.borrow_mut().pong();
+        }
+    });
+}
+
+impl<BEnd: Backend> Navda<BEnd> {
+    ///
+    /// Ping every client with a window, and (un)mark the windows
+    /// of any client that's been quiet for too long as unresponsive.
+    ///
+    /// Called once per event loop iteration.
+    ///
+    pub fn ping_clients(&mut self) {
+        let now = Instant::now();
+
+        let pongs: Vec<u32> = self.xconn.as_ref().map(|c| c.pongs()).unwrap_or_default();
+
+        for window in self.space.elements() {
+            let unresponsive = match window {
+                AvWindow::Wayland(w) => {
+                    let Some(client) = w.toplevel().client() else {
+                        continue;
+                    };
+                    Self::ping_wayland(&client, now)
+                }
+                AvWindow::X11(w) => {
+                    let Some(xconn) = &self.xconn else { continue };
+                    Self::ping_x11(xconn, w, &pongs, now)
+                }
+            };
+
+            if unresponsive != window.is_unresponsive() {
+                slog::info!(
+                    self.log,
+                    "Window {} responding.",
+                    if unresponsive { "stopped" } else { "started" };
+                    "title" => window.title()
+                );
+                window.set_unresponsive(unresponsive);
+            }
+        }
+    }
+
+    ///
+    /// Whether a Wayland `client` is unresponsive,
+    /// pinging it if it's been long enough.
+    ///
+    fn ping_wayland(client: &ShellClient, now: Instant) -> bool {
+        let check = client
+            .with_data(|data| {
+                data.insert_if_missing(|| RefCell::new(PingState::default()));
+                data.get::<RefCell<PingState>>()
+                    .unwrap()
+                    .borrow()
+                    .check(now)
+            })
+            .unwrap_or(Some(false));
+
+        match check {
+            Some(unresponsive) => unresponsive,
+            None => {
+                if client.send_ping(SERIAL_COUNTER.next_serial()).is_ok() {
+                    let _ = client.with_data(|data| {
+                        data.get::<RefCell<PingState>>()
+                            .unwrap()
+                            .borrow_mut()
+                            .pending = Some(now);
+                    });
+                }
+                false
+            }
+        }
+    }
+
+    ///
+    /// Whether an X11 `window` is unresponsive, pinging it if it's been
+    /// long enough -- `pongs` are the windows that have answered since.
+    ///
+    /// Windows without `_NET_WM_PING` are never unresponsive.
+    ///
+    fn ping_x11(xconn: &XConnection, window: &X11Surface, pongs: &[u32], now: Instant) -> bool {
+        let data = window.user_data();
+
+        data.insert_if_missing(|| PingSupport(xconn.supports_ping(window.window_id())));
+        if !data.get::<PingSupport>().unwrap().0 {
+            return false;
+        }
+
+        data.insert_if_missing(|| RefCell::new(PingState::default()));
+        let state = data.get::<RefCell<PingState>>().unwrap();
+
+        if pongs.contains(&window.window_id()) {
+            state.borrow_mut().pong();
+        }
+
+        let check = state.borrow().check(now);
+
+        match check {
+            Some(unresponsive) => unresponsive,
+            None => {
+                if xconn.send_ping(window.window_id()).is_ok() {
+                    state.borrow_mut().pending = Some(now);
+                }
+                false
+            }
+        }
+    }
+
+    ///
+    /// Kill the process behind `window`, for when
+    /// it won't close on its own.
+    ///
+    /// X11 clients are disconnected from XWayland rather than killed
+    /// -- XWayland is the Wayland client behind every X11 window.
+    ///
+    pub fn kill_window_client(&mut self, window: &AvWindow) {
+        let w = match window {
+            AvWindow::Wayland(w) => w,
+            AvWindow::X11(x) => {
+                let Some(xconn) = &self.xconn else { return };
+
+                slog::warn!(
+                    self.log,
+                    "Disconnecting unresponsive X11 client.";
+                    "title" => window.title()
+                );
+
+                if let Err(err) = xconn.kill_client(x.window_id()) {
+                    slog::error!(self.log, "Failed to disconnect X11 client: {}", err);
+                }
+                return;
+            }
+        };
+
+        let credentials = match self
+            .display_handle
+            .get_client(w.toplevel().wl_surface().id())
+        {
+            Ok(client) => client.get_credentials(&self.display_handle),
+            Err(_) => return,
+        };
+
+        match credentials {
+            Ok(credentials) => {
+                slog::warn!(
+                    self.log,
+                    "Killing unresponsive client.";
+                    "pid" => credentials.pid,
+                    "title" => window.title()
+                );
+
+                // SAFETY: `kill` has no memory safety requirements.
+                if unsafe { libc::kill(credentials.pid, libc::SIGKILL) } != 0 {
+                    slog::error!(
+                        self.log,
+                        "Failed to kill client";
+                        "pid" => credentials.pid,
+                        "err" => format!("{:?}", std::io::Error::last_os_error())
+                    );
+                }
+            }
+            Err(err) => {
+                slog::warn!(self.log, "Couldn't get the client's credentials: {:?}", err);
+            }
+        }
+    }
+}
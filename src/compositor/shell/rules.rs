@@ -0,0 +1,437 @@
+//!
+//! Window rules -- per-application overrides for where
+//! (and how) new windows open, from the `windowRules` config section.
+//!
+
+use regex::Regex;
+use smithay::{
+    desktop::space::SpaceElement,
+    reexports::{
+        wayland_protocols::xdg::decoration::zv1::server::zxdg_toplevel_decoration_v1::Mode,
+        wayland_server::Resource,
+    },
+    utils::{Logical, Point, Rectangle, Size},
+};
+
+use crate::config::sections::window_rules::{WindowMatch, WindowRule};
+
+use super::{place_new_window, AvWindow};
+use crate::compositor::{backend::Backend, state::Navda};
+
+///
+/// A `WindowMatch` with its patterns compiled.
+///
+#[derive(Debug)]
+struct Matcher {
+    app_id: Option<Regex>,
+    title: Option<Regex>,
+    class: Option<Regex>,
+    instance: Option<Regex>,
+    role: Option<Regex>,
+}
+
+impl Matcher {
+    ///
+    /// `None` if any of the patterns isn't a valid regex.
+    ///
+    fn new(matches: &WindowMatch, log: &slog::Logger) -> Option<Self> {
+        let compile = |pattern: &Option<String>| -> Result<Option<Regex>, ()> {
+            pattern
+                .as_deref()
+                .map(|p| {
+                    Regex::new(p).map_err(|err| {
+                        slog::warn!(log, "Invalid window rule pattern `{}`: {}", p, err);
+                    })
+                })
+                .transpose()
+        };
+
+        Some(Self {
+            app_id: compile(&matches.app_id).ok()?,
+            title: compile(&matches.title).ok()?,
+            class: compile(&matches.class).ok()?,
+            instance: compile(&matches.instance).ok()?,
+            role: compile(&matches.role).ok()?,
+        })
+    }
+
+    fn matches(&self, props: &WindowProps) -> bool {
+        // A missing pattern matches anything,
+        // a missing value matches nothing.
+        let check = |pattern: &Option<Regex>, value: &Option<String>| match pattern {
+            None => true,
+            Some(pattern) => value.as_ref().map(|v| pattern.is_match(v)).unwrap_or(false),
+        };
+
+        check(&self.app_id, &props.app_id)
+            && check(&self.title, &props.title)
+            && check(&self.class, &props.class)
+            && check(&self.instance, &props.instance)
+            && check(&self.role, &props.role)
+    }
+}
+
+///
+/// What a window's rules are matched against.
+///
+#[derive(Debug, Default)]
+struct WindowProps {
+    app_id: Option<String>,
+    title: Option<String>,
+    class: Option<String>,
+    instance: Option<String>,
+    role: Option<String>,
+}
+
+impl WindowProps {
+    ///
+    /// `role` is the window's `WM_WINDOW_ROLE`, for X11 windows.
+    ///
+    fn of(window: &AvWindow, role: Option<&str>) -> Self {
+        let (class, instance) = match window {
+            AvWindow::Wayland(_) => (None, None),
+            AvWindow::X11(w) => (Some(w.class()), Some(w.instance())),
+        };
+
+        Self {
+            app_id: window.app_id(),
+            title: window.title(),
+            class,
+            instance,
+            role: role.map(str::to_string),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Rule {
+    matcher: Matcher,
+    rule: AppliedRules,
+}
+
+///
+/// What the matching rules decided for a window
+/// -- kept around in its user data (see [`AvWindow::window_rules`]).
+///
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AppliedRules {
+    pub output: Option<String>,
+
+    ///
+    /// Not acted upon until there are workspaces.
+    ///
+    pub workspace: Option<u32>,
+
+    pub size: Option<Size<i32, Logical>>,
+    pub position: Option<Point<i32, Logical>>,
+
+    ///
+    /// Every window floats for now --
+    /// this is for when tiling comes along.
+    ///
+    pub floating: Option<bool>,
+
+    pub fullscreen: Option<bool>,
+    pub maximized: Option<bool>,
+    pub opacity: Option<f32>,
+    pub decorations: Option<Mode>,
+}
+
+impl AppliedRules {
+    fn from_config(rule: &WindowRule, log: &slog::Logger) -> Self {
+        let decorations = rule.decorations.as_deref().and_then(|mode| match mode {
+            "client" => Some(Mode::ClientSide),
+            "server" => Some(Mode::ServerSide),
+            _ => {
+                slog::warn!(
+                    log,
+                    "Unknown decoration mode `{}` in window rule, ignoring it.",
+                    mode
+                );
+                None
+            }
+        });
+
+        Self {
+            output: rule.output.clone(),
+            workspace: rule.workspace,
+            size: rule.size.map(|(w, h)| (w.max(1), h.max(1)).into()),
+            position: rule.position.map(Point::from),
+            floating: rule.floating,
+            fullscreen: rule.fullscreen,
+            maximized: rule.maximized,
+            opacity: rule.opacity.map(|o| o.clamp(0.0, 1.0)),
+            decorations,
+        }
+    }
+
+    ///
+    /// Lay `other` over these rules --
+    /// whatever it sets, wins.
+    ///
+    fn merge(&mut self, other: &Self) {
+        macro_rules! take {
+            ($($field:ident),*) => {
+                $(if other.$field.is_some() {
+                    self.$field = other.$field.clone();
+                })*
+            };
+        }
+
+        take!(
+            output,
+            workspace,
+            size,
+            position,
+            floating,
+            fullscreen,
+            maximized,
+            opacity,
+            decorations
+        );
+    }
+}
+
+///
+/// Every window rule from the config, ready to be matched.
+///
+#[derive(Debug, Default)]
+pub struct WindowRules(Vec<Rule>);
+
+impl WindowRules {
+    ///
+    /// Rules with invalid patterns are skipped (with a warning).
+    ///
+    pub fn from_config(rules: &[WindowRule], log: &slog::Logger) -> Self {
+        Self(
+            rules
+                .iter()
+                .filter_map(|rule| {
+                    Some(Rule {
+                        matcher: Matcher::new(&rule.matches, log)?,
+                        rule: AppliedRules::from_config(rule, log),
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    ///
+    /// Every rule matching `window` (with `role`), merged in order.
+    ///
+    pub fn resolve(&self, window: &AvWindow, role: Option<&str>) -> AppliedRules {
+        self.resolve_props(&WindowProps::of(window, role))
+    }
+
+    fn resolve_props(&self, props: &WindowProps) -> AppliedRules {
+        self.0
+            .iter()
+            .filter(|rule| rule.matcher.matches(props))
+            .fold(AppliedRules::default(), |mut applied, rule| {
+                applied.merge(&rule.rule);
+                applied
+            })
+    }
+}
+
+impl<BEnd: Backend> Navda<BEnd> {
+    ///
+    /// Match a new window against the window rules,
+    /// and move/resize/... it to suit.
+    ///
+    /// Called on the first commit of xdg toplevels (once the app_id
+    /// and title are known), and when X11 windows ask to be mapped.
+    ///
+    pub fn apply_window_rules(&mut self, window: &AvWindow) {
+        let role: Option<String> = match (window, &self.xconn) {
+            (AvWindow::X11(w), Some(xconn)) => xconn.role(w.window_id()),
+            _ => None,
+        };
+
+        let rules = self.window_rules.resolve(window, role.as_deref());
+        window.set_window_rules(rules.clone());
+        window.set_effects(self.effects_policy.for_window(&rules));
+
+        if rules == AppliedRules::default() {
+            return;
+        }
+
+        let output = rules.output.as_ref().and_then(|name| {
+            let output = self.space.outputs().find(|o| &o.name() == name).cloned();
+            if output.is_none() {
+                slog::warn!(self.log, "Window rule output `{}` not found.", name);
+            }
+            output
+        });
+
+        if let Some(size) = rules.size {
+            match window {
+                AvWindow::Wayland(w) => w.toplevel().with_pending_state(|state| {
+                    state.size = Some(size);
+                }),
+                AvWindow::X11(w) => {
+                    let mut geo = w.geometry();
+                    geo.size = size;
+                    let _ = w.configure(geo);
+                }
+            }
+        }
+
+        match rules.position {
+            Some(position) => {
+                let origin = output
+                    .as_ref()
+                    .or_else(|| self.space.outputs_for_element(window).first())
+                    .or_else(|| self.space.outputs().next())
+                    .and_then(|o| self.space.output_geometry(o))
+                    .map(|geo| geo.loc)
+                    .unwrap_or_default();
+
+                self.space
+                    .map_element(window.clone(), origin + position, true);
+            }
+            None if output.is_some() => {
+                place_new_window(&mut self.space, window, output.clone(), true);
+            }
+            None => {}
+        }
+
+        if rules.maximized == Some(true) {
+            self.maximize_window(window, true);
+        }
+
+        if rules.fullscreen == Some(true) {
+            let wl_output = output.as_ref().zip(window.wl_surface()).and_then(|(o, s)| {
+                let client = self.display_handle.get_client(s.id()).ok()?;
+                o.client_outputs(&client).into_iter().next()
+            });
+            self.fullscreen_window(window, wl_output, true);
+        }
+
+        if let (AvWindow::Wayland(w), Some(mode)) = (window, rules.decorations) {
+            w.toplevel().with_pending_state(|state| {
+                state.decoration_mode = Some(mode);
+            });
+        }
+
+        // X11 windows need to hear where they ended up.
+        if let AvWindow::X11(w) = window {
+            if let Some(location) = self.space.element_location(window) {
+                let _ = w.configure(Rectangle::from_loc_and_size(location, w.geometry().size));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::sections::window_rules::{WindowMatch, WindowRule};
+
+    use super::{AppliedRules, Matcher, WindowProps, WindowRules};
+
+    fn log() -> slog::Logger {
+        slog::Logger::root(slog::Discard, slog::o!())
+    }
+
+    fn props(app_id: &str, title: &str, role: Option<&str>) -> WindowProps {
+        WindowProps {
+            app_id: Some(app_id.to_string()),
+            title: Some(title.to_string()),
+            role: role.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn matcher(app_id: Option<&str>, title: Option<&str>, role: Option<&str>) -> Matcher {
+        let matches = WindowMatch {
+            app_id: app_id.map(str::to_string),
+            title: title.map(str::to_string),
+            role: role.map(str::to_string),
+            ..Default::default()
+        };
+
+        Matcher::new(&matches, &log()).unwrap()
+    }
+
+    #[test]
+    fn app_id() {
+        let m = matcher(Some("^org\\.gnome\\..*$"), None, None);
+
+        assert!(m.matches(&props("org.gnome.Nautilus", "Files", None)));
+        assert!(!m.matches(&props("firefox", "Files", None)));
+    }
+
+    #[test]
+    fn title() {
+        let m = matcher(None, Some("Picture-in-Picture"), None);
+
+        assert!(m.matches(&props("firefox", "Picture-in-Picture", None)));
+        assert!(!m.matches(&props("firefox", "Mozilla Firefox", None)));
+    }
+
+    #[test]
+    fn role() {
+        let m = matcher(None, None, Some("^pop-up$"));
+
+        assert!(m.matches(&props("gimp", "GIMP", Some("pop-up"))));
+        assert!(!m.matches(&props("gimp", "GIMP", Some("toolbox"))));
+        // Wayland windows have no role to match.
+        assert!(!m.matches(&props("gimp", "GIMP", None)));
+    }
+
+    #[test]
+    fn all_patterns_have_to_match() {
+        let m = matcher(Some("firefox"), Some("Private"), None);
+
+        assert!(m.matches(&props("firefox", "Private Browsing", None)));
+        assert!(!m.matches(&props("firefox", "Mozilla Firefox", None)));
+        assert!(!m.matches(&props("chromium", "Private Browsing", None)));
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        let matches = WindowMatch {
+            app_id: Some("(".to_string()),
+            ..Default::default()
+        };
+
+        assert!(Matcher::new(&matches, &log()).is_none());
+    }
+
+    #[test]
+    fn later_rule_wins() {
+        let rule = |app_id: &str, opacity, maximized| WindowRule {
+            matches: WindowMatch {
+                app_id: Some(app_id.to_string()),
+                ..Default::default()
+            },
+            opacity: Some(opacity),
+            maximized,
+            ..Default::default()
+        };
+
+        let rules = WindowRules::from_config(
+            &[
+                rule("firefox", 0.5, Some(true)),
+                rule("chromium", 0.2, Some(false)),
+                rule("fire", 0.8, None),
+            ],
+            &log(),
+        );
+
+        assert_eq!(
+            rules.resolve_props(&props("firefox", "Mozilla Firefox", None)),
+            AppliedRules {
+                opacity: Some(0.8),
+                // Left alone by the later rule.
+                maximized: Some(true),
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(
+            rules.resolve_props(&props("alacritty", "Alacritty", None)),
+            AppliedRules::default()
+        );
+    }
+}
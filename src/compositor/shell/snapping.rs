@@ -0,0 +1,311 @@
+//!
+//! Snapping dragged windows to edges, and
+//! tiling them by dragging against the side of an output.
+//!
+
+use smithay::{
+    desktop::{space::SpaceElement, Space},
+    utils::{Logical, Point, Rectangle},
+};
+
+use crate::config::sections::snapping::Snapping;
+
+use crate::compositor::{backend::Backend, state::Navda};
+
+use super::{usable_geometry, AvWindow};
+
+///
+/// How close (in logical pixels) the pointer has to be
+/// to an output's edge to tile against it.
+///
+const EDGE_ZONE: i32 = 8;
+
+///
+/// How far from a corner still counts
+/// as the corner, along each edge.
+///
+const CORNER_ZONE: i32 = 64;
+
+///
+/// The user's snapping preferences, read
+/// from the `snapping` config section.
+///
+#[derive(Debug, Clone)]
+pub struct SnapPolicy {
+    pub threshold: i32,
+    pub edge_tiling: bool,
+}
+
+impl Default for SnapPolicy {
+    fn default() -> Self {
+        Self {
+            threshold: 16,
+            edge_tiling: true,
+        }
+    }
+}
+
+impl SnapPolicy {
+    pub fn from_config(snapping: &Snapping) -> Self {
+        Self {
+            threshold: snapping.threshold.clamp(0, i32::MAX as i64) as i32,
+            edge_tiling: snapping.edgeTiling,
+        }
+    }
+}
+
+///
+/// Where on an output a window can be tiled.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileZone {
+    Maximize,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl TileZone {
+    ///
+    /// The zone the pointer is in, if it's
+    /// pressed against an edge of `output` (at `output_geo`).
+    ///
+    pub fn at(pointer: Point<f64, Logical>, output_geo: Rectangle<i32, Logical>) -> Option<Self> {
+        let pointer = pointer.to_i32_round::<i32>() - output_geo.loc;
+        let size = output_geo.size;
+
+        let left = pointer.x < EDGE_ZONE;
+        let right = pointer.x >= size.w - EDGE_ZONE;
+        let top = pointer.y < EDGE_ZONE;
+        let bottom = pointer.y >= size.h - EDGE_ZONE;
+
+        let near_top = pointer.y < CORNER_ZONE;
+        let near_bottom = pointer.y >= size.h - CORNER_ZONE;
+        let near_left = pointer.x < CORNER_ZONE;
+        let near_right = pointer.x >= size.w - CORNER_ZONE;
+
+        let zone = if (left && near_top) || (top && near_left) {
+            Self::TopLeft
+        } else if (right && near_top) || (top && near_right) {
+            Self::TopRight
+        } else if (left && near_bottom) || (bottom && near_left) {
+            Self::BottomLeft
+        } else if (right && near_bottom) || (bottom && near_right) {
+            Self::BottomRight
+        } else if top {
+            Self::Maximize
+        } else if left {
+            Self::Left
+        } else if right {
+            Self::Right
+        } else {
+            return None;
+        };
+
+        Some(zone)
+    }
+
+    ///
+    /// Where a window tiled here goes, within
+    /// the `usable` area of its output.
+    ///
+    pub fn geometry(&self, usable: Rectangle<i32, Logical>) -> Rectangle<i32, Logical> {
+        let (x, y) = (usable.loc.x, usable.loc.y);
+        let (w, h) = (usable.size.w, usable.size.h);
+        let (half_w, half_h) = (w / 2, h / 2);
+
+        let (loc, size) = match self {
+            Self::Maximize => ((x, y), (w, h)),
+            Self::Left => ((x, y), (half_w, h)),
+            Self::Right => ((x + half_w, y), (w - half_w, h)),
+            Self::TopLeft => ((x, y), (half_w, half_h)),
+            Self::TopRight => ((x + half_w, y), (w - half_w, half_h)),
+            Self::BottomLeft => ((x, y + half_h), (half_w, h - half_h)),
+            Self::BottomRight => ((x + half_w, y + half_h), (w - half_w, h - half_h)),
+        };
+
+        Rectangle::from_loc_and_size(loc, size)
+    }
+}
+
+///
+/// The tile zone under the pointer, along with
+/// where the window would end up.
+///
+pub fn tile_target(
+    space: &Space<AvWindow>,
+    pointer: Point<f64, Logical>,
+) -> Option<(TileZone, Rectangle<i32, Logical>)> {
+    let output = space.output_under(pointer).next()?;
+    let zone = TileZone::at(pointer, space.output_geometry(output)?)?;
+    let geometry = zone.geometry(usable_geometry(space, output)?);
+
+    Some((zone, geometry))
+}
+
+///
+/// How far to nudge a span (`start..end`) along one axis
+/// so one of its ends meets the nearest of `edges`
+/// -- if any is within `threshold`.
+///
+fn snap_axis(start: i32, end: i32, edges: &[i32], threshold: i32) -> i32 {
+    edges
+        .iter()
+        .flat_map(|edge| [edge - start, edge - end])
+        .filter(|offset| offset.abs() <= threshold)
+        .min_by_key(|offset| offset.abs())
+        .unwrap_or(0)
+}
+
+///
+/// Snap `window`, about to be at `rect`, to the
+/// edges of outputs and the other windows nearby.
+///
+pub fn snap_location(
+    space: &Space<AvWindow>,
+    window: &AvWindow,
+    rect: Rectangle<i32, Logical>,
+    threshold: i32,
+) -> Point<i32, Logical> {
+    if threshold == 0 {
+        return rect.loc;
+    }
+
+    let targets = space
+        .outputs()
+        .filter_map(|o| usable_geometry(space, o))
+        .chain(space.elements().filter(|w| *w != window).filter_map(|w| {
+            Some(Rectangle::from_loc_and_size(
+                space.element_location(w)?,
+                w.geometry().size,
+            ))
+        }))
+        .collect::<Vec<_>>();
+
+    let x_edges = targets
+        .iter()
+        .flat_map(|t| [t.loc.x, t.loc.x + t.size.w])
+        .collect::<Vec<_>>();
+    let y_edges = targets
+        .iter()
+        .flat_map(|t| [t.loc.y, t.loc.y + t.size.h])
+        .collect::<Vec<_>>();
+
+    let dx = snap_axis(rect.loc.x, rect.loc.x + rect.size.w, &x_edges, threshold);
+    let dy = snap_axis(rect.loc.y, rect.loc.y + rect.size.h, &y_edges, threshold);
+
+    rect.loc + Point::from((dx, dy))
+}
+
+impl<BEnd: Backend> Navda<BEnd> {
+    ///
+    /// Tile `window` into `zone`, at `geometry`.
+    ///
+    /// Its current geometry is kept, to go back
+    /// to once it's dragged out again.
+    ///
+    pub fn tile_window(
+        &mut self,
+        window: &AvWindow,
+        zone: TileZone,
+        geometry: Rectangle<i32, Logical>,
+    ) {
+        if let Some(location) = self.space.element_location(window) {
+            window.save_geometry(Rectangle::from_loc_and_size(
+                location,
+                window.geometry().size,
+            ));
+        }
+
+        if zone == TileZone::Maximize {
+            self.maximize_window(window, true);
+        } else {
+            let from = self.drawn_rect(window);
+            self.set_window_geometry(window, geometry);
+            self.animate_from(window, from, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use smithay::utils::{Logical, Rectangle};
+
+    use super::{snap_axis, TileZone};
+
+    fn output() -> Rectangle<i32, Logical> {
+        Rectangle::from_loc_and_size((0, 0), (1920, 1080))
+    }
+
+    fn zone_at(x: f64, y: f64) -> Option<TileZone> {
+        TileZone::at((x, y).into(), output())
+    }
+
+    #[test]
+    fn edges() {
+        assert_eq!(zone_at(960.0, 0.0), Some(TileZone::Maximize));
+        assert_eq!(zone_at(0.0, 540.0), Some(TileZone::Left));
+        assert_eq!(zone_at(1919.0, 540.0), Some(TileZone::Right));
+
+        // Nothing to tile to along the bottom, or away from the edges.
+        assert_eq!(zone_at(960.0, 1079.0), None);
+        assert_eq!(zone_at(960.0, 540.0), None);
+        assert_eq!(zone_at(8.0, 540.0), None);
+    }
+
+    #[test]
+    fn corners() {
+        assert_eq!(zone_at(0.0, 0.0), Some(TileZone::TopLeft));
+        assert_eq!(zone_at(30.0, 0.0), Some(TileZone::TopLeft));
+        assert_eq!(zone_at(1919.0, 10.0), Some(TileZone::TopRight));
+        assert_eq!(zone_at(0.0, 1079.0), Some(TileZone::BottomLeft));
+        assert_eq!(zone_at(1900.0, 1079.0), Some(TileZone::BottomRight));
+    }
+
+    #[test]
+    fn relative_to_output() {
+        let second = Rectangle::from_loc_and_size((1920, 0), (1920, 1080));
+
+        assert_eq!(
+            TileZone::at((1920.0, 540.0).into(), second),
+            Some(TileZone::Left)
+        );
+        assert_eq!(TileZone::at((2880.0, 540.0).into(), second), None);
+    }
+
+    #[test]
+    fn tiles_within_non_exclusive_zone() {
+        // The second output, less a 32px panel along its top
+        // -- an odd width, so the halves don't quite match.
+        let usable = Rectangle::from_loc_and_size((1920, 32), (1921, 1048));
+
+        assert_eq!(TileZone::Maximize.geometry(usable), usable);
+        assert_eq!(
+            TileZone::Left.geometry(usable),
+            Rectangle::from_loc_and_size((1920, 32), (960, 1048))
+        );
+        assert_eq!(
+            TileZone::Right.geometry(usable),
+            Rectangle::from_loc_and_size((2880, 32), (961, 1048))
+        );
+        assert_eq!(
+            TileZone::TopLeft.geometry(usable),
+            Rectangle::from_loc_and_size((1920, 32), (960, 524))
+        );
+        assert_eq!(
+            TileZone::BottomRight.geometry(usable),
+            Rectangle::from_loc_and_size((2880, 556), (961, 524))
+        );
+    }
+
+    #[test]
+    fn snaps_to_nearest_edge() {
+        assert_eq!(snap_axis(95, 195, &[0, 100, 200], 10), 5);
+        assert_eq!(snap_axis(-6, 94, &[0, 100], 10), 6);
+        assert_eq!(snap_axis(50, 150, &[0, 200], 10), 0);
+        assert_eq!(snap_axis(95, 195, &[100], 4), 0);
+    }
+}
@@ -20,21 +20,9 @@ use crate::compositor::{
 use super::{
     avwindow::AvWindow,
     grabs::{MoveSurfaceGrab, ResizeData, ResizeState, ResizeSurfaceGrab},
-    place_new_window, FullscreenSurface, SurfaceData,
+    place_new_window, usable_geometry, FullscreenSurface, SurfaceData,
 };
 
-#[derive(Debug, Default)]
-struct OldGeometry(RefCell<Option<Rectangle<i32, Logical>>>);
-impl OldGeometry {
-    pub fn save(&self, geo: Rectangle<i32, Logical>) {
-        *self.0.borrow_mut() = Some(geo);
-    }
-
-    pub fn restore(&self) -> Option<Rectangle<i32, Logical>> {
-        self.0.borrow_mut().take()
-    }
-}
-
 ///
 /// Implementing a XWayland server
 ///
@@ -51,12 +39,22 @@ impl<BEnd: Backend> XwmHandler for CalloopData<BEnd> {
         window.set_mapped(true).unwrap();
 
         let window = AvWindow::X11(window);
+        window.set_logger(&self.state.log);
+
+        // Mapping an iconified window brings it back.
+        if window.is_minimized() {
+            self.state.restore_window(&window);
+            return;
+        }
 
-        place_new_window(&mut self.state.space, &window, true);
+        place_new_window(&mut self.state.space, &window, None, true);
         let bbox = self.state.space.element_bbox(&window).unwrap();
         let AvWindow::X11(xsurface) = &window else { unreachable!() };
         xsurface.configure(Some(bbox)).unwrap();
 
+        self.state.apply_window_rules(&window);
+        self.state.animate_open(&window);
+
         // TODO: Server-side decorations
     }
 
@@ -75,6 +73,7 @@ impl<BEnd: Backend> XwmHandler for CalloopData<BEnd> {
             .cloned();
 
         if let Some(elem) = maybe {
+            self.state.animate_close(&elem);
             self.state.space.unmap_elem(&elem);
         }
         if !window.is_override_redirect() {
@@ -132,83 +131,23 @@ impl<BEnd: Backend> XwmHandler for CalloopData<BEnd> {
     }
 
     fn unmaximize_request(&mut self, xwm: XwmId, window: X11Surface) {
-        let Some(elem) = self
-            .state.space
-            .elements().find(|e|
-                matches!(e, AvWindow::X11(w) if w == &window)
-            )
-            .cloned()
-        else { return };
+        self.state.unmaximize_request_x11(&window);
+    }
 
-        window.set_maximized(false).unwrap();
+    fn minimize_request(&mut self, _xwm: XwmId, window: X11Surface) {
+        self.state.minimize_window(&AvWindow::X11(window));
+    }
 
-        if let Some(old_geo) = window
-            .user_data()
-            .get::<OldGeometry>()
-            .and_then(|data| data.restore())
-        {
-            window.configure(old_geo).unwrap();
-            self.state.space.map_element(elem, old_geo.loc, false);
-        }
+    fn unminimize_request(&mut self, _xwm: XwmId, window: X11Surface) {
+        self.state.restore_window(&AvWindow::X11(window));
     }
 
     fn fullscreen_request(&mut self, xwm: XwmId, window: X11Surface) {
-        if let Some(elem) = self
-            .state
-            .space
-            .elements()
-            .find(|e| matches!(e, AvWindow::X11(w) if w == &window))
-        {
-            let outputs_for_window = self.state.space.outputs_for_element(elem);
-            let output = outputs_for_window
-                .first()
-                // The window hasn't been mapped yet, use the primary output instead
-                .or_else(|| self.state.space.outputs().next())
-                // Assumes that at least one output exists
-                .expect("No outputs found");
-            let geometry = self.state.space.output_geometry(output).unwrap();
-
-            window.set_fullscreen(true).unwrap();
-            window.configure(geometry).unwrap();
-            output
-                .user_data()
-                .insert_if_missing(FullscreenSurface::default);
-            output
-                .user_data()
-                .get::<FullscreenSurface>()
-                .unwrap()
-                .set(elem.clone());
-            slog::trace!(self.state.log, "Fullscreening: {:?}", elem);
-        }
+        self.state.fullscreen_request_x11(&window);
     }
 
     fn unfullscreen_request(&mut self, _xwm: XwmId, window: X11Surface) {
-        if let Some(elem) = self
-            .state
-            .space
-            .elements()
-            .find(|e| matches!(e, AvWindow::X11(w) if w == &window))
-        {
-            window.set_fullscreen(false).unwrap();
-            if let Some(output) = self.state.space.outputs().find(|o| {
-                o.user_data()
-                    .get::<FullscreenSurface>()
-                    .and_then(|f| f.get())
-                    .map(|w| &w == elem)
-                    .unwrap_or(false)
-            }) {
-                slog::trace!(self.state.log, "Unfullscreening: {:?}", elem);
-                output
-                    .user_data()
-                    .get::<FullscreenSurface>()
-                    .unwrap()
-                    .clear();
-                window
-                    .configure(self.state.space.element_bbox(elem))
-                    .unwrap();
-                self.state.backend_data.reset_buffers(output);
-            }
-        }
+        self.state.unfullscreen_request_x11(&window);
     }
 
     fn resize_request(
@@ -289,19 +228,85 @@ impl<BEnd: Backend> Navda<BEnd> {
             .or_else(|| self.space.outputs().next())
             // Assumes that at least one output exists
             .expect("No outputs found");
-        let geometry = self.space.output_geometry(output).unwrap();
+        let geometry = usable_geometry(&self.space, output).unwrap();
 
         window.set_maximized(true).unwrap();
         window.configure(geometry).unwrap();
-        window.user_data().insert_if_missing(OldGeometry::default);
-        window
-            .user_data()
-            .get::<OldGeometry>()
-            .unwrap()
-            .save(old_geo);
+        elem.save_geometry(old_geo);
         self.space.map_element(elem, geometry.loc, false);
     }
 
+    pub fn unmaximize_request_x11(&mut self, window: &X11Surface) {
+        let Some(elem) = self
+            .space
+            .elements()
+            .find(|e| matches!(e, AvWindow::X11(w) if w == window))
+            .cloned()
+        else { return };
+
+        window.set_maximized(false).unwrap();
+
+        if let Some(old_geo) = elem.take_saved_geometry() {
+            window.configure(old_geo).unwrap();
+            self.space.map_element(elem, old_geo.loc, false);
+        }
+    }
+
+    pub fn fullscreen_request_x11(&mut self, window: &X11Surface) {
+        if let Some(elem) = self
+            .space
+            .elements()
+            .find(|e| matches!(e, AvWindow::X11(w) if w == window))
+        {
+            let outputs_for_window = self.space.outputs_for_element(elem);
+            let output = outputs_for_window
+                .first()
+                // The window hasn't been mapped yet, use the primary output instead
+                .or_else(|| self.space.outputs().next())
+                // Assumes that at least one output exists
+                .expect("No outputs found");
+            let geometry = self.space.output_geometry(output).unwrap();
+
+            window.set_fullscreen(true).unwrap();
+            window.configure(geometry).unwrap();
+            output
+                .user_data()
+                .insert_if_missing(FullscreenSurface::default);
+            output
+                .user_data()
+                .get::<FullscreenSurface>()
+                .unwrap()
+                .set(elem.clone());
+            slog::trace!(self.log, "Fullscreening: {:?}", elem);
+        }
+    }
+
+    pub fn unfullscreen_request_x11(&mut self, window: &X11Surface) {
+        if let Some(elem) = self
+            .space
+            .elements()
+            .find(|e| matches!(e, AvWindow::X11(w) if w == window))
+        {
+            window.set_fullscreen(false).unwrap();
+            if let Some(output) = self.space.outputs().find(|o| {
+                o.user_data()
+                    .get::<FullscreenSurface>()
+                    .and_then(|f| f.get())
+                    .map(|w| &w == elem)
+                    .unwrap_or(false)
+            }) {
+                slog::trace!(self.log, "Unfullscreening: {:?}", elem);
+                output
+                    .user_data()
+                    .get::<FullscreenSurface>()
+                    .unwrap()
+                    .clear();
+                window.configure(self.space.element_bbox(elem)).unwrap();
+                self.backend_data.reset_buffers(output);
+            }
+        }
+    }
+
     pub fn move_request_x11(&mut self, window: &X11Surface) {
         let seat = &self.seat; // luckily anvil only supports one seat anyway...
         let pointer = seat.get_pointer().unwrap();
@@ -320,11 +325,7 @@ impl<BEnd: Backend> Navda<BEnd> {
             window.set_maximized(false).unwrap();
             let pos = pointer.current_location();
             initial_window_location = (pos.x as i32, pos.y as i32).into();
-            if let Some(old_geo) = window
-                .user_data()
-                .get::<OldGeometry>()
-                .and_then(|data| data.restore())
-            {
+            if let Some(old_geo) = element.take_saved_geometry() {
                 window
                     .configure(Rectangle::from_loc_and_size(
                         initial_window_location,
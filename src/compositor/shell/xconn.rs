@@ -0,0 +1,171 @@
+//!
+//! A connection of our own to XWayland, beside smithay's window manager
+//! -- for what [`X11Wm`](smithay::xwayland::X11Wm) doesn't do (yet):
+//! reading `WM_WINDOW_ROLE`, pinging windows with `_NET_WM_PING`,
+//! and killing clients that stopped answering.
+//!
+
+use std::error::Error;
+
+use smithay::reexports::x11rb::{
+    connection::Connection,
+    protocol::{
+        xproto::{
+            AtomEnum, ChangeWindowAttributesAux, ClientMessageEvent, ConnectionExt as _, EventMask,
+            Window,
+        },
+        Event,
+    },
+    rust_connection::RustConnection,
+    CURRENT_TIME,
+};
+
+#[derive(Debug, Clone, Copy)]
+struct Atoms {
+    wm_protocols: u32,
+    wm_window_role: u32,
+    net_wm_ping: u32,
+}
+
+pub struct XConnection {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+impl std::fmt::Debug for XConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("XConnection")
+            .field("atoms", &self.atoms)
+            .finish_non_exhaustive()
+    }
+}
+
+impl XConnection {
+    ///
+    /// Connect to XWayland's `display` (the `N` in `:N`).
+    ///
+    pub fn connect(display: u32) -> Result<Self, Box<dyn Error>> {
+        let (conn, screen) = RustConnection::connect(Some(&format!(":{}", display)))?;
+        let root: Window = conn.setup().roots[screen].root;
+
+        let intern = |name: &str| -> Result<u32, Box<dyn Error>> {
+            Ok(conn.intern_atom(false, name.as_bytes())?.reply()?.atom)
+        };
+
+        let atoms = Atoms {
+            wm_protocols: intern("WM_PROTOCOLS")?,
+            wm_window_role: intern("WM_WINDOW_ROLE")?,
+            net_wm_ping: intern("_NET_WM_PING")?,
+        };
+
+        // Pongs are sent to the root window -- only the WM can redirect
+        // what's sent there, but anyone can listen in.
+        conn.change_window_attributes(
+            root,
+            &ChangeWindowAttributesAux::new().event_mask(EventMask::SUBSTRUCTURE_NOTIFY),
+        )?
+        .check()?;
+
+        Ok(Self { conn, root, atoms })
+    }
+
+    ///
+    /// A text property of `window`, if it's set.
+    ///
+    fn string_property(&self, window: Window, property: u32) -> Option<String> {
+        let reply = self
+            .conn
+            .get_property(false, window, property, AtomEnum::ANY, 0, 1024)
+            .ok()?
+            .reply()
+            .ok()?;
+
+        match reply.value.is_empty() {
+            true => None,
+            false => Some(String::from_utf8_lossy(&reply.value).into_owned()),
+        }
+    }
+
+    ///
+    /// `window`'s `WM_WINDOW_ROLE` -- which of an
+    /// application's windows it is (`browser`, `pop-up`, ...).
+    ///
+    pub fn role(&self, window: Window) -> Option<String> {
+        self.string_property(window, self.atoms.wm_window_role)
+    }
+
+    ///
+    /// Whether `window` answers `_NET_WM_PING`s
+    /// -- listed in its `WM_PROTOCOLS`.
+    ///
+    pub fn supports_ping(&self, window: Window) -> bool {
+        self.conn
+            .get_property(
+                false,
+                window,
+                self.atoms.wm_protocols,
+                AtomEnum::ATOM,
+                0,
+                64,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| Some(reply.value32()?.any(|a| a == self.atoms.net_wm_ping)))
+            .unwrap_or(false)
+    }
+
+    ///
+    /// Send `window` a `_NET_WM_PING` -- answered in [`XConnection::pongs`].
+    ///
+    pub fn send_ping(&self, window: Window) -> Result<(), Box<dyn Error>> {
+        let ping = ClientMessageEvent::new(
+            32,
+            window,
+            self.atoms.wm_protocols,
+            [self.atoms.net_wm_ping, CURRENT_TIME, window, 0, 0],
+        );
+
+        self.conn
+            .send_event(false, window, EventMask::NO_EVENT, ping)?;
+        self.conn.flush()?;
+
+        Ok(())
+    }
+
+    ///
+    /// The windows that answered a ping since this was last called.
+    ///
+    pub fn pongs(&self) -> Vec<Window> {
+        let mut pongs: Vec<Window> = vec![];
+
+        while let Ok(Some(event)) = self.conn.poll_for_event() {
+            let Event::ClientMessage(message) = event else {
+                continue;
+            };
+            let data: [u32; 5] = message.data.as_data32();
+
+            // Sent back with the root as the window,
+            // but the pinged one still in the data.
+            if message.window == self.root
+                && message.type_ == self.atoms.wm_protocols
+                && data[0] == self.atoms.net_wm_ping
+            {
+                pongs.push(data[2]);
+            }
+        }
+
+        pongs
+    }
+
+    ///
+    /// Disconnect the client behind `window` from XWayland
+    /// (like `xkill`) -- which usually ends it.
+    ///
+    pub fn kill_client(&self, window: Window) -> Result<(), Box<dyn Error>> {
+        self.conn.kill_client(window)?;
+        self.conn.flush()?;
+
+        Ok(())
+    }
+}
@@ -23,11 +23,17 @@ impl<BEnd: Backend> XdgDecorationHandler for Navda<BEnd> {
         toplevel.send_configure();
     }
     fn request_mode(&mut self, toplevel: ToplevelSurface, mode: Mode) {
-        if let Some(_w) = self
+        if let Some(w) = self
             .space
             .elements()
             .find(|window| matches!(window, AvWindow::Wayland(w) if w.toplevel() == &toplevel))
         {
+            // Window rules get the final say.
+            let mode = w
+                .window_rules()
+                .and_then(|rules| rules.decorations)
+                .unwrap_or(mode);
+
             toplevel.with_pending_state(|state| {
                 state.decoration_mode = Some(match mode {
                     Mode::ServerSide => {
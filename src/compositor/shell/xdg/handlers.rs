@@ -12,6 +12,7 @@ use smithay::{
         wayland_protocols::xdg::decoration::zv1::server::zxdg_toplevel_decoration_v1::Mode,
         wayland_server::protocol::wl_surface::WlSurface,
     },
+    utils::SERIAL_COUNTER,
     wayland::{
         compositor::with_states,
         shell::xdg::{decoration::XdgDecorationHandler, ToplevelSurface, XdgToplevelSurfaceData},
@@ -34,19 +35,22 @@ impl<BEnd: Backend> XdgActivationHandler for Navda<BEnd> {
         token_data: XdgActivationTokenData,
         surface: WlSurface,
     ) {
-        if token_data.timestamp.elapsed().as_secs() < 10 {
-            // Just grant the wish
-            let w = self
-                .space
-                .elements()
-                .find(|window| window.wl_surface().map(|s| s == surface).unwrap_or(false))
-                .cloned();
-            if let Some(window) = w {
-                self.space.raise_element(&window, true);
+        let keyboard = self.seat.get_keyboard().unwrap();
+        let focus = keyboard.current_focus();
+        let allowed = self
+            .focus_policy
+            .allows_activation(&token_data, focus.as_ref());
+
+        match self.window_for_surface(&surface) {
+            Some(window) if allowed && window.is_minimized() => self.restore_window(&window),
+            Some(window) if allowed => {
+                self.focus_window(&window, true, SERIAL_COUNTER.next_serial());
+            }
+            _ => {
+                // Discard the request
+                slog::debug!(self.log, "Denied activation request for {:?}", surface);
+                self.xdg_activation_state.remove_request(&token);
             }
-        } else {
-            // Discard the request
-            self.xdg_activation_state.remove_request(&token);
         }
     }
 
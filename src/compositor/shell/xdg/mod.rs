@@ -31,8 +31,8 @@ use smithay::{
         compositor::with_states,
         seat::WaylandFocus,
         shell::xdg::{
-            self, PopupSurface, PositionerState, ToplevelSurface, XdgShellHandler, XdgShellState,
-            XdgToplevelSurfaceData,
+            self, PopupSurface, PositionerState, ShellClient, ToplevelSurface, XdgShellHandler,
+            XdgShellState, XdgToplevelSurfaceData,
         },
     },
 };
@@ -45,7 +45,7 @@ use super::{
     avwindow::AvWindow,
     fullscreen_output_geometry,
     grabs::{MoveSurfaceGrab, ResizeData, ResizeState, ResizeSurfaceGrab},
-    place_new_window, SurfaceData,
+    ping, place_new_window, usable_geometry, SurfaceData,
 };
 
 mod decoration;
@@ -68,7 +68,15 @@ impl<BEnd: Backend> XdgShellHandler for Navda<BEnd> {
 
     fn new_toplevel(&mut self, surface: ToplevelSurface) {
         let window = AvWindow::Wayland(Window::new(surface));
-        place_new_window(&mut self.space, &window, true);
+        window.set_logger(&self.log);
+        place_new_window(&mut self.space, &window, None, true);
+    }
+
+    ///
+    /// [xdg_wm_base.pong](https://wayland.app/protocols/xdg-shell#xdg_wm_base:request:pong)
+    ///
+    fn client_pong(&mut self, client: ShellClient) {
+        ping::pong_received(&client);
     }
 
     ///
@@ -307,8 +315,6 @@ impl<BEnd: Backend> XdgShellHandler for Navda<BEnd> {
     }
 
     fn maximize_request(&mut self, surface: ToplevelSurface) {
-        // NOTE from `anvil`: This should use layer-shell when it is implemented to
-        // get the correct maximum size
         let window = self.window_for_surface(surface.wl_surface()).unwrap();
         let outputs_for_window = self.space.outputs_for_element(&window);
         let output = outputs_for_window
@@ -317,7 +323,15 @@ impl<BEnd: Backend> XdgShellHandler for Navda<BEnd> {
             .or_else(|| self.space.outputs().next())
             // Assumes that at least one output exists
             .expect("No outputs found");
-        let geometry = self.space.output_geometry(output).unwrap();
+        // Leave room for any panels.
+        let geometry = usable_geometry(&self.space, output).unwrap();
+
+        if let Some(location) = self.space.element_location(&window) {
+            window.save_geometry(Rectangle::from_loc_and_size(
+                location,
+                window.geometry().size,
+            ));
+        }
 
         surface.with_pending_state(|state| {
             state.states.set(xdg_toplevel::State::Maximized);
@@ -328,11 +342,25 @@ impl<BEnd: Backend> XdgShellHandler for Navda<BEnd> {
     }
 
     fn unmaximize_request(&mut self, surface: ToplevelSurface) {
+        let old_geo = self
+            .window_for_surface(surface.wl_surface())
+            .and_then(|window| Some((window.take_saved_geometry()?, window)));
+
         surface.with_pending_state(|state| {
             state.states.unset(xdg_toplevel::State::Maximized);
-            state.size = None;
+            state.size = old_geo.as_ref().map(|(geo, _)| geo.size);
         });
         surface.send_configure();
+
+        if let Some((geo, window)) = old_geo {
+            self.space.map_element(window, geo.loc, false);
+        }
+    }
+
+    fn minimize_request(&mut self, surface: ToplevelSurface) {
+        if let Some(window) = self.window_for_surface(surface.wl_surface()) {
+            self.minimize_window(&window);
+        }
     }
 
     fn grab(&mut self, surface: PopupSurface, seat: WlSeat, serial: Serial) {
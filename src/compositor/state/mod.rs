@@ -11,7 +11,7 @@ use std::sync::{atomic::AtomicBool, Arc, Mutex};
 use smithay::{
     delegate_compositor,
     desktop::{PopupManager, Space},
-    input::{pointer::CursorImageStatus, Seat, SeatState},
+    input::{keyboard::ModifiersState, pointer::CursorImageStatus, Seat, SeatState},
     reexports::{
         calloop::LoopHandle,
         wayland_server::{
@@ -20,7 +20,7 @@ use smithay::{
             Display, DisplayHandle,
         },
     },
-    utils::{Clock, Logical, Monotonic, Point},
+    utils::{Clock, Logical, Monotonic, Point, Rectangle},
     wayland::{
         compositor::CompositorState,
         data_device::DataDeviceState,
@@ -40,13 +40,32 @@ use smithay::{
     xwayland::{X11Wm, XWayland},
 };
 
-use super::{backend::Backend, shell::AvWindow};
+use super::{
+    animation::{AnimationPolicy, ClosingWindow},
+    backend::Backend,
+    clipboard::ClipboardState,
+    components::{ConfigBanner, Overview, Wallpaper, WindowSwitcher},
+    effects::EffectsPolicy,
+    focus::{FocusHistory, FocusPolicy},
+    input::KeyBindings,
+    protocols::{data_control::DataControlState, foreign_toplevel::ForeignToplevelState},
+    scale::ScalePolicy,
+    shell::{AvWindow, SnapPolicy, WindowRules, XConnection},
+};
 
 ///
-/// State for a client (application).
+/// State for a client (application)
+/// -- decided when it connects.
 ///
 #[derive(Debug, Default)]
-pub struct ClientState;
+pub struct ClientState {
+    ///
+    /// Whether it gets to see `zwlr_data_control_manager_v1`
+    /// -- the clipboard, without a focused window.
+    ///
+    pub data_control: bool,
+}
+
 impl ClientData for ClientState {
     /// Notification that a client was initialized
     fn initialized(&self, _client_id: ClientId) {}
@@ -248,6 +267,17 @@ pub struct Navda<BEnd: Backend + 'static> {
     ///
     pub suppressed_keys: Vec<u32>,
 
+    ///
+    /// Modifiers held down, as of the last key event.
+    ///
+    pub modifiers: ModifiersState,
+
+    ///
+    /// Window management shortcuts
+    /// (see the `keybinds` config section).
+    ///
+    pub key_bindings: KeyBindings,
+
     ///
     /// Location of the pointer.
     ///
@@ -290,6 +320,104 @@ pub struct Navda<BEnd: Backend + 'static> {
     ///
     pub dnd_icon: Option<WlSurface>,
 
+    ///
+    /// How keyboard focus moves between windows
+    /// (see the `focus` config section).
+    ///
+    pub focus_policy: FocusPolicy,
+
+    ///
+    /// Per-application overrides for new windows
+    /// (see the `windowRules` config section).
+    ///
+    pub window_rules: WindowRules,
+
+    ///
+    /// How dragged windows snap and tile
+    /// (see the `snapping` config section).
+    ///
+    pub snap_policy: SnapPolicy,
+
+    ///
+    /// Where the window being dragged would be tiled to,
+    /// if it were dropped right now.
+    ///
+    pub tile_preview: Option<Rectangle<i32, Logical>>,
+
+    ///
+    /// How windows animate
+    /// (see the `animations` config section).
+    ///
+    pub animation_policy: AnimationPolicy,
+
+    ///
+    /// Windows that are gone, but still animating out.
+    ///
+    pub closing_windows: Vec<ClosingWindow>,
+
+    ///
+    /// How windows are drawn
+    /// (see the `effects` config section).
+    ///
+    pub effects_policy: EffectsPolicy,
+
+    ///
+    /// Drawn beneath everything, on outputs without
+    /// a layer-shell background (see the `background` config section).
+    ///
+    pub wallpaper: Wallpaper,
+
+    ///
+    /// Says the config couldn't be loaded,
+    /// for a little while after starting up.
+    ///
+    pub config_banner: Option<ConfigBanner>,
+
+    ///
+    /// How outputs are scaled when they're
+    /// connected (see the `scaling` config section).
+    ///
+    pub scale_policy: ScalePolicy,
+
+    ///
+    /// Windows in most-recently-focused order.
+    ///
+    pub focus_history: FocusHistory,
+
+    ///
+    /// The Alt-Tab switcher, while it's open.
+    ///
+    pub switcher: Option<WindowSwitcher>,
+
+    ///
+    /// Overview mode, while it's open
+    /// (or still animating closed).
+    ///
+    pub overview: Option<Overview>,
+
+    ///
+    /// Minimized windows, oldest first.
+    ///
+    pub minimized: Vec<AvWindow>,
+
+    ///
+    /// Windows as advertised to taskbars
+    /// (see [`ForeignToplevelState`]).
+    ///
+    pub foreign_toplevel_state: ForeignToplevelState,
+
+    ///
+    /// The clipboard and primary selection
+    /// (see the `clipboard` config section).
+    ///
+    pub clipboard_state: ClipboardState,
+
+    ///
+    /// Clipboard tools' access to the selections
+    /// (see [`DataControlState`]).
+    ///
+    pub data_control_state: DataControlState,
+
     // </INPUT>
 
     // <XWAYLAND>
@@ -315,6 +443,11 @@ pub struct Navda<BEnd: Backend + 'static> {
     /// XWayland display id (for environment variables)
     ///
     pub x_display: Option<u32>,
+
+    ///
+    /// Our own connection to XWayland, for what `xwm` doesn't do.
+    ///
+    pub xconn: Option<XConnection>,
     // </XWAYLAND>
 }
 
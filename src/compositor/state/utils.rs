@@ -13,15 +13,19 @@ use smithay::{
         },
         PopupManager, Space,
     },
-    input::{keyboard::XkbConfig, pointer::CursorImageStatus, SeatState},
+    input::{
+        keyboard::{ModifiersState, XkbConfig},
+        pointer::CursorImageStatus,
+        SeatState,
+    },
     output::Output,
     reexports::{
         calloop::{generic::Generic, Interest, LoopHandle, Mode, PostAction},
-        wayland_server::Display,
+        wayland_server::{protocol::wl_surface::WlSurface, Display},
     },
     utils::{Clock, Point, Size},
     wayland::{
-        compositor::CompositorState,
+        compositor::{CompositorState, SurfaceData},
         data_device::DataDeviceState,
         fractional_scale::{with_fractional_scale, FractionalScaleManagerState},
         input_method::{InputMethodManagerState, InputMethodSeat},
@@ -44,8 +48,21 @@ use smithay::{
     xwayland::{X11Wm, XWayland, XWaylandEvent},
 };
 
-use crate::compositor::{
-    backend::Backend, components::Cursor, shell::AvWindow, state::ClientState,
+use crate::{
+    compositor::{
+        animation::AnimationPolicy,
+        backend::Backend,
+        clipboard::{ClipboardPolicy, ClipboardState},
+        components::{ConfigBanner, Cursor, Wallpaper},
+        effects::{offscreen, EffectsPolicy},
+        focus::{FocusHistory, FocusPolicy},
+        input::KeyBindings,
+        protocols::{data_control::DataControlState, foreign_toplevel::ForeignToplevelState},
+        scale::ScalePolicy,
+        shell::{AvWindow, SnapPolicy, WindowRules, XConnection},
+        state::ClientState,
+    },
+    Config,
 };
 
 use super::{CalloopData, Navda};
@@ -59,16 +76,36 @@ impl<BEnd: Backend + 'static> Navda<BEnd> {
     ) -> Self {
         let clock = Clock::new().expect("failed to initialize clock");
 
+        let focus_policy = FocusPolicy::from_config(&Config::config().focus, &log);
+        let window_rules = WindowRules::from_config(&Config::config().window_rules, &log);
+        let snap_policy = SnapPolicy::from_config(&Config::config().snapping);
+        let animation_policy = AnimationPolicy::from_config(&Config::config().animations, &log);
+        let effects_policy = EffectsPolicy::from_config(&Config::config().effects, &log);
+        let wallpaper = Wallpaper::from_config(&Config::config().background, &log);
+        let config_banner = Config::load_failure()
+            .map(|failure| ConfigBanner::from_failure(failure, clock.now().into()));
+        let scale_policy = ScalePolicy::from_config(&Config::config().scaling, &log);
+        let key_bindings = KeyBindings::from_config(&Config::config().keybinds, &log);
+
         // init wayland clients
         let socket_name = {
             let source = ListeningSocketSource::new_auto(log.clone()).unwrap();
             let socket_name = source.socket_name().to_string_lossy().into_owned();
             handle
                 .insert_source(source, |client_stream, _, data| {
+                    let client_state = ClientState {
+                        data_control: data
+                            .state
+                            .clipboard_state
+                            .policy()
+                            .data_control
+                            .allows(&client_stream),
+                    };
+
                     if let Err(err) = data
                         .display
                         .handle()
-                        .insert_client(client_stream, Arc::new(ClientState))
+                        .insert_client(client_stream, Arc::new(client_state))
                     {
                         slog::warn!(data.state.log, "Error adding wayland client: {}", err);
                     };
@@ -134,6 +171,10 @@ impl<BEnd: Backend + 'static> Navda<BEnd> {
 
         let dh = display.handle();
         let keyboard_shortcuts_inhibit_state = KeyboardShortcutsInhibitState::new::<Self>(&dh);
+        let foreign_toplevel_state = ForeignToplevelState::new::<Self>(&dh);
+        let clipboard_state =
+            ClipboardState::new(ClipboardPolicy::from_config(&Config::config().clipboard));
+        let data_control_state = DataControlState::new::<Self>(&dh);
 
         let xwayland = {
             let (xwayland, channel) = XWayland::new(log.clone(), &dh);
@@ -163,9 +204,16 @@ impl<BEnd: Backend + 'static> Navda<BEnd> {
                     .expect("Failed to set xwayland default cursor");
                     data.state.xwm = Some(wm);
                     data.state.x_display = Some(display);
+
+                    data.state.xconn = XConnection::connect(display)
+                        .map_err(|err| {
+                            slog::warn!(log2, "Failed to connect to XWayland: {}", err);
+                        })
+                        .ok();
                 }
                 XWaylandEvent::Exited => {
                     let _ = data.state.xwm.take();
+                    let _ = data.state.xconn.take();
                 }
             });
             if let Err(e) = ret {
@@ -201,8 +249,27 @@ impl<BEnd: Backend + 'static> Navda<BEnd> {
             presentation_state,
             fractional_scale_manager_state,
             dnd_icon: None,
+            focus_policy,
+            window_rules,
+            snap_policy,
+            tile_preview: None,
+            animation_policy,
+            closing_windows: Vec::new(),
+            effects_policy,
+            wallpaper,
+            config_banner,
+            scale_policy,
+            focus_history: FocusHistory::default(),
+            switcher: None,
+            overview: None,
+            minimized: Vec::new(),
+            foreign_toplevel_state,
+            clipboard_state,
+            data_control_state,
             log,
             suppressed_keys: Vec::new(),
+            modifiers: ModifiersState::default(),
+            key_bindings,
             pointer_location: (0.0, 0.0).into(),
             cursor_status,
             seat_name,
@@ -211,6 +278,23 @@ impl<BEnd: Backend + 'static> Navda<BEnd> {
             xwayland,
             xwm: None,
             x_display: None,
+            xconn: None,
+        }
+    }
+
+    ///
+    /// Take down the config banner once it's been up long enough.
+    ///
+    pub fn refresh_config_banner(&mut self) {
+        let now: Duration = self.clock.now().into();
+
+        if self
+            .config_banner
+            .as_ref()
+            .map(|banner| banner.expired(now))
+            .unwrap_or(false)
+        {
+            self.config_banner = None;
         }
     }
 }
@@ -225,6 +309,8 @@ pub fn post_repaint(
     let throttle = Some(Duration::from_secs(1));
 
     space.elements().for_each(|window| {
+        let scanout_output = offscreen_scanout_output(window, output);
+
         window.with_surfaces(|surface, states| {
             let primary_scanout_output = update_surface_primary_scanout_output(
                 surface,
@@ -232,7 +318,8 @@ pub fn post_repaint(
                 states,
                 render_element_states,
                 default_primary_scanout_output_compare,
-            );
+            )
+            .or_else(|| scanout_output(surface, states));
 
             if let Some(output) = primary_scanout_output {
                 with_fractional_scale(states, |fraction_scale| {
@@ -242,7 +329,7 @@ pub fn post_repaint(
         });
 
         if space.outputs_for_element(window).contains(output) {
-            window.send_frame(output, time, throttle, surface_primary_scanout_output);
+            window.send_frame(output, time, throttle, scanout_output);
         }
     });
     let map = smithay::desktop::layer_map_for_output(output);
@@ -267,6 +354,23 @@ pub fn post_repaint(
     }
 }
 
+///
+/// Which output a surface of `window` is mostly shown on -- windows drawn
+/// from textures (see [`offscreen`]) don't have their surfaces in the
+/// render element states, so are shown on `output` if anywhere.
+///
+fn offscreen_scanout_output<'a>(
+    window: &AvWindow,
+    output: &'a Output,
+) -> impl Fn(&WlSurface, &SurfaceData) -> Option<Output> + Copy + 'a {
+    let offscreen = offscreen::has_snapshot(window);
+
+    move |surface, states| {
+        surface_primary_scanout_output(surface, states)
+            .or_else(|| offscreen.then(|| output.clone()))
+    }
+}
+
 pub fn take_presentation_feedback(
     output: &Output,
     space: &Space<AvWindow>,
@@ -278,7 +382,7 @@ pub fn take_presentation_feedback(
         if space.outputs_for_element(window).contains(output) {
             window.take_presentation_feedback(
                 &mut output_presentation_feedback,
-                surface_primary_scanout_output,
+                offscreen_scanout_output(window, output),
                 |surface, _| {
                     surface_presentation_feedback_flags_from_states(surface, render_element_states)
                 },
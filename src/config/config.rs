@@ -1,6 +1,13 @@
 use crate::{
-    config::errors::UnexpectedToken,
-    CONST::{CONFIG_FILE, CONFIG_FOLDER},
+    config::{
+        errors::{IncludeError, InvalidConfig, UnexpectedToken},
+        include, syntax,
+    },
+    core::{
+        diagnostics::{Diagnostic, Format},
+        error::{compare_errors, Severity, Traceable, TraceableError},
+    },
+    CONST::{CONFIG_FILE, CONFIG_FOLDER, LAST_GOOD_CONFIG_FILE, SYSTEM_CONFIG_FOLDERS},
 };
 
 pub(crate) use json_comments::StripComments;
@@ -12,38 +19,143 @@ use json_tree::{parser::Token, Index, Indexer, JSONPath, Location, Source, Token
 use serde::Deserialize;
 
 use std::{
+    cell::RefCell,
+    cmp::Ordering,
     collections::HashMap,
     error::Error,
-    fs::{self, File},
-    io::BufReader,
-    path::PathBuf,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
-use super::sections::keybinds::Keybinds;
+use super::sections::{
+    animations::Animations, background::Background, clipboard::Clipboard, diagnostics::Diagnostics,
+    effects::Effects, focus::Focus, keybinds::Keybinds, scaling::Scaling, snapping::Snapping,
+    window_rules::WindowRule,
+};
 
 lazy_static! {
-    pub static ref PATH: PathBuf = CONFIG_FOLDER.join(*CONFIG_FILE);
+    ///
+    /// The user's config -- `None` without a `$HOME`.
+    ///
+    pub static ref PATH: Option<PathBuf> = CONFIG_FOLDER.as_ref().map(|f| f.join(*CONFIG_FILE));
 }
 
-static mut INDEX: Option<Index> = None;
+const DEFAULT_CONFIG: &str = include_str!("../../DefaultConfig.jsonc");
+
+// What's being loaded -- per thread, so reading one
+// config doesn't get mixed up with reading another.
+thread_local! {
+    ///
+    /// Each file's JSON index, in the order they were merged.
+    ///
+    static INDEX: RefCell<Vec<(String, Index)>> = RefCell::new(Vec::new());
+
+    ///
+    /// Each file's contents, as they were read -- for
+    /// showing where errors are (see [`Traceable::snippet`]).
+    ///
+    static SOURCES: RefCell<Vec<(String, Arc<str>)>> = RefCell::new(Vec::new());
+
+    ///
+    /// The file being loaded (or one it includes),
+    /// when it isn't [`PATH`].
+    ///
+    static FILE: RefCell<Option<PathBuf>> = RefCell::new(None);
+
+    ///
+    /// Errors found while loading -- only shown once the whole config's
+    /// been read, since the `diagnostics` section decides which to show.
+    ///
+    static REPORTED: RefCell<Vec<Box<dyn TraceableError>>> = RefCell::new(Vec::new());
+}
 
 static mut CONFIG: Option<Config> = None;
 
+static mut FAILURE: Option<LoadFailure> = None;
+
+///
+/// A config read from its files, along with them merged together.
+///
+type Loaded = Result<(Config, serde_json::Value), Box<dyn Error>>;
+
 #[derive(Deserialize, Debug)]
 pub struct Config {
     pub keybinds: Keybinds,
+
+    #[serde(default)]
+    pub focus: Focus,
+
+    #[serde(default)]
+    pub snapping: Snapping,
+
+    #[serde(default)]
+    pub animations: Animations,
+
+    #[serde(default)]
+    pub effects: Effects,
+
+    #[serde(default)]
+    pub background: Background,
+
+    #[serde(default)]
+    pub scaling: Scaling,
+
+    #[serde(default)]
+    pub clipboard: Clipboard,
+
+    #[serde(default)]
+    pub diagnostics: Diagnostics,
+
+    #[serde(default, rename = "windowRules")]
+    pub window_rules: Vec<WindowRule>,
 }
 
 impl Config {
     pub fn path() -> String {
-        PATH.to_str().unwrap().to_string()
+        let file: Option<PathBuf> = FILE.with(|f| f.borrow().clone());
+        let file: &Path = file.as_deref().or(PATH.as_deref()).unwrap_or(*CONFIG_FILE);
+
+        file.to_str().unwrap().to_string()
+    }
+
+    ///
+    /// Where `path` is in the config -- in the last file setting it,
+    /// since that's the one that counts.
+    ///
+    /// `key` is as in [`Traceable::combine`].
+    ///
+    pub fn locate(path: &JSONPath, key: Option<bool>) -> Option<Traceable> {
+        INDEX.with(|i| {
+            i.borrow().iter().rev().find_map(|(file, index)| {
+                let at = index.get(path)?;
+
+                Some(Traceable::combine(file, at, key).with_source(Self::source(file)))
+            })
+        })
     }
 
     ///
-    /// Returns the config file's JSON index.
+    /// `at` (a line and column) in the file being read.
     ///
-    pub fn index<'a>() -> &'a Index {
-        unsafe { INDEX.as_ref().unwrap() }
+    pub fn trace(at: (usize, usize)) -> Traceable {
+        let path: String = Self::path();
+        let source: Option<Arc<str>> = Self::source(&path);
+
+        Traceable::new(path, at).with_source(source)
+    }
+
+    ///
+    /// What was read from `file`.
+    ///
+    fn source(file: &str) -> Option<Arc<str>> {
+        SOURCES.with(|s| {
+            s.borrow()
+                .iter()
+                .rev()
+                .find(|(f, _)| f == file)
+                .map(|(_, src)| src.clone())
+        })
     }
 
     ///
@@ -54,75 +166,530 @@ impl Config {
     }
 
     ///
-    /// Loads the config.
+    /// Holds onto `errors` until the config's done loading.
+    ///
+    pub fn report(errors: Vec<Box<dyn TraceableError>>) {
+        REPORTED.with(|r| r.borrow_mut().extend(errors))
+    }
+
+    ///
+    /// Everything reported so far, in order.
+    ///
+    fn take_reported() -> Vec<Box<dyn TraceableError>> {
+        let mut errors: Vec<Box<dyn TraceableError>> =
+            REPORTED.with(|r| std::mem::take(&mut *r.borrow_mut()));
+
+        errors.sort_by(|a, b| compare_errors(a, b).unwrap_or(Ordering::Equal));
+
+        errors
+    }
+
+    ///
+    /// Why the config couldn't be loaded, if it
+    /// couldn't -- for the banner in the session.
+    ///
+    pub fn load_failure<'a>() -> Option<&'a LoadFailure> {
+        unsafe { FAILURE.as_ref() }
+    }
+
+    ///
+    /// Loads the config -- the user's, over any from the system
+    /// (see [`Config::system_files`]).
+    ///
+    /// If it's broken, the last config that loaded is used instead
+    /// (or the default one) -- see [`Config::load_failure`].
     ///
     /// A CALL TO THIS FUNCTION SHOULD BE NEAR THE TOP OF `main.rs`
     ///
     pub fn load() -> Result<(), Box<dyn Error>> {
-        // Recursively crate config dir if it doesn't exist.
-        fs::create_dir_all(&*CONFIG_FOLDER)
-            .expect("Could not create config folder '$XDG_CONFIG_HOME/avdan'.");
-
-        let file: File = match fs::OpenOptions::new().read(true).open(&*PATH) {
-            Err(_) => {
-                // File probs doesn't exist
-                let default = include_str!("../../DefaultConfig.jsonc");
-                fs::write(&*PATH, default)
-                    .expect(&format!("{} not writeable!", PATH.to_str().unwrap()));
-
-                fs::OpenOptions::new()
-                    .read(true)
-                    .open(&*PATH)
-                    .expect("Couldn't read newly-written default config!")
+        let mut layers: Vec<(PathBuf, String)> = Self::system_files()
+            .into_iter()
+            .filter_map(|file: PathBuf| match fs::read_to_string(&file) {
+                Ok(src) => Some((file, src)),
+                Err(err) => {
+                    println!("Couldn't read {}: {}", file.display(), err);
+                    None
+                }
+            })
+            .collect();
+
+        if let Some(path) = &*PATH {
+            // Without one from the system, start the user off with the default.
+            if layers.is_empty() && fs::metadata(path).is_err() {
+                let written: io::Result<()> = fs::create_dir_all(path.parent().unwrap())
+                    .and_then(|_| fs::write(path, DEFAULT_CONFIG));
+
+                if let Err(err) = written {
+                    println!(
+                        "Couldn't write the default config to {}: {}",
+                        path.display(),
+                        err
+                    );
+                }
+            }
+
+            if path.is_file() {
+                layers.push((path.clone(), fs::read_to_string(path)?));
             }
-            Ok(o) => o,
+        }
+
+        if layers.is_empty() {
+            println!("There's no config to load -- using the default one.");
+
+            unsafe { CONFIG = Some(Self::default_config()?) }
+
+            return Ok(());
+        }
+
+        let path: String = layers.last().unwrap().0.to_str().unwrap().to_string();
+
+        let (res, errors) = Self::read(&layers);
+
+        // Without a config, there's nothing saying what to hide.
+        let shown: Vec<&Box<dyn TraceableError>> = match &res {
+            Ok((o, _)) => errors
+                .iter()
+                .filter(|e| o.diagnostics.shows(e.severity()))
+                .collect(),
+            Err(_) => errors.iter().collect(),
         };
 
-        let reader: BufReader<File> = BufReader::new(file);
+        let format: Format = Format::from_env();
 
-        let stripped: StripComments<_> = StripComments::new(reader);
+        for err in shown {
+            println!("{}", format.render_one(err))
+        }
 
-        let src_map: HashMap<JSONPath, Location> = {
-            let mut src: Source = Source::new(
-                // TODO: @Sammy99jsp Prettier Error
-                fs::read_to_string(&*PATH).expect("Missing AvdanOS config!"),
-            );
+        let fatal: Vec<Diagnostic> = errors
+            .iter()
+            .filter(|e| e.severity() == Severity::Error)
+            .map(Diagnostic::from)
+            .collect();
 
-            let tokens: Vec<Token> = match Tokenizer::tokenize(&mut src) {
-                Ok(tkns) => tkns,
-                Err(err) => {
-                    let a: UnexpectedToken = UnexpectedToken::from_parser(err);
+        if let (Ok((o, merged)), true) = (res, fatal.is_empty()) {
+            // Something to fall back on next time -- along with everything
+            // it includes, in case they're the next thing to break.
+            if let Some(folder) = &*CONFIG_FOLDER {
+                let copy: String = serde_json::to_string_pretty(&merged)?;
 
-                    println!("{}", a);
+                let written: io::Result<()> = fs::create_dir_all(folder)
+                    .and_then(|_| fs::write(folder.join(*LAST_GOOD_CONFIG_FILE), copy));
 
-                    panic!()
+                if let Err(err) = written {
+                    println!("Couldn't keep a copy of the config: {}", err);
                 }
-            };
-            let root: Value = match Value::parse(&mut tokens.iter().peekable()) {
-                Ok(r) => r,
+            }
 
-                Err(err) => {
-                    let a: UnexpectedToken = UnexpectedToken::from_parser(err);
+            unsafe { CONFIG = Some(o) }
+
+            return Ok(());
+        }
 
-                    println!("{}", a);
+        let (o, fallback): (Config, &'static str) = Self::fallback()?;
 
-                    panic!()
+        println!("Couldn't load {} -- using {} instead.", path, fallback);
+
+        unsafe {
+            CONFIG = Some(o);
+            FAILURE = Some(LoadFailure {
+                path,
+                errors: fatal,
+                fallback,
+            });
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// The system's configs (in `$XDG_CONFIG_DIRS`, like
+    /// `/etc/xdg/avdan/Compositor.jsonc`) that exist -- least important
+    /// first, so they can be merged in order under the user's.
+    ///
+    fn system_files() -> Vec<PathBuf> {
+        SYSTEM_CONFIG_FOLDERS
+            .iter()
+            .rev()
+            .map(|f| f.join(*CONFIG_FILE))
+            .filter(|f| f.is_file())
+            .collect()
+    }
+
+    ///
+    /// The last config that loaded -- or the default one, if there isn't one
+    /// (or it's broken too, after an update).
+    ///
+    fn fallback() -> Result<(Config, &'static str), Box<dyn Error>> {
+        let last_good: Option<PathBuf> = CONFIG_FOLDER
+            .as_ref()
+            .map(|f| f.join(*LAST_GOOD_CONFIG_FILE));
+
+        if let Some(Ok(src)) = last_good.as_ref().map(fs::read_to_string) {
+            let (res, errors) = Self::read(&[(last_good.unwrap(), src)]);
+            let clean: bool = errors.iter().all(|e| e.severity() != Severity::Error);
+
+            if let (Ok((o, _)), true) = (res, clean) {
+                return Ok((o, "the last config that worked"));
+            }
+        }
+
+        Ok((Self::default_config()?, "the default config"))
+    }
+
+    ///
+    /// The config that ships with the compositor.
+    ///
+    fn default_config() -> Result<Config, Box<dyn Error>> {
+        let default: (PathBuf, String) = ("DefaultConfig.jsonc".into(), DEFAULT_CONFIG.into());
+
+        let (o, _) = Self::read(&[default]).0?;
+
+        Ok(o)
+    }
+
+    ///
+    /// Loads the config at `path` (leaving `$XDG_CONFIG_HOME` alone, but
+    /// over the system's, as it would be), and returns every problem
+    /// with it, in order.
+    ///
+    /// Used by `navda --check-config <PATH>`.
+    ///
+    pub fn check(path: &Path) -> io::Result<Vec<Box<dyn TraceableError>>> {
+        let src: String = fs::read_to_string(path)?;
+
+        let mut layers: Vec<(PathBuf, String)> = Self::system_files()
+            .into_iter()
+            // Checking one of them, which would be there twice.
+            .filter(|f| f.canonicalize().ok() != path.canonicalize().ok())
+            .filter_map(|f: PathBuf| Some((f.clone(), fs::read_to_string(f).ok()?)))
+            .collect();
+
+        layers.push((path.to_path_buf(), src));
+
+        let (_, errors) = Self::read(&layers);
+
+        Ok(errors)
+    }
+
+    ///
+    /// Reads each of `layers` (files, and their contents), along with
+    /// everything they include, and deserializes them merged together
+    /// in order -- returning the merged JSON too.
+    ///
+    /// The last layer is the main file, that errors
+    /// not in any one file are put down to.
+    ///
+    /// When a file isn't valid JSON, every syntax error
+    /// that can be found in it is reported.
+    ///
+    /// Comes with everything reported along the way, in order.
+    ///
+    fn read(layers: &[(PathBuf, String)]) -> (Loaded, Vec<Box<dyn TraceableError>>) {
+        // Anything left over from before isn't about these.
+        Self::take_reported();
+
+        let res: Loaded = Self::read_layers(layers);
+
+        (res, Self::take_reported())
+    }
+
+    ///
+    /// [`Config::read`], reporting as it goes.
+    ///
+    fn read_layers(layers: &[(PathBuf, String)]) -> Loaded {
+        INDEX.with(|i| i.borrow_mut().clear());
+        SOURCES.with(|s| s.borrow_mut().clear());
+
+        let mut merged: serde_json::Value = serde_json::Value::Null;
+
+        // Every layer gets read, to report as much as possible.
+        let failed: Vec<bool> = layers
+            .iter()
+            .map(|(path, src)| {
+                let root: PathBuf = path.canonicalize().unwrap_or_else(|_| path.clone());
+                let folder: PathBuf = root.parent().map(Path::to_path_buf).unwrap_or_default();
+
+                Self::read_file(path, src, &folder, &mut vec![root], &mut merged).is_err()
+            })
+            .collect();
+
+        let (path, src): &(PathBuf, String) = layers.last().ok_or("There's no config to read.")?;
+
+        // Anything found from here on is the main file's problem.
+        FILE.with(|f| *f.borrow_mut() = Some(path.clone()));
+
+        if failed.contains(&true) {
+            return Err("The config isn't valid JSON.".into());
+        }
+
+        let config: Result<Config, InvalidConfig> = match INDEX.with(|i| i.borrow().len()) {
+            // Reading the file itself keeps lines and columns in the errors.
+            1 => serde_json::from_reader(StripComments::new(src.as_bytes()))
+                .map_err(|err: serde_json::Error| InvalidConfig::from_serde(&err)),
+            _ => Self::from_merged(&merged),
+        };
+
+        match config {
+            Ok(o) => Ok((o, merged)),
+            Err(err) => {
+                Self::report(vec![Box::new(err)]);
+
+                Err("The config doesn't match its schema.".into())
+            }
+        }
+    }
+
+    ///
+    /// Deserializes the `merged` files -- with any error put down
+    /// to the file (and line) that set what it's about.
+    ///
+    fn from_merged(merged: &serde_json::Value) -> Result<Config, InvalidConfig> {
+        let (text, spans) = include::write_out(merged);
+
+        serde_json::from_str(&text).map_err(|err: serde_json::Error| {
+            // It's all on one line, so the column's how far in the error is.
+            let offset: usize = err.column().saturating_sub(1);
+
+            let keys: &[String] = spans
+                .iter()
+                .filter(|(_, bytes)| bytes.contains(&offset))
+                .min_by_key(|(_, bytes)| bytes.len())
+                .map(|(keys, _)| keys.as_slice())
+                .unwrap_or_default();
+
+            // Anything not in a section is the main file's problem.
+            let at: Traceable = Self::locate_keys(keys).unwrap_or_else(|| Self::trace((1, 1)));
+
+            InvalidConfig::from_serde_at(&err, at)
+        })
+    }
+
+    ///
+    /// Where the value at `keys` (from the root) was last set.
+    ///
+    fn locate_keys(keys: &[String]) -> Option<Traceable> {
+        let (section, keys) = keys.split_first()?;
+        let path: JSONPath = format!("$.{}", section).try_into().ok()?;
+
+        let path: JSONPath = keys.iter().fold(path, |p, key| p.push(key.clone()));
+
+        Self::locate(&path, Some(false))
+    }
+
+    ///
+    /// Reads whatever one file of the config includes into
+    /// `merged`, then the file itself on top (see [`include`]).
+    ///
+    /// `stack` is the files that led here, to catch includes going round in circles.
+    ///
+    fn read_file(
+        path: &Path,
+        src: &str,
+        folder: &Path,
+        stack: &mut Vec<PathBuf>,
+        merged: &mut serde_json::Value,
+    ) -> Result<(), Box<dyn Error>> {
+        FILE.with(|f| *f.borrow_mut() = Some(path.to_path_buf()));
+        SOURCES.with(|s| s.borrow_mut().push((Self::path(), src.into())));
+
+        let file: String = Self::path();
+        let index: Index = Self::index_file(src)?;
+
+        let mut value: serde_json::Value = serde_json::from_reader(StripComments::new(
+            src.as_bytes(),
+        ))
+        .map_err(|err: serde_json::Error| {
+            Self::report(vec![Box::new(InvalidConfig::from_serde(&err))]);
+
+            "The config isn't valid JSON."
+        })?;
+
+        let at: Traceable = Self::trace(include::position(src));
+        let patterns: Vec<String> = include::take_patterns(&mut value).unwrap_or_else(|message| {
+            Self::report(vec![Box::new(IncludeError(message, at.clone()))]);
+            vec![]
+        });
+
+        let dir: PathBuf = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        for pattern in patterns {
+            let files: Vec<PathBuf> = match include::resolve(&pattern, &dir, folder) {
+                Ok(f) => f,
+                Err(message) => {
+                    Self::report(vec![Box::new(IncludeError(message, at.clone()))]);
+                    continue;
                 }
             };
 
-            let mut index: HashMap<JSONPath, Location> = HashMap::new();
+            for file in files {
+                if stack.contains(&file) {
+                    let message: String = format!("`{}` ends up including itself.", pattern);
+                    Self::report(vec![Box::new(IncludeError(message, at.clone()))]);
+                    continue;
+                }
+
+                let src: String = match fs::read_to_string(&file) {
+                    Ok(s) => s,
+                    Err(err) => {
+                        let message: String =
+                            format!("Couldn't read `{}`: {}", file.display(), err);
+                        Self::report(vec![Box::new(IncludeError(message, at.clone()))]);
+                        continue;
+                    }
+                };
+
+                stack.push(file.clone());
+                Self::read_file(&file, &src, folder, stack, merged)?;
+                stack.pop();
+            }
+        }
 
-            Indexer::index(&root, &mut index, None);
+        // Its own settings win over everything it includes.
+        FILE.with(|f| *f.borrow_mut() = Some(path.to_path_buf()));
+        INDEX.with(|i| i.borrow_mut().push((file, index)));
 
-            index
+        include::merge(merged, value);
+
+        Ok(())
+    }
+
+    ///
+    /// Tokenizes and indexes `src` (the contents of [`Config::path`]).
+    ///
+    fn index_file(src: &str) -> Result<Index, Box<dyn Error>> {
+        let mut source: Source = Source::new(src.to_string());
+
+        let parsed = Tokenizer::tokenize(&mut source)
+            .map_err(|err| UnexpectedToken::from_parser(err, src))
+            .and_then(|tokens: Vec<Token>| {
+                Value::parse(&mut tokens.iter().peekable())
+                    .map_err(|err| UnexpectedToken::from_parser(err, src))
+            });
+
+        let root: Value = match parsed {
+            Ok(r) => r,
+
+            Err(err) => {
+                // `json_tree` stops at the first error -- look for the rest.
+                let errors: Vec<Box<dyn TraceableError>> = syntax::check(src)
+                    .into_iter()
+                    .map(|e| Box::new(e) as Box<dyn TraceableError>)
+                    .collect();
+
+                match errors.is_empty() {
+                    true => Self::report(vec![err]),
+                    false => Self::report(errors),
+                }
+
+                return Err("The config isn't valid JSON.".into());
+            }
         };
 
-        unsafe { INDEX = Some(src_map) }
+        let mut index: HashMap<JSONPath, Location> = HashMap::new();
 
-        let o: Config = serde_json::from_reader(stripped)?;
+        Indexer::index(&root, &mut index, None);
 
-        unsafe { CONFIG = Some(o) }
+        Ok(index)
+    }
+}
 
-        Ok(())
+///
+/// Why the config didn't load.
+///
+#[derive(Debug)]
+pub struct LoadFailure {
+    pub path: String,
+    pub errors: Vec<Diagnostic>,
+
+    ///
+    /// What's being used instead.
+    ///
+    pub fallback: &'static str,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        path::{Path, PathBuf},
+    };
+
+    use crate::core::error::{AvError, TraceableError};
+
+    use super::Config;
+
+    ///
+    /// A folder of config files, gone once the test's done with it.
+    ///
+    struct TempFolder(PathBuf);
+
+    impl TempFolder {
+        fn new(name: &str) -> Self {
+            let path = env::temp_dir().join(format!("navda-{}-{}", name, std::process::id()));
+            fs::create_dir_all(&path).unwrap();
+
+            Self(path)
+        }
+
+        fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempFolder {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn system() -> (PathBuf, String) {
+        (
+            "/etc/xdg/avdan/Compositor.jsonc".into(),
+            "{\n  \"keybinds\": {}\n}".into(),
+        )
+    }
+
+    #[test]
+    fn system_layer_keeps_user_locations() {
+        let user: (PathBuf, String) = (
+            "/home/user/.config/avdan/Compositor.jsonc".into(),
+            [
+                "{",
+                "  // Half see-through.",
+                r#"  "windowRules": [{ "match": {}, "opacity": "half" }]"#,
+                "}",
+            ]
+            .join("\n"),
+        );
+
+        let (res, errors) = Config::read(&[system(), user.clone()]);
+        assert!(res.is_err());
+
+        let invalid = errors
+            .iter()
+            .find(|e| e.code() == "CONFIG_INVALID")
+            .unwrap();
+
+        assert_eq!(invalid.location().path(), user.0.to_str().unwrap());
+        assert_eq!(invalid.location().line(), 3);
+    }
+
+    #[test]
+    fn including_file_wins() {
+        let folder = TempFolder::new("including-file-wins");
+        fs::write(
+            folder.path().join("base.jsonc"),
+            r#"{ "focus": { "mode": "click" } }"#,
+        )
+        .unwrap();
+
+        let main: (PathBuf, String) = (
+            folder.path().join("Compositor.jsonc"),
+            r#"{ "$include": "base.jsonc", "keybinds": {}, "focus": { "mode": "sloppy" } }"#.into(),
+        );
+
+        let (config, _) = Config::read(&[system(), main]).0.unwrap();
+
+        assert_eq!(config.focus.mode.as_str(), "sloppy");
     }
 }
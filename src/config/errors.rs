@@ -6,7 +6,7 @@ use crate::core::error::{AvError, Traceable, TraceableError};
 
 use json_tree::{ParserError, TokenContent};
 
-use super::config;
+use super::Config;
 
 ///
 /// Error in parsing the config file.
@@ -15,7 +15,11 @@ use super::config;
 pub struct UnexpectedToken(pub String, pub Traceable);
 
 impl UnexpectedToken {
-    pub fn from_parser<T>(p: ParserError<T>) -> Self
+    ///
+    /// `src` is the config's contents, for
+    /// where it ends (if that's the problem).
+    ///
+    pub fn from_parser<T>(p: ParserError<T>, src: &str) -> Box<dyn TraceableError>
     where
         T: Into<TokenContent>,
     {
@@ -23,18 +27,25 @@ impl UnexpectedToken {
             ParserError::UnexpectedToken(t) => {
                 let to: TokenContent = t.into();
 
-                let loc: Traceable =
-                    Traceable::new(config::PATH.to_str().unwrap().to_string(), to.loc());
+                let loc: Traceable = Config::trace(to.loc()).spanning(&to.content);
 
                 // Convert to our friendlier error format
-                UnexpectedToken(
+                Box::new(UnexpectedToken(
                     // rust-analyzer (and I guess cargo check) gives up on the line below
                     // but it *does* compile -- smh my head
                     to.content, loc,
-                )
+                ))
             }
 
-            ParserError::UnexpectedEnd(_, _) => unimplemented!(),
+            ParserError::UnexpectedEnd(_, _) => {
+                let line: usize = src.lines().count().max(1);
+                let column: usize = src.lines().last().unwrap_or("").chars().count() + 1;
+
+                Box::new(SyntaxError(
+                    "The file ended in the middle of something.".into(),
+                    Config::trace((line, column)),
+                ))
+            }
         }
     }
 }
@@ -48,3 +59,77 @@ impl TraceableError for UnexpectedToken {
         format!("Unexpected token `{}`", self.0.blue())
     }
 }
+
+///
+/// The config isn't valid JSON(C) --
+/// found by [`syntax::check`](super::syntax::check).
+///
+#[AvError(TraceableError, CONFIG_SYNTAX_ERROR, "Config: Syntax Error")]
+pub struct SyntaxError(pub String, pub Traceable);
+
+impl TraceableError for SyntaxError {
+    fn location(&self) -> &Traceable {
+        &self.1
+    }
+
+    fn description(&self) -> String {
+        self.0.clone()
+    }
+}
+
+///
+/// The config doesn't fit what [`Config`] expects
+/// (a missing `keybinds` section, a bad window rule, ...).
+///
+#[AvError(TraceableError, CONFIG_INVALID, "Config: Invalid")]
+pub struct InvalidConfig(pub String, pub Traceable);
+
+impl InvalidConfig {
+    pub fn from_serde(err: &serde_json::Error) -> Self {
+        let at: (usize, usize) = match err.line() {
+            0 => (1, 1),
+            line => (line, err.column()),
+        };
+
+        Self::from_serde_at(err, Config::trace(at))
+    }
+
+    ///
+    /// The error, somewhere other than where `serde_json`
+    /// says it is -- for JSON that isn't in any one file.
+    ///
+    pub fn from_serde_at(err: &serde_json::Error, at: Traceable) -> Self {
+        // `serde_json` tacks the location onto the message.
+        let location: String = format!(" at line {} column {}", err.line(), err.column());
+        let message: String = err.to_string();
+
+        InvalidConfig(message.trim_end_matches(&location).to_string(), at)
+    }
+}
+
+impl TraceableError for InvalidConfig {
+    fn location(&self) -> &Traceable {
+        &self.1
+    }
+
+    fn description(&self) -> String {
+        self.0.clone()
+    }
+}
+
+///
+/// An `"$include"` that can't be followed -- a missing file,
+/// one outside the config folder, a cycle...
+///
+#[AvError(TraceableError, CONFIG_INCLUDE_ERROR, "Config: Include Error")]
+pub struct IncludeError(pub String, pub Traceable);
+
+impl TraceableError for IncludeError {
+    fn location(&self) -> &Traceable {
+        &self.1
+    }
+
+    fn description(&self) -> String {
+        self.0.clone()
+    }
+}
@@ -0,0 +1,355 @@
+//!
+//! `"$include"` -- pulling other files into the config, so a shared
+//! base can be kept apart from each machine's own outputs and inputs:
+//!
+//! ```jsonc
+//! {
+//!     "$include": ["base.jsonc", "machines/*.jsonc"],
+//!     // ...
+//! }
+//! ```
+//!
+//! Paths are relative to the file including them, and have to stay
+//! inside the config folder. `*` and `?` match within a single part
+//! of a path, and whatever they match is included in alphabetical order.
+//!
+//! The files it includes are merged first, in order, and the including
+//! file last -- so later files override earlier ones, and the including
+//! file overrides them all. Objects are merged key by key; anything else
+//! (arrays too) is replaced outright.
+//!
+
+use serde_json::Value;
+
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+pub const DIRECTIVE: &str = "$include";
+
+///
+/// Takes the directive out of (the root of) `value`,
+/// returning the paths it lists.
+///
+pub fn take_patterns(value: &mut Value) -> Result<Vec<String>, String> {
+    let directive: Value = match value.as_object_mut().and_then(|o| o.remove(DIRECTIVE)) {
+        Some(d) => d,
+        None => return Ok(vec![]),
+    };
+
+    match directive {
+        Value::String(s) => Ok(vec![s]),
+
+        Value::Array(a) => a
+            .into_iter()
+            .map(|v: Value| match v {
+                Value::String(s) => Ok(s),
+                v => Err(format!("Expected a path to include, got `{}`.", v)),
+            })
+            .collect(),
+
+        v => Err(format!(
+            "`{}` takes a path, or a list of them -- got `{}`.",
+            DIRECTIVE, v
+        )),
+    }
+}
+
+///
+/// Where the directive is in `src`, for errors about it.
+///
+pub fn position(src: &str) -> (usize, usize) {
+    let needle: String = format!("\"{}\"", DIRECTIVE);
+
+    src.lines()
+        .enumerate()
+        .find_map(|(n, line)| {
+            let column: usize = line.find(&needle)?;
+            Some((n + 1, line[..column].chars().count() + 1))
+        })
+        .unwrap_or((1, 1))
+}
+
+///
+/// The files `pattern` (from a file in `from`) stands for
+/// -- which all have to be in `folder`.
+///
+/// A pattern without wildcards has to match a file,
+/// but one with them is allowed to match nothing.
+///
+pub fn resolve(pattern: &str, from: &Path, folder: &Path) -> Result<Vec<PathBuf>, String> {
+    let wildcards: [char; 2] = ['*', '?'];
+
+    let mut candidates: Vec<PathBuf> = vec![PathBuf::new()];
+
+    for component in from.join(pattern).components() {
+        let part: String = component.as_os_str().to_string_lossy().into_owned();
+
+        if !part.contains(wildcards) {
+            candidates.iter_mut().for_each(|c| c.push(component));
+            continue;
+        }
+
+        candidates = candidates
+            .into_iter()
+            .flat_map(|dir: PathBuf| {
+                let mut names: Vec<String> = fs::read_dir(&dir)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|e| e.file_name().to_string_lossy().into_owned())
+                    // Hidden files only when asked for.
+                    .filter(|name| !name.starts_with('.') || part.starts_with('.'))
+                    .filter(|name| wildcard(&part, name))
+                    .collect();
+
+                names.sort();
+                names.into_iter().map(move |name| dir.join(name))
+            })
+            .collect();
+    }
+
+    if !pattern.contains(wildcards) && !candidates.iter().any(|c| c.is_file()) {
+        return Err(format!("`{}` doesn't exist.", pattern));
+    }
+
+    let folder: PathBuf = folder
+        .canonicalize()
+        .unwrap_or_else(|_| folder.to_path_buf());
+
+    candidates
+        .into_iter()
+        .filter(|c| c.is_file())
+        .map(|c| {
+            let file: PathBuf = c
+                .canonicalize()
+                .map_err(|err| format!("Couldn't include `{}`: {}", c.display(), err))?;
+
+            match file.starts_with(&folder) {
+                true => Ok(file),
+                false => Err(format!(
+                    "`{}` is outside the config folder ({}).",
+                    pattern,
+                    folder.display()
+                )),
+            }
+        })
+        .collect()
+}
+
+///
+/// Whether `name` matches `pattern`, where `*` is
+/// any run of characters and `?` any one.
+///
+fn wildcard(pattern: &str, name: &str) -> bool {
+    fn matches(p: &[char], n: &[char]) -> bool {
+        match (p.first(), n.first()) {
+            (None, _) => n.is_empty(),
+            (Some('*'), _) => matches(&p[1..], n) || (!n.is_empty() && matches(p, &n[1..])),
+            (Some('?'), Some(_)) => matches(&p[1..], &n[1..]),
+            (Some(a), Some(b)) if a == b => matches(&p[1..], &n[1..]),
+            _ => false,
+        }
+    }
+
+    let p: Vec<char> = pattern.chars().collect();
+    let n: Vec<char> = name.chars().collect();
+
+    matches(&p, &n)
+}
+
+///
+/// Puts `over` on top of `base` -- objects are merged
+/// key by key, and anything else replaced.
+///
+pub fn merge(base: &mut Value, over: Value) {
+    match (base, over) {
+        (Value::Object(base), Value::Object(over)) => {
+            for (key, value) in over {
+                match base.get_mut(&key) {
+                    Some(b) => merge(b, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+
+        (base, over) => *base = over,
+    }
+}
+
+///
+/// `value` written out on a single line, along with where each of
+/// its values went in it -- as its keys (from the root) and bytes.
+///
+/// Merged JSON has no file of its own, so this is what `serde_json`'s
+/// errors about it point into. Values in arrays are put down to the array.
+///
+pub fn write_out(value: &Value) -> (String, Vec<(Vec<String>, Range<usize>)>) {
+    ///
+    /// `keys` is `None` inside arrays.
+    ///
+    fn write(
+        value: &Value,
+        keys: Option<&[String]>,
+        out: &mut String,
+        spans: &mut Vec<(Vec<String>, Range<usize>)>,
+    ) {
+        let start: usize = out.len();
+
+        match value {
+            Value::Object(o) => {
+                out.push('{');
+
+                for (n, (key, v)) in o.iter().enumerate() {
+                    if n > 0 {
+                        out.push(',');
+                    }
+
+                    out.push_str(&Value::String(key.clone()).to_string());
+                    out.push(':');
+
+                    let keys: Option<Vec<String>> =
+                        keys.map(|keys| [keys, &[key.clone()]].concat());
+                    write(v, keys.as_deref(), out, spans);
+                }
+
+                out.push('}');
+            }
+
+            Value::Array(a) => {
+                out.push('[');
+
+                for (n, v) in a.iter().enumerate() {
+                    if n > 0 {
+                        out.push(',');
+                    }
+
+                    write(v, None, out, spans);
+                }
+
+                out.push(']');
+            }
+
+            v => out.push_str(&v.to_string()),
+        }
+
+        if let Some(keys) = keys {
+            spans.push((keys.to_vec(), start..out.len()));
+        }
+    }
+
+    let mut out: String = String::new();
+    let mut spans: Vec<(Vec<String>, Range<usize>)> = vec![];
+
+    write(value, Some(&[]), &mut out, &mut spans);
+
+    (out, spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use serde_json::json;
+
+    use super::{merge, position, resolve, take_patterns, wildcard, write_out};
+
+    #[test]
+    fn merging() {
+        let mut base = json!({
+            "keybinds": { "closeWindow": "Alt+F4", "centerWindow": "Logo+c" },
+            "windowRules": [{ "match": { "appId": "a" } }],
+        });
+
+        merge(
+            &mut base,
+            json!({
+                "keybinds": { "closeWindow": "Logo+w" },
+                "windowRules": [],
+            }),
+        );
+
+        assert_eq!(
+            base,
+            json!({
+                "keybinds": { "closeWindow": "Logo+w", "centerWindow": "Logo+c" },
+                "windowRules": [],
+            })
+        );
+    }
+
+    #[test]
+    fn writing_out() {
+        let value = json!({ "focus": { "mode": "x" }, "windowRules": [{ "opacity": 2 }] });
+        let (out, spans) = write_out(&value);
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&out).unwrap(),
+            value
+        );
+
+        let span = |keys: &[&str]| {
+            spans
+                .iter()
+                .filter(|(k, _)| k == keys)
+                .min_by_key(|(_, r)| r.len())
+                .map(|(_, r)| &out[r.clone()])
+        };
+
+        assert_eq!(span(&["focus", "mode"]), Some("\"x\""));
+        assert_eq!(span(&["focus"]), Some(r#"{"mode":"x"}"#));
+        assert_eq!(span(&["windowRules"]), Some(r#"[{"opacity":2}]"#));
+        assert_eq!(span(&[]).map(str::len), Some(out.len()));
+    }
+
+    #[test]
+    fn patterns() {
+        let mut v = json!({ "$include": ["a.jsonc", "b/*.jsonc"], "focus": {} });
+
+        assert_eq!(
+            take_patterns(&mut v),
+            Ok(vec!["a.jsonc".into(), "b/*.jsonc".into()])
+        );
+        assert_eq!(v, json!({ "focus": {} }));
+
+        assert!(take_patterns(&mut json!({ "$include": 4 })).is_err());
+        assert_eq!(position("{\n  \"$include\": \"a\"\n}"), (2, 3));
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard("*.jsonc", "laptop.jsonc"));
+        assert!(wildcard("desk?.jsonc", "desk1.jsonc"));
+        assert!(!wildcard("*.jsonc", "laptop.json"));
+        assert!(!wildcard("desk?.jsonc", "desk.jsonc"));
+    }
+
+    #[test]
+    fn resolving() {
+        let folder = env::temp_dir().join(format!("navda-include-{}", std::process::id()));
+        fs::create_dir_all(folder.join("machines")).unwrap();
+
+        for file in [
+            "base.jsonc",
+            "machines/b.jsonc",
+            "machines/a.jsonc",
+            "machines/.c.jsonc",
+        ] {
+            fs::write(folder.join(file), "{}").unwrap();
+        }
+
+        let found = resolve("machines/*.jsonc", &folder, &folder).unwrap();
+        let names: Vec<_> = found.iter().map(|f| f.file_name().unwrap()).collect();
+        assert_eq!(names, ["a.jsonc", "b.jsonc"]);
+
+        assert!(resolve("missing.jsonc", &folder, &folder).is_err());
+        assert_eq!(resolve("missing/*.jsonc", &folder, &folder), Ok(vec![]));
+        assert!(resolve("../*", &folder.join("machines"), &folder.join("machines")).is_err());
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -1,7 +1,10 @@
 pub mod config;
 pub mod errors;
+pub mod include;
+pub mod schema;
 pub mod section;
 pub mod sections;
+pub mod syntax;
 pub mod templating;
 
 pub use config::Config;
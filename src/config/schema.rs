@@ -0,0 +1,347 @@
+//!
+//! Generates the JSON Schema (Draft 2020-12) for `Compositor.jsonc` --
+//! the one its `$schema` points at -- from the `config_section!`
+//! declarations, so editors can autocomplete and validate it offline.
+//!
+//! Run `navda --schema [PATH]` to write it out.
+//!
+
+use serde_json::{json, Map, Value};
+
+use super::{
+    include,
+    sections::{
+        animations::Animations, background::Background, clipboard::Clipboard,
+        diagnostics::Diagnostics, effects::Effects, focus::Focus, keybinds::Keybinds,
+        scaling::Scaling, snapping::Snapping,
+    },
+    templating::AvValue,
+    ConfigurationSection,
+};
+
+use crate::core::keyboard::AvKeys;
+
+pub const SCHEMA_ID: &str = "https://schema.avdanos.org/Compositor.json";
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+///
+/// Keyboard shortcuts -- keys joined by `+`, where keys
+/// can be macro parameters (`{d}`, `{dir}`, ...).
+///
+const AVKEYS_PATTERN: &str = r"^[^+\s]+(\+[^+\s]+)*$";
+
+const COLOR_PATTERN: &str = r"^#([0-9a-fA-F]{6}|[0-9a-fA-F]{8})$";
+
+const DURATION_PATTERN: &str = r"^\d+(\.\d+)?(ms|s|m)$";
+
+///
+/// Everything the schema says about a single macro,
+/// as declared in `config_section!`.
+///
+#[derive(Debug)]
+pub struct MacroSchema {
+    pub name: &'static str,
+    pub parameters: Vec<&'static str>,
+    pub description: &'static str,
+    pub default: AvValue,
+}
+
+impl MacroSchema {
+    ///
+    /// The macro as written in the config, like `focus(dir)`.
+    ///
+    pub fn key(&self) -> String {
+        match self.parameters.len() {
+            0 => self.name.to_string(),
+            _ => format!("{}({})", self.name, self.parameters.join(",")),
+        }
+    }
+
+    pub fn schema(&self) -> Value {
+        let mut schema: Value = value_schema(&self.default);
+
+        schema["description"] = json!(self.description);
+
+        schema
+    }
+}
+
+///
+/// The schema for values like `default` -- with it as the default.
+///
+fn value_schema(default: &AvValue) -> Value {
+    let keys = json!({ "type": "string", "pattern": AVKEYS_PATTERN });
+
+    let (mut schema, default) = match default {
+        AvValue::String(s) => (json!({ "type": "string" }), json!(s)),
+        AvValue::i64(i) => (json!({ "type": "integer" }), json!(i)),
+        AvValue::f64(f) => (json!({ "type": "number" }), json!(f)),
+        AvValue::bool(b) => (json!({ "type": "boolean" }), json!(b)),
+        AvValue::AvKeys(k) => (keys, json!(k.to_string())),
+        AvValue::AvKeysList(l) => (
+            json!({ "oneOf": [keys, { "type": "array", "items": keys }] }),
+            json!(l.0.iter().map(AvKeys::to_string).collect::<Vec<_>>()),
+        ),
+        AvValue::AvEnum(e) => (json!({ "enum": e.variants() }), json!(e.as_str())),
+        AvValue::AvColor(c) => (
+            json!({ "type": "string", "pattern": COLOR_PATTERN }),
+            json!(c.to_string()),
+        ),
+        AvValue::AvDuration(d) => (
+            json!({
+                "oneOf": [
+                    { "type": "string", "pattern": DURATION_PATTERN },
+                    { "type": "integer", "minimum": 0, "description": "Milliseconds." },
+                ]
+            }),
+            json!(d.to_string()),
+        ),
+        AvValue::AvObject(o) => {
+            let properties: Map<String, Value> =
+                o.0.iter()
+                    .map(|(k, v)| (k.clone(), value_schema(v)))
+                    .collect();
+            let default: Map<String, Value> = properties
+                .iter()
+                .map(|(k, v)| (k.clone(), v["default"].clone()))
+                .collect();
+
+            (
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "additionalProperties": false,
+                }),
+                json!(default),
+            )
+        }
+    };
+
+    schema["default"] = default;
+
+    schema
+}
+
+///
+/// Implemented by `config_section!` for every section.
+///
+pub trait SectionSchema: ConfigurationSection {
+    fn macros() -> Vec<MacroSchema>;
+
+    ///
+    /// The section's key in the config's root object.
+    ///
+    fn key() -> &'static str {
+        Self::PATH.trim_start_matches("$.")
+    }
+
+    fn schema() -> Value {
+        let properties: Map<String, Value> = Self::macros()
+            .iter()
+            .map(|m| (m.key(), m.schema()))
+            .collect();
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            // Unknown macros are reported by `from_map` too.
+            "additionalProperties": false,
+        })
+    }
+}
+
+///
+/// `windowRules` isn't made of macros, so
+/// it's described by hand -- see [`WindowRule`].
+///
+/// [`WindowRule`]: super::sections::window_rules::WindowRule
+///
+fn window_rules_schema() -> Value {
+    let pattern = json!({ "type": "string", "format": "regex" });
+    let pair = json!({
+        "type": "array",
+        "prefixItems": [{ "type": "integer" }, { "type": "integer" }],
+        "items": false,
+        "minItems": 2,
+    });
+    let flag = json!({ "type": "boolean" });
+
+    json!({
+        "description": "Matched against every new window, in order -- later rules win.",
+        "type": "array",
+        "items": {
+            "type": "object",
+            "required": ["match"],
+            "additionalProperties": false,
+            "properties": {
+                "match": {
+                    "description": "Regular expressions that all have to match the window.",
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "appId": pattern,
+                        "title": pattern,
+                        "class": pattern,
+                        "instance": pattern,
+                        "role": pattern,
+                    },
+                },
+                "output": {
+                    "description": "Name of the output to open on (e.g. `HDMI-A-1`).",
+                    "type": "string",
+                },
+                "workspace": { "type": "integer", "minimum": 0 },
+                "size": pair,
+                "position": pair,
+                "floating": flag,
+                "fullscreen": flag,
+                "maximized": flag,
+                "opacity": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                "decorations": { "enum": ["client", "server"] },
+            },
+        },
+        "default": [],
+    })
+}
+
+///
+/// `keybinds.multitasking` isn't made of macros either -- its
+/// shortcuts are for workspaces, which aren't there to act on yet.
+///
+fn multitasking_schema() -> Value {
+    json!({
+        "description": "Workspace shortcuts -- not acted upon until there are workspaces.",
+        "type": "object",
+        "additionalProperties": { "type": "string", "pattern": AVKEYS_PATTERN },
+    })
+}
+
+///
+/// The whole schema.
+///
+pub fn generate() -> Value {
+    let mut keybinds: Value = Keybinds::schema();
+    keybinds["properties"]["multitasking"] = multitasking_schema();
+
+    let sections = [
+        (Keybinds::key(), keybinds),
+        (Focus::key(), Focus::schema()),
+        (Snapping::key(), Snapping::schema()),
+        (Animations::key(), Animations::schema()),
+        (Effects::key(), Effects::schema()),
+        (Background::key(), Background::schema()),
+        (Scaling::key(), Scaling::schema()),
+        (Clipboard::key(), Clipboard::schema()),
+        (Diagnostics::key(), Diagnostics::schema()),
+    ];
+
+    let mut properties: Map<String, Value> = sections
+        .into_iter()
+        .map(|(key, schema)| (key.to_string(), schema))
+        .collect();
+
+    properties.insert("$schema".to_string(), json!({ "type": "string" }));
+    properties.insert("windowRules".to_string(), window_rules_schema());
+    properties.insert(
+        include::DIRECTIVE.to_string(),
+        json!({
+            "description": "Files to merge in under this one (relative paths, `*` and `?` allowed) \
+                -- later ones override earlier ones, and this file overrides them all.",
+            "oneOf": [
+                { "type": "string" },
+                { "type": "array", "items": { "type": "string" } },
+            ],
+        }),
+    );
+
+    json!({
+        "$schema": DRAFT,
+        "$id": SCHEMA_ID,
+        "title": "AvdanOS Compositor Configuration",
+        "type": "object",
+        // No `required` -- with includes, `keybinds` can be in another file.
+        "properties": properties,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{generate, MacroSchema, SectionSchema};
+
+    use crate::config::{config::StripComments, sections::keybinds::Keybinds, templating::AvValue};
+
+    #[test]
+    fn default_config_keys_are_in_schema() {
+        let schema = generate();
+        let default: Value = serde_json::from_reader(StripComments::new(
+            include_str!("../../DefaultConfig.jsonc").as_bytes(),
+        ))
+        .unwrap();
+
+        for (key, value) in default.as_object().unwrap() {
+            let section = &schema["properties"][key];
+            assert!(section.is_object(), "`{}` isn't in the schema", key);
+
+            // Only sections made of macros list every key.
+            if !section["properties"].is_object() {
+                continue;
+            }
+
+            for inner in value.as_object().unwrap().keys() {
+                assert!(
+                    section["properties"][inner].is_object(),
+                    "`{}.{}` isn't in the schema",
+                    key,
+                    inner
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn macro_keys() {
+        let m = MacroSchema {
+            name: "window",
+            parameters: vec!["d"],
+            description: "",
+            default: AvValue::bool(true),
+        };
+
+        assert_eq!(m.key(), "window(d)");
+        assert_eq!(m.schema()["type"], "boolean");
+        assert_eq!(m.schema()["default"], true);
+    }
+
+    #[test]
+    fn sections() {
+        let schema = generate();
+
+        assert_eq!(Keybinds::key(), "keybinds");
+        assert_eq!(
+            schema["properties"]["keybinds"]["properties"]["closeWindow"]["default"],
+            json!(["Alt+F4"])
+        );
+        assert_eq!(
+            schema["properties"]["effects"]["properties"]["cornerRadius"]["type"],
+            "integer"
+        );
+    }
+
+    #[test]
+    fn value_types() {
+        let schema = generate();
+        let focus = &schema["properties"]["focus"]["properties"];
+        let effects = &schema["properties"]["effects"]["properties"];
+
+        assert_eq!(
+            focus["mode"]["enum"],
+            json!(["click", "followsMouse", "sloppy"])
+        );
+        assert_eq!(focus["mode"]["default"], "click");
+        assert_eq!(focus["activationTimeout"]["default"], "10s");
+        assert_eq!(effects["shadowColor"]["default"], "#00000066");
+    }
+}
@@ -4,14 +4,14 @@ use compositor_macros::{description, location, AvError};
 
 use crate::{
     config::{
-        config::{self, Config},
+        config::Config,
         templating::{avvalue::AvValue, r#macro::SignatureMismatchError, AvMacro},
     },
-    core::error::compare_errors,
+    core::error::{closest_match, compare_errors},
     core::error::{AvError, Traceable, TraceableError},
 };
 
-use json_tree::{JSONPath, Location};
+use json_tree::JSONPath;
 
 use std::collections::HashMap;
 pub trait ConfigurationSection: Sized {
@@ -32,9 +32,7 @@ pub trait ConfigurationSection: Sized {
     /// Returns this section's traceable.
     ///
     fn traceable(key: Option<bool>) -> Traceable {
-        let loc: &Location = Config::index().get(&Self::path()).unwrap();
-
-        Traceable::combine(&config::PATH.to_str().unwrap().to_string(), loc, key)
+        Config::locate(&Self::path(), key).unwrap()
     }
 
     fn from_map(
@@ -46,11 +44,10 @@ pub trait ConfigurationSection: Sized {
         let res = raw.iter().map(|(k, v)| {
             // Parse as a macro.
             let p: JSONPath = path.push(k.clone());
-            let loc: &Location = Config::index().get(&p).unwrap();
-            let k_pos: Traceable = Traceable::combine(&Config::path(), loc, Some(true));
-            let v_pos: Traceable = Traceable::combine(&Config::path(), loc, Some(false));
+            let k_pos: Traceable = Config::locate(&p, Some(true)).unwrap();
+            let v_pos: Traceable = Config::locate(&p, Some(false)).unwrap();
 
-            (AvMacro::parse(k_pos.clone(), k.clone()), v, k_pos, v_pos)
+            (AvMacro::parse(k_pos.clone(), k.clone()), v, k_pos, v_pos, p)
         });
 
         // Syntactically invalid macros.
@@ -58,8 +55,8 @@ pub trait ConfigurationSection: Sized {
         let mut errors: Vec<(Box<dyn TraceableError>, Traceable)> = vec![];
 
         res.clone()
-            .filter(|(k, _, _, _)| k.is_err())
-            .for_each(|(k, _, p, _)| {
+            .filter(|(k, ..)| k.is_err())
+            .for_each(|(k, _, p, ..)| {
                 let n: Vec<Box<dyn TraceableError>> = k.unwrap_err();
 
                 for err in n {
@@ -69,18 +66,21 @@ pub trait ConfigurationSection: Sized {
 
         // Syntactically Valid macros
         let defined = res
-            .filter(|(k, _, _, _)| k.is_ok())
-            .map(|(k, v, p1, p2)| (k.unwrap(), v, p1, p2));
+            .filter(|(k, ..)| k.is_ok())
+            .map(|(k, v, p1, p2, at)| (k.unwrap(), v, p1, p2, at));
 
         let mut output: HashMap<AvMacro, AvValue> = HashMap::new();
 
         let mut found_macros: Vec<usize> = vec![];
+
+        // For suggestions, when a macro isn't found.
+        let names: Vec<String> = declared.keys().map(AvMacro::identifier).collect();
         // Look up the valid macros against our declared HashMap.
 
         for (declared_m, default_v) in declared {
             let defined_m: Option<usize> = defined
                 .clone()
-                .position(|(m, _, _, _)| m.identifier() == declared_m.identifier());
+                .position(|(m, ..)| m.identifier() == declared_m.identifier());
 
             let (avmacro, avvalue) = match defined_m {
                 None => {
@@ -104,7 +104,7 @@ pub trait ConfigurationSection: Sized {
 
                 Some(i) => {
                     found_macros.push(i);
-                    let (m, v, p, p_v) = defined.clone().nth(i).unwrap();
+                    let (m, v, p, p_v, at) = defined.clone().nth(i).unwrap();
 
                     // Check if the macro's signature matches our defined one.
                     let sig_check = declared_m.has_signature(&m);
@@ -123,7 +123,7 @@ pub trait ConfigurationSection: Sized {
                     } else {
                         // VALUE CHECKS
                         // Now check the value's type against the default's
-                        match default_v.parse_same_type(p_v.clone(), v.clone()) {
+                        match default_v.parse_same_type(p_v.clone(), &at, v.clone()) {
                             Err(e) => {
                                 errors.push((e, p));
                                 (declared_m, default_v)
@@ -156,26 +156,34 @@ pub trait ConfigurationSection: Sized {
             .map(|(_, e)| e)
             .collect();
 
-        for (m, _, p1, _) in not_found {
+        for (m, _, p1, ..) in not_found {
             errors.push((
-                Box::new(MacroNotFound(p1.clone(), m.identifier(), Self::path()))
-                    as Box<dyn TraceableError>,
+                Box::new(MacroNotFound(
+                    p1.clone(),
+                    m.identifier(),
+                    Self::path(),
+                    closest_match(&m.identifier(), names.iter().map(String::as_str))
+                        .map(str::to_string),
+                )) as Box<dyn TraceableError>,
                 p1,
             ))
         }
 
         errors.sort_by(|(a, _), (b, _)| compare_errors(a, b).unwrap());
 
-        for (err, _) in errors {
-            println!("{}", err)
-        }
+        Config::report(errors.into_iter().map(|(err, _)| err).collect());
 
         output
     }
 }
 
-#[AvError(TraceableError, CONFIG_MACRO_NOT_FOUND, "Config: Macro Not Found")]
-pub struct MacroNotFound(pub Traceable, pub String, pub JSONPath);
+#[AvError(
+    TraceableError,
+    CONFIG_MACRO_NOT_FOUND,
+    "Config: Macro Not Found",
+    Warning
+)]
+pub struct MacroNotFound(pub Traceable, pub String, pub JSONPath, pub Option<String>);
 
 impl TraceableError for MacroNotFound {
     location!(&self.0);
@@ -184,9 +192,15 @@ impl TraceableError for MacroNotFound {
         self.1.blue(),
         self.2
     ));
+
+    fn help(&self) -> Option<String> {
+        let closest: &String = self.3.as_ref()?;
+
+        Some(format!("did you mean `{}`?", closest.blue()))
+    }
 }
 
-#[AvError(TraceableError, CONFIG_MACRO_MISSING, "Config: Macro Missing")]
+#[AvError(TraceableError, CONFIG_MACRO_MISSING, "Config: Macro Missing", Warning)]
 pub struct MacroMissing(pub Traceable, pub String, pub JSONPath);
 
 impl TraceableError for MacroMissing {
@@ -0,0 +1,29 @@
+use compositor_macros::config_section;
+
+use crate::config::ConfigurationSection;
+
+config_section!(
+    Animations {
+        "Animate windows at all -- `false` makes every change instant."
+        enabled                 => true,
+
+        "Easing curve: `linear`, `easeOutQuad`, `easeOutCubic` or `easeInOutCubic`."
+        curve                   => "easeOutCubic" | "linear" | "easeOutQuad" | "easeInOutCubic",
+
+        "How long windows take to appear."
+        openDuration            => duration("200ms"),
+
+        "How long windows take to disappear."
+        closeDuration           => duration("150ms"),
+
+        "How long windows take to slide to a new position."
+        moveDuration            => duration("200ms"),
+
+        "How long windows take to (un)maximize."
+        maximizeDuration        => duration("250ms"),
+    }
+);
+
+impl ConfigurationSection for Animations {
+    const PATH: &'static str = "$.animations";
+}
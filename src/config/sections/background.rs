@@ -0,0 +1,20 @@
+use compositor_macros::config_section;
+
+use crate::config::ConfigurationSection;
+
+config_section!(
+    Background {
+        "Color behind (and around) the wallpaper, as `#rrggbb`."
+        color                   => color("#cccce6"),
+
+        "Wallpaper image (PNG or JPEG) -- empty for just the color."
+        image                   => "",
+
+        "How the image covers each output: `fill`, `fit`, `center` or `tile`."
+        mode                    => "fill" | "fit" | "center" | "tile",
+    }
+);
+
+impl ConfigurationSection for Background {
+    const PATH: &'static str = "$.background";
+}
@@ -0,0 +1,23 @@
+use compositor_macros::config_section;
+
+use crate::config::ConfigurationSection;
+
+config_section!(
+    Clipboard {
+        "Keep what was copied after the program it was copied from closes."
+        persist                 => true,
+
+        "How many clipboard entries to remember -- the `cycleClipboard` keybind goes back through them."
+        historySize             => 10,
+
+        "The most (in KiB) a clipboard entry can take up to be kept -- bigger ones are left with their program."
+        maxEntrySize            => 16384,
+
+        "Which programs can read and set the clipboard without a focused window: `tools` (wl-clipboard and known clipboard managers), `all` or `none`."
+        dataControl             => "tools" | "all" | "none",
+    }
+);
+
+impl ConfigurationSection for Clipboard {
+    const PATH: &'static str = "$.clipboard";
+}
@@ -0,0 +1,31 @@
+use compositor_macros::config_section;
+
+use crate::{config::ConfigurationSection, core::error::Severity};
+
+config_section!(
+    Diagnostics {
+        "Show warnings about the config (like unknown or missing macros)."
+        showWarnings            => true,
+
+        "Show hints about the config."
+        showHints               => true,
+    }
+);
+
+impl ConfigurationSection for Diagnostics {
+    const PATH: &'static str = "$.diagnostics";
+}
+
+impl Diagnostics {
+    ///
+    /// Whether problems this bad get shown
+    /// -- errors always are.
+    ///
+    pub fn shows(&self, severity: Severity) -> bool {
+        match severity {
+            Severity::Error => true,
+            Severity::Warning => self.showWarnings,
+            Severity::Hint => self.showHints,
+        }
+    }
+}
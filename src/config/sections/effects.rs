@@ -0,0 +1,26 @@
+use compositor_macros::config_section;
+
+use crate::config::ConfigurationSection;
+
+config_section!(
+    Effects {
+        "How round (in pixels) window corners are -- `0` keeps them square."
+        cornerRadius            => 8,
+
+        "Draw a drop shadow under windows."
+        shadows                 => true,
+
+        "How far (in pixels) shadows spread out from under windows."
+        shadowSize              => 24,
+
+        "Shadow color, as `#rrggbbaa`."
+        shadowColor             => color("#00000066"),
+
+        "Draw effects with GPU shaders -- `false` falls back to (slower) textures drawn on the CPU."
+        useShaders              => true,
+    }
+);
+
+impl ConfigurationSection for Effects {
+    const PATH: &'static str = "$.effects";
+}
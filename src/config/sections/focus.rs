@@ -0,0 +1,26 @@
+use compositor_macros::config_section;
+
+use crate::config::ConfigurationSection;
+
+config_section!(
+    Focus {
+        "How windows receive keyboard focus: `click`, `followsMouse` or `sloppy`."
+        mode                    => "click" | "followsMouse" | "sloppy",
+
+        "Raise a window to the top when it is clicked."
+        raiseOnClick            => true,
+
+        "Raise a window to the top when the pointer focuses it (`followsMouse` and `sloppy` only)."
+        raiseOnFocus            => false,
+
+        "Who may take focus through xdg-activation: `off`, `smart` or `strict`."
+        stealingPrevention      => "smart" | "off" | "strict",
+
+        "How long an activation token stays valid."
+        activationTimeout       => duration("10s"),
+    }
+);
+
+impl ConfigurationSection for Focus {
+    const PATH: &'static str = "$.focus";
+}
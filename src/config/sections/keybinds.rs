@@ -4,11 +4,62 @@ use crate::config::ConfigurationSection;
 
 config_section!(
     Keybinds {
-        "Move focused window to `d`th on the taskbar."
-        window(d)           => (Meta+{d}),
+        "Focus the nearest window in direction `dir`."
+        focus(dir)          => [(Logo+{dir})],
 
-        "How many horns does a unicorn have?"
-        hornsInUnicorn      => 1,
+        "Move the pointer to the `o`th output."
+        focusOutput(o)      => [(Logo+{o})],
+
+        "Nudge the focused window left."
+        moveWindowLeft      => [(Logo+Alt+Left)],
+
+        "Nudge the focused window right."
+        moveWindowRight     => [(Logo+Alt+Right)],
+
+        "Nudge the focused window up."
+        moveWindowUp        => [(Logo+Alt+Up)],
+
+        "Nudge the focused window down."
+        moveWindowDown      => [(Logo+Alt+Down)],
+
+        "Make the focused window wider."
+        growWindowWidth     => [(Logo+Alt+Shift+Right)],
+
+        "Make the focused window narrower."
+        shrinkWindowWidth   => [(Logo+Alt+Shift+Left)],
+
+        "Make the focused window taller."
+        growWindowHeight    => [(Logo+Alt+Shift+Down)],
+
+        "Make the focused window shorter."
+        shrinkWindowHeight  => [(Logo+Alt+Shift+Up)],
+
+        "Center the focused window on its output."
+        centerWindow        => [(Logo+c)],
+
+        "Maximize the focused window, or restore it if it already is."
+        toggleMaximize      => [(Logo+m)],
+
+        "Make the focused window fullscreen, or leave fullscreen."
+        toggleFullscreen    => [(Logo+f)],
+
+        "Minimize the focused window."
+        minimizeWindow      => [(Logo+h)],
+
+        "Bring back the most recently minimized window."
+        restoreMinimized    => [(Logo+Shift+h)],
+
+        "Ask the focused window to close -- or kill it, if it has stopped responding."
+        closeWindow         => [(Alt+F4)],
+
+        "Put the previous clipboard entry back on the clipboard."
+        cycleClipboard      => [(Logo+v)],
+
+        "Open the window switcher, or step to the next window -- let go of the modifiers to switch."
+        switchWindows       => [(Alt+Tab)],
+
+        "Open the window switcher, or step back to the previous window."
+        switchWindowsBackwards => [(Alt+Shift+Tab)],
     }
 );
 
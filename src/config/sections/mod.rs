@@ -1 +1,10 @@
+pub mod animations;
+pub mod background;
+pub mod clipboard;
+pub mod diagnostics;
+pub mod effects;
+pub mod focus;
 pub mod keybinds;
+pub mod scaling;
+pub mod snapping;
+pub mod window_rules;
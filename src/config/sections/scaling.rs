@@ -0,0 +1,17 @@
+use compositor_macros::config_section;
+
+use crate::config::ConfigurationSection;
+
+config_section!(
+    Scaling {
+        "Pick each output's scale from its size and resolution when it's connected."
+        auto                    => true,
+
+        "The DPI things are drawn at scale 1 for -- outputs denser than this are scaled up."
+        targetDpi               => 96,
+    }
+);
+
+impl ConfigurationSection for Scaling {
+    const PATH: &'static str = "$.scaling";
+}
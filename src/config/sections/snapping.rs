@@ -0,0 +1,17 @@
+use compositor_macros::config_section;
+
+use crate::config::ConfigurationSection;
+
+config_section!(
+    Snapping {
+        "How close (in pixels) a dragged window has to get to an edge to snap to it -- `0` turns snapping off."
+        threshold               => 16,
+
+        "Tile windows dragged against an output's edges: halves on the sides, quarters in the corners, maximized at the top."
+        edgeTiling              => true,
+    }
+);
+
+impl ConfigurationSection for Snapping {
+    const PATH: &'static str = "$.snapping";
+}
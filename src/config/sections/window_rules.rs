@@ -0,0 +1,87 @@
+//!
+//! Per-application window rules.
+//!
+//! Unlike the other sections, this one is a list of objects
+//! rather than a set of macros -- so it's deserialized directly
+//! instead of through `config_section!`.
+//!
+
+use serde::Deserialize;
+
+///
+/// Which windows a rule applies to.
+///
+/// Every pattern is a regular expression, and all the given
+/// ones have to match. Patterns are unanchored, so wrap them
+/// in `^...$` to match a whole value.
+///
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WindowMatch {
+    ///
+    /// Wayland app_id (also matched against
+    /// the class of X11 windows).
+    ///
+    pub app_id: Option<String>,
+
+    pub title: Option<String>,
+
+    ///
+    /// X11 `WM_CLASS` class -- never matches Wayland windows.
+    ///
+    pub class: Option<String>,
+
+    ///
+    /// X11 `WM_CLASS` instance -- never matches Wayland windows.
+    ///
+    pub instance: Option<String>,
+
+    ///
+    /// X11 `WM_WINDOW_ROLE` -- never matches Wayland windows.
+    ///
+    pub role: Option<String>,
+}
+
+///
+/// A single entry of `windowRules`.
+///
+/// Anything left out is left up to the compositor (or client).
+/// When several rules match a window, later ones win.
+///
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WindowRule {
+    #[serde(rename = "match")]
+    pub matches: WindowMatch,
+
+    ///
+    /// Name of the output to open on (e.g. `HDMI-A-1`).
+    ///
+    pub output: Option<String>,
+
+    pub workspace: Option<u32>,
+
+    ///
+    /// Initial `[width, height]`.
+    ///
+    pub size: Option<(i32, i32)>,
+
+    ///
+    /// Initial `[x, y]`, relative to the output.
+    ///
+    pub position: Option<(i32, i32)>,
+
+    pub floating: Option<bool>,
+    pub fullscreen: Option<bool>,
+    pub maximized: Option<bool>,
+
+    ///
+    /// From `0.0` (invisible) to `1.0` (opaque).
+    ///
+    pub opacity: Option<f32>,
+
+    ///
+    /// `client` or `server`.
+    ///
+    pub decorations: Option<String>,
+}
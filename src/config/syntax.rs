@@ -0,0 +1,492 @@
+//!
+//! A forgiving pass over the config's JSON(C), for when it doesn't parse
+//! -- `json_tree` gives up at the first mistake, but this skips past
+//! each one to find the rest.
+//!
+//! It only reports problems; it never builds anything.
+//!
+
+use std::{iter::Peekable, str::Chars};
+
+use super::{errors::SyntaxError, Config};
+
+///
+/// 1-based line and column.
+///
+type Position = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Open(char),
+    Close(char),
+    Colon,
+    Comma,
+    String,
+    Scalar,
+
+    ///
+    /// Something that isn't JSON (or is broken), with a reason.
+    ///
+    Invalid(String),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Kind,
+    text: String,
+    at: Position,
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Self {
+            chars: src.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn here(&self) -> Position {
+        (self.line, self.column)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c: char = self.chars.next()?;
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(c)
+    }
+
+    ///
+    /// Skips whitespace and comments -- returning an error
+    /// for a block comment that never ends.
+    ///
+    fn skip_trivia(&mut self) -> Option<Token> {
+        loop {
+            match self.chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') => {
+                    let at: Position = self.here();
+                    let mut ahead = self.chars.clone();
+                    ahead.next();
+
+                    match ahead.next() {
+                        Some('/') => {
+                            while !matches!(self.chars.peek(), Some('\n') | None) {
+                                self.bump();
+                            }
+                        }
+                        Some('*') => {
+                            self.bump();
+                            self.bump();
+
+                            let mut last: char = ' ';
+                            loop {
+                                match self.bump() {
+                                    Some('/') if last == '*' => break,
+                                    Some(c) => last = c,
+                                    None => {
+                                        return Some(Token {
+                                            kind: Kind::Invalid(
+                                                "This comment is never closed.".into(),
+                                            ),
+                                            text: "/*".into(),
+                                            at,
+                                        })
+                                    }
+                                }
+                            }
+                        }
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn string(&mut self, at: Position) -> Token {
+        let mut text: String = String::from('"');
+        self.bump();
+
+        loop {
+            match self.chars.peek() {
+                None | Some('\n') => {
+                    return Token {
+                        kind: Kind::Invalid("This string is never closed.".into()),
+                        text,
+                        at,
+                    }
+                }
+                Some('"') => {
+                    text.push('"');
+                    self.bump();
+
+                    return Token {
+                        kind: Kind::String,
+                        text,
+                        at,
+                    };
+                }
+                Some('\\') => {
+                    text.push('\\');
+                    self.bump();
+                    text.extend(self.bump());
+                }
+                Some(&c) => {
+                    text.push(c);
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn scalar(&mut self, at: Position) -> Token {
+        let mut text: String = String::new();
+
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || "{}[]:,\"/".contains(c) {
+                break;
+            }
+
+            text.push(c);
+            self.bump();
+        }
+
+        let valid: bool = matches!(text.as_str(), "true" | "false" | "null")
+            || text.parse::<f64>().map(|n| n.is_finite()).unwrap_or(false);
+
+        let kind: Kind = match valid {
+            true => Kind::Scalar,
+            false if text.is_empty() => {
+                // A lone `/`, that isn't a comment.
+                text.extend(self.bump());
+                Kind::Invalid(format!("Unexpected `{}`.", text))
+            }
+            false => Kind::Invalid(format!(
+                "`{}` isn't a value -- strings need quotes around them.",
+                text
+            )),
+        };
+
+        Token { kind, text, at }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        if let Some(err) = self.skip_trivia() {
+            return Some(err);
+        }
+
+        let at: Position = self.here();
+        let c: char = *self.chars.peek()?;
+
+        let kind: Kind = match c {
+            '{' | '[' => Kind::Open(c),
+            '}' | ']' => Kind::Close(c),
+            ':' => Kind::Colon,
+            ',' => Kind::Comma,
+            '"' => return Some(self.string(at)),
+            _ => return Some(self.scalar(at)),
+        };
+
+        self.bump();
+
+        Some(Token {
+            kind,
+            text: c.to_string(),
+            at,
+        })
+    }
+}
+
+struct Checker {
+    tokens: Vec<Token>,
+    next: usize,
+    end: Position,
+    errors: Vec<(String, Position)>,
+}
+
+impl Checker {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let token: Token = self.tokens.get(self.next)?.clone();
+        self.next += 1;
+
+        Some(token)
+    }
+
+    fn error(&mut self, message: String, at: Position) {
+        self.errors.push((message, at));
+    }
+
+    fn unexpected(&mut self, token: &Token, expected: &str) {
+        match &token.kind {
+            Kind::Invalid(reason) => self.error(reason.clone(), token.at),
+            _ => self.error(
+                format!("Expected {}, found `{}`.", expected, token.text),
+                token.at,
+            ),
+        }
+    }
+
+    fn unexpected_end(&mut self, expected: &str) {
+        self.error(
+            format!("Expected {}, but the file ended.", expected),
+            self.end,
+        );
+    }
+
+    ///
+    /// Skips to the next `,` or closing bracket at this depth,
+    /// and leaves it for the caller.
+    ///
+    fn recover(&mut self) {
+        let mut depth: usize = 0;
+
+        while let Some(token) = self.peek() {
+            match token.kind {
+                Kind::Open(_) => depth += 1,
+                Kind::Close(_) if depth == 0 => return,
+                Kind::Close(_) => depth -= 1,
+                Kind::Comma if depth == 0 => return,
+                _ => {}
+            }
+
+            self.next += 1;
+        }
+    }
+
+    ///
+    /// Checks a value -- `false` if the file ended.
+    ///
+    fn value(&mut self) -> bool {
+        let token: Token = match self.peek() {
+            Some(t) => t.clone(),
+            None => {
+                self.unexpected_end("a value");
+                return false;
+            }
+        };
+
+        match token.kind {
+            Kind::Open(c) => {
+                self.bump();
+                self.collection(c)
+            }
+            Kind::String | Kind::Scalar => {
+                self.bump();
+                true
+            }
+
+            // Leave these for whatever the value's in.
+            Kind::Close(_) | Kind::Comma => {
+                self.unexpected(&token, "a value");
+                true
+            }
+
+            _ => {
+                self.unexpected(&token, "a value");
+                self.bump();
+                self.recover();
+                true
+            }
+        }
+    }
+
+    ///
+    /// Checks one of an object's keys, and its value.
+    ///
+    fn member(&mut self) -> bool {
+        let token: Token = match self.bump() {
+            Some(t) => t,
+            None => {
+                self.unexpected_end("a key");
+                return false;
+            }
+        };
+
+        if token.kind != Kind::String {
+            self.unexpected(&token, "a key (in quotes)");
+            self.recover();
+            return true;
+        }
+
+        match self.peek().cloned() {
+            Some(Token {
+                kind: Kind::Colon, ..
+            }) => {
+                self.bump();
+            }
+            Some(next) => self.unexpected(&next, "`:` after the key"),
+            None => {
+                self.unexpected_end("`:`");
+                return false;
+            }
+        }
+
+        self.value()
+    }
+
+    ///
+    /// Checks the rest of an object (`{`) or array (`[`)
+    /// -- `false` if the file ended.
+    ///
+    fn collection(&mut self, open: char) -> bool {
+        let (close, item): (char, &str) = match open {
+            '{' => ('}', "a key"),
+            _ => (']', "a value"),
+        };
+
+        // Empty?
+        if let Some(Kind::Close(_)) = self.peek().map(|t| &t.kind) {
+            let token: Token = self.bump().unwrap();
+            self.closed_with(&token, close);
+            return true;
+        }
+
+        loop {
+            let more: bool = match open {
+                '{' => self.member(),
+                _ => self.value(),
+            };
+            if !more {
+                return false;
+            }
+
+            let token: Token = match self.bump() {
+                Some(t) => t,
+                None => {
+                    self.unexpected_end(&format!("`,` or `{}`", close));
+                    return false;
+                }
+            };
+
+            match token.kind {
+                Kind::Comma => {
+                    if let Some(Kind::Close(_)) = self.peek().map(|t| &t.kind) {
+                        self.error("Trailing commas aren't allowed.".into(), token.at);
+
+                        let token: Token = self.bump().unwrap();
+                        self.closed_with(&token, close);
+                        return true;
+                    }
+                }
+
+                Kind::Close(_) => {
+                    self.closed_with(&token, close);
+                    return true;
+                }
+
+                // Most likely a forgotten comma -- carry on as if it were there.
+                Kind::String | Kind::Scalar | Kind::Open(_) => {
+                    self.error(format!("Missing `,` before {}.", item), token.at);
+                    self.next -= 1;
+                }
+
+                _ => {
+                    self.unexpected(&token, &format!("`,` or `{}`", close));
+                    self.recover();
+                }
+            }
+        }
+    }
+
+    fn closed_with(&mut self, token: &Token, close: char) {
+        if token.kind != Kind::Close(close) {
+            self.unexpected(token, &format!("`{}`", close));
+        }
+    }
+}
+
+///
+/// Every syntax error in `src` -- messages, and where they are.
+///
+fn find_errors(src: &str) -> Vec<(String, Position)> {
+    let tokens: Vec<Token> = Lexer::new(src).collect();
+
+    let last_line: &str = src.lines().last().unwrap_or("");
+    let end: Position = (src.lines().count().max(1), last_line.chars().count() + 1);
+
+    let mut checker: Checker = Checker {
+        tokens,
+        next: 0,
+        end,
+        errors: vec![],
+    };
+
+    if checker.value() {
+        if let Some(token) = checker.bump() {
+            checker.unexpected(&token, "the end of the file");
+        }
+    }
+
+    checker.errors
+}
+
+///
+/// Every syntax error in `src` (the config's contents).
+///
+pub fn check(src: &str) -> Vec<SyntaxError> {
+    find_errors(src)
+        .into_iter()
+        .map(|(message, at)| SyntaxError(message, Config::trace(at)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_errors;
+
+    #[test]
+    fn valid() {
+        let src: &str = r#"{
+            // A comment
+            "keybinds": { "closeWindow": "Alt+F4", "n": [1, 2.5, true, null] },
+            /* Another */ "effects": {}
+        }"#;
+
+        assert!(find_errors(src).is_empty());
+    }
+
+    #[test]
+    fn every_error() {
+        let src: &str = r#"{
+  "a": 1
+  "b": tru,
+  "c": [1, 2,],
+  "d" 4
+}"#;
+
+        let lines: Vec<usize> = find_errors(src).iter().map(|(_, (l, _))| *l).collect();
+        assert_eq!(lines, vec![3, 3, 4, 5]);
+    }
+
+    #[test]
+    fn unexpected_end() {
+        let errors = find_errors("{\n  \"a\": [1, 2");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].1, (2, 13));
+    }
+}
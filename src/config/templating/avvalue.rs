@@ -10,13 +10,16 @@ use compositor_macros::{description, location, AvError, AvValue};
 
 use crate::core::{
     error::{AvError, Traceable, TraceableError},
-    keyboard::{avkeys::AvKey, AvKeys},
+    keyboard::{avkeys::AvKeysList, AvKeys},
 };
 
+use json_tree::JSONPath;
+
 use serde_json::Value;
 
 use super::{
-    r#macro::{AvKeysMismatch, Difference},
+    r#macro::{AvKeysMismatch, Difference, RepeatedParameter},
+    types::{AvColor, AvDuration, AvEnum, AvObject},
     AvMacro, MacroParameter,
 };
 
@@ -56,12 +59,17 @@ impl UnexpectedType {
     }
 }
 
-AvValue!([String, i64, f64, bool, AvKeys]);
+AvValue!([String, i64, f64, bool, AvKeys, AvKeysList, AvEnum, AvColor, AvDuration, AvObject]);
 
 impl AvValue {
+    ///
+    /// Reads `val` (at `path`, in the config) as
+    /// the same type as this -- see [`AvObject::parse_same_keys`].
+    ///
     pub fn parse_same_type(
         &self,
         loc: Traceable,
+        path: &JSONPath,
         val: Value,
     ) -> Result<Self, Box<dyn TraceableError>> {
         match &self {
@@ -70,6 +78,11 @@ impl AvValue {
             AvValue::f64(_) => f64::deserialize(loc, val),
             AvValue::bool(_) => bool::deserialize(loc, val),
             AvValue::AvKeys(_) => AvKeys::deserialize(loc, val),
+            AvValue::AvKeysList(_) => AvKeysList::deserialize(loc, val),
+            AvValue::AvEnum(e) => e.parse_variant(loc, val),
+            AvValue::AvColor(_) => AvColor::deserialize(loc, val),
+            AvValue::AvDuration(_) => AvDuration::deserialize(loc, val),
+            AvValue::AvObject(o) => o.parse_same_keys(loc, path, val),
         }
     }
 
@@ -83,16 +96,32 @@ impl AvValue {
             AvValue::i64(_) => Ok(()),
             AvValue::f64(_) => Ok(()),
             AvValue::bool(_) => Ok(()),
+            AvValue::AvEnum(_) => Ok(()),
+            AvValue::AvColor(_) => Ok(()),
+            AvValue::AvDuration(_) => Ok(()),
+            AvValue::AvObject(_) => Ok(()),
+
+            // Every binding has to fit the macro.
+            AvValue::AvKeysList(l) => l.0.iter().try_for_each(|k: &AvKeys| {
+                AvValue::AvKeys(k.clone()).consistent_with_macro(loc.clone(), m)
+            }),
+
             AvValue::AvKeys(k) => {
-                let p: Vec<_> =
-                    k.0.iter()
-                        .filter_map(|k: &AvKey| match k {
-                            AvKey::Parameter(k) => Some(k),
-                            _ => None,
-                        })
-                        .collect();
-
-                m.has_parameters(p.iter().map(|k: &&MacroParameter| (*k).clone()).collect())
+                let p: Vec<MacroParameter> = k.parameters();
+
+                // Each parameter stands for a single key, so
+                // `Ctrl+{d}+{d}` could only ever mean `Ctrl+1+1`.
+                for (i, param) in p.iter().enumerate() {
+                    if p[..i].contains(param) {
+                        return Err(Box::new(RepeatedParameter(
+                            loc,
+                            k.to_string(),
+                            param.clone(),
+                        )));
+                    }
+                }
+
+                m.has_parameters(p)
                     .map_err(|e: (Difference, Vec<MacroParameter>)| {
                         Box::new(AvKeysMismatch(loc, k.to_string(), e)) as Box<dyn TraceableError>
                     })
@@ -55,6 +55,67 @@ pub enum MacroParameter {
     /// Function keys from 1...12
     ///
     FunctionKey,
+
+    ///
+    /// Letter keys from a...z
+    ///
+    LetterKey,
+
+    ///
+    /// Numpad digit keys from 0...9
+    ///
+    NumpadKey,
+
+    ///
+    /// Arrow keys, for left, right, up and down
+    ///
+    Direction,
+
+    ///
+    /// Outputs 1...9, on the digit keys
+    ///
+    OutputIndex,
+}
+
+impl MacroParameter {
+    ///
+    /// Every key this parameter stands for,
+    /// along with what each one means.
+    ///
+    pub fn expansions(&self) -> Vec<(String, MacroArgument)> {
+        match self {
+            Self::DigitKey => (0..=9)
+                .map(|n: u32| (n.to_string(), MacroArgument::Digit(n)))
+                .collect(),
+
+            Self::FunctionKey => (1..=12)
+                .map(|n: u32| (format!("F{}", n), MacroArgument::Function(n)))
+                .collect(),
+
+            Self::LetterKey => ('a'..='z')
+                .map(|c: char| (c.to_string(), MacroArgument::Letter(c)))
+                .collect(),
+
+            Self::NumpadKey => (0..=9)
+                .map(|n: u32| (format!("KP_{}", n), MacroArgument::Digit(n)))
+                .collect(),
+
+            Self::Direction => [
+                Direction::Left,
+                Direction::Right,
+                Direction::Up,
+                Direction::Down,
+            ]
+            .into_iter()
+            .map(|d: Direction| (d.key().to_string(), MacroArgument::Direction(d)))
+            .collect(),
+
+            // Outputs count from 0, but the keys from 1.
+            Self::OutputIndex => (0..9)
+                .map(|i: usize| ((i + 1).to_string(), MacroArgument::Output(i)))
+                .collect(),
+        }
+    }
 }
 
 impl<'a> TryFrom<&'a str> for MacroParameter {
@@ -67,6 +128,10 @@ impl<'a> TryFrom<&'a str> for MacroParameter {
         match value {
             "d" => Ok(Self::DigitKey),
             "F" => Ok(Self::FunctionKey),
+            "a" => Ok(Self::LetterKey),
+            "kp" => Ok(Self::NumpadKey),
+            "dir" => Ok(Self::Direction),
+            "o" => Ok(Self::OutputIndex),
             _ => Err(value.to_string()),
         }
     }
@@ -77,11 +142,69 @@ impl From<MacroParameter> for String {
         match p {
             MacroParameter::DigitKey => "d",
             MacroParameter::FunctionKey => "F",
+            MacroParameter::LetterKey => "a",
+            MacroParameter::NumpadKey => "kp",
+            MacroParameter::Direction => "dir",
+            MacroParameter::OutputIndex => "o",
         }
         .to_string()
     }
 }
 
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    ///
+    /// The arrow key pointing this way.
+    ///
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Left => "Left",
+            Self::Right => "Right",
+            Self::Up => "Up",
+            Self::Down => "Down",
+        }
+    }
+}
+
+///
+/// What a [`MacroParameter`] stands for,
+/// in one expansion of a macro's value.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroArgument {
+    ///
+    /// From `{d}` and `{kp}`.
+    ///
+    Digit(u32),
+
+    ///
+    /// From `{F}`, 1...12.
+    ///
+    Function(u32),
+
+    ///
+    /// From `{a}`.
+    ///
+    Letter(char),
+
+    ///
+    /// From `{dir}`.
+    ///
+    Direction(Direction),
+
+    ///
+    /// From `{o}` -- counting from 0.
+    ///
+    Output(usize),
+}
+
 impl ToString for MacroParameter {
     fn to_string(&self) -> String {
         <Self as Into<String>>::into(self.clone())
@@ -185,32 +308,27 @@ impl AvMacro {
         }
     }
 
+    ///
+    /// Checks the parameters used in this macro's value
+    /// (in any order) are exactly the ones it declares.
+    ///
     pub fn has_parameters(
         &self,
         v: Vec<MacroParameter>,
     ) -> Result<(), (Difference, Vec<MacroParameter>)> {
-        match self.parameters.len() {
-            l if l < v.len() => Err((
-                Difference::FromNew,
-                v.iter()
-                    .filter(|k: &&MacroParameter| !self.parameters.contains(k))
-                    .map(|k: &MacroParameter| k.clone())
-                    .collect(),
-            )),
-
-            l if l == v.len() => Ok(()),
-
-            l if l > v.len() => Err((
-                Difference::FromNew,
-                self.parameters
-                    .iter()
-                    .filter(|k: &&MacroParameter| !v.contains(k))
-                    .map(|k: &MacroParameter| k.clone())
-                    .collect(),
-            )),
-
-            _ => unreachable!(),
+        let used: HashSet<MacroParameter> = HashSet::from_iter(v);
+
+        let excess: Vec<MacroParameter> = used.difference(&self.parameters).cloned().collect();
+        if !excess.is_empty() {
+            return Err((Difference::FromNew, excess));
+        }
+
+        let missing: Vec<MacroParameter> = self.parameters.difference(&used).cloned().collect();
+        if !missing.is_empty() {
+            return Err((Difference::FromOriginal, missing));
         }
+
+        Ok(())
     }
 
     pub fn has_same_id(&self, o: &Self) -> bool {
@@ -423,13 +541,29 @@ impl TraceableError for AvKeysMismatch {
     );
 }
 
+#[AvError(
+    TraceableError,
+    CONFIG_AVKEYS_REPEATED_PARAMETER,
+    "Config: Repeated Macro Parameter"
+)]
+pub struct RepeatedParameter(pub Traceable, pub String, pub MacroParameter);
+
+impl TraceableError for RepeatedParameter {
+    location!(&self.0);
+    description!((
+        "The Key expression {} uses `{}` more than once -- each parameter can only be used once.",
+        self.1,
+        self.2.to_string().blue()
+    ));
+}
+
 #[cfg(test)]
 mod tests {
     use compositor_macros::traceable;
 
     use crate::core::error::TraceableError;
 
-    use super::AvMacro;
+    use super::{AvMacro, MacroArgument, MacroParameter};
 
     #[test]
     fn parsing_test() {
@@ -440,4 +574,26 @@ mod tests {
 
         println!("{m:?}");
     }
+
+    #[test]
+    fn parameters() {
+        let m: AvMacro = AvMacro::parse(traceable!(), "focus(dir, o)".to_string()).unwrap();
+
+        assert!(m
+            .has_parameters(vec![MacroParameter::OutputIndex, MacroParameter::Direction])
+            .is_ok());
+        assert!(m.has_parameters(vec![MacroParameter::Direction]).is_err());
+        assert!(m
+            .has_parameters(vec![MacroParameter::Direction, MacroParameter::DigitKey])
+            .is_err());
+    }
+
+    #[test]
+    fn expansions() {
+        let outputs = MacroParameter::OutputIndex.expansions();
+
+        assert_eq!(outputs[0], ("1".to_string(), MacroArgument::Output(0)));
+        assert_eq!(MacroParameter::LetterKey.expansions().len(), 26);
+        assert_eq!(MacroParameter::NumpadKey.expansions()[3].0, "KP_3");
+    }
 }
@@ -3,4 +3,11 @@ pub use avvalue::AvDeserialize;
 pub use avvalue::AvValue;
 pub mod r#macro;
 pub use r#macro::AvMacro;
+pub use r#macro::Direction;
+pub use r#macro::MacroArgument;
 pub use r#macro::MacroParameter;
+pub mod types;
+pub use types::AvColor;
+pub use types::AvDuration;
+pub use types::AvEnum;
+pub use types::AvObject;
@@ -0,0 +1,414 @@
+//!
+//! Config values that are more than a plain scalar -- each one
+//! checked as it's read, so mistakes are reported with where they are,
+//! instead of being quietly swapped for a default later on.
+//!
+//! * [`AvEnum`] -- one of a fixed set of strings.
+//! * [`AvColor`] -- `#RRGGBB` or `#RRGGBBAA`.
+//! * [`AvDuration`] -- like `250ms`, `1.5s` or `2m`.
+//! * [`AvObject`] -- a nested object, with its own keys.
+//!
+
+use colored::Colorize;
+
+use compositor_macros::{description, location, AvError};
+
+use json_tree::JSONPath;
+
+use serde_json::Value;
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use crate::{
+    config::config::Config,
+    core::error::{closest_match, AvError, Traceable, TraceableError},
+};
+
+use super::{avvalue::UnexpectedType, AvDeserialize, AvValue};
+
+///
+/// One of a fixed set of strings -- declared in
+/// `config_section!` as `"click" | "followsMouse" | ...`,
+/// where the first one is the default.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvEnum {
+    value: String,
+    variants: &'static [&'static str],
+}
+
+impl AvEnum {
+    pub fn new(value: &str, variants: &'static [&'static str]) -> Self {
+        Self {
+            value: value.to_string(),
+            variants,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    pub fn variants(&self) -> &'static [&'static str] {
+        self.variants
+    }
+
+    ///
+    /// Reads `val` as another of this enum's variants.
+    ///
+    pub fn parse_variant(
+        &self,
+        loc: Traceable,
+        val: Value,
+    ) -> Result<AvValue, Box<dyn TraceableError>> {
+        match val {
+            Value::String(s) if self.variants.contains(&s.as_str()) => {
+                Ok(AvValue::AvEnum(Self::new(&s, self.variants)))
+            }
+
+            Value::String(s) => Err(Box::new(UnexpectedVariant(loc, s, self.variants))),
+
+            v => Err(Box::new(UnexpectedType::from(loc, "String", v))),
+        }
+    }
+}
+
+#[AvError(TraceableError, CONFIG_UNEXPECTED_VARIANT, "Config: Unexpected Value")]
+pub struct UnexpectedVariant(Traceable, String, &'static [&'static str]);
+
+impl TraceableError for UnexpectedVariant {
+    location!(&self.0);
+    description!((
+        "Expected one of {}, got `{}`.",
+        self.2
+            .iter()
+            .map(|v| format!("`{}`", v.blue()))
+            .collect::<Vec<_>>()
+            .join(", "),
+        self.1.blue()
+    ));
+
+    fn help(&self) -> Option<String> {
+        let closest: &str = closest_match(&self.1, self.2.iter().copied())?;
+
+        Some(format!("did you mean `{}`?", closest.blue()))
+    }
+}
+
+///
+/// A color, as (non-premultiplied) RGBA.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AvColor(pub [f32; 4]);
+
+impl AvColor {
+    ///
+    /// `#rrggbb` or `#rrggbbaa` -- leaving out
+    /// the alpha makes it opaque.
+    ///
+    pub fn parse(color: &str) -> Option<Self> {
+        let hex: &str = color.strip_prefix('#')?;
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+            return None;
+        }
+
+        let channel = |i: usize| match hex.get(i * 2..i * 2 + 2) {
+            Some(c) => u8::from_str_radix(c, 16).ok().map(|c| c as f32 / 255.0),
+            None => Some(1.0),
+        };
+
+        Some(Self([channel(0)?, channel(1)?, channel(2)?, channel(3)?]))
+    }
+}
+
+impl fmt::Display for AvColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b, a] = self.0.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+
+        match a {
+            255 => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+            _ => write!(f, "#{:02x}{:02x}{:02x}{:02x}", r, g, b, a),
+        }
+    }
+}
+
+#[AvError(TraceableError, CONFIG_INVALID_COLOR, "Config: Invalid Color")]
+pub struct InvalidColor(Traceable, String);
+
+impl TraceableError for InvalidColor {
+    location!(&self.0);
+    description!(("`{}` isn't a color.", self.1.blue()));
+
+    fn help(&self) -> Option<String> {
+        Some(format!(
+            "colors are written `{}`, or `{}` to make them see-through",
+            "#RRGGBB".blue(),
+            "#RRGGBBAA".blue()
+        ))
+    }
+}
+
+impl AvDeserialize for AvColor {
+    fn deserialize(loc: Traceable, val: Value) -> Result<AvValue, Box<dyn TraceableError>> {
+        match val {
+            Value::String(s) => match Self::parse(&s) {
+                Some(c) => Ok(AvValue::AvColor(c)),
+                None => Err(Box::new(InvalidColor(loc, s))),
+            },
+
+            v => Err(Box::new(UnexpectedType::from(loc, "Color", v))),
+        }
+    }
+}
+
+///
+/// A length of time -- written with a unit (`250ms`, `1.5s`, `2m`),
+/// or as a plain number of milliseconds.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AvDuration(pub Duration);
+
+impl AvDuration {
+    pub fn parse(duration: &str) -> Option<Self> {
+        let split: usize = duration.find(|c: char| c.is_ascii_alphabetic())?;
+        let (amount, unit): (&str, &str) = duration.split_at(split);
+
+        let amount: f64 = amount.trim().parse().ok()?;
+        let nanos: f64 = match unit {
+            "ms" => amount * 1e6,
+            "s" => amount * 1e9,
+            "m" => amount * 60e9,
+            _ => return None,
+        };
+
+        match nanos.is_finite() && nanos >= 0.0 && nanos <= u64::MAX as f64 {
+            true => Some(Self(Duration::from_nanos(nanos.round() as u64))),
+            false => None,
+        }
+    }
+}
+
+impl fmt::Display for AvDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms: u128 = self.0.as_millis();
+
+        match ms {
+            ms if ms > 0 && ms % 1000 == 0 => write!(f, "{}s", ms / 1000),
+            ms => write!(f, "{}ms", ms),
+        }
+    }
+}
+
+impl From<AvDuration> for Duration {
+    fn from(d: AvDuration) -> Self {
+        d.0
+    }
+}
+
+#[AvError(TraceableError, CONFIG_INVALID_DURATION, "Config: Invalid Duration")]
+pub struct InvalidDuration(Traceable, String);
+
+impl TraceableError for InvalidDuration {
+    location!(&self.0);
+    description!(("`{}` isn't a duration.", self.1.blue()));
+
+    fn help(&self) -> Option<String> {
+        Some(format!(
+            "durations are written like `{}`, `{}` or `{}`",
+            "250ms".blue(),
+            "1.5s".blue(),
+            "2m".blue()
+        ))
+    }
+}
+
+impl AvDeserialize for AvDuration {
+    fn deserialize(loc: Traceable, val: Value) -> Result<AvValue, Box<dyn TraceableError>> {
+        match val {
+            // Plain milliseconds, like durations used to be.
+            Value::Number(ref n) => match n.as_u64() {
+                Some(ms) => Ok(AvValue::AvDuration(Self(Duration::from_millis(ms)))),
+                None => Err(Box::new(InvalidDuration(loc, n.to_string()))),
+            },
+
+            Value::String(s) => match Self::parse(&s) {
+                Some(d) => Ok(AvValue::AvDuration(d)),
+                None => Err(Box::new(InvalidDuration(loc, s))),
+            },
+
+            v => Err(Box::new(UnexpectedType::from(loc, "Duration", v))),
+        }
+    }
+}
+
+///
+/// A nested object -- declared in `config_section!`
+/// as `{ key: value, ... }`, with each value its default.
+///
+/// Keys left out keep their default, and each value has
+/// to have the same type as its default.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvObject(pub BTreeMap<String, AvValue>);
+
+impl AvObject {
+    pub fn get<'a, T>(&'a self, key: &str) -> Option<T>
+    where
+        T: TryFrom<&'a AvValue>,
+    {
+        T::try_from(self.0.get(key)?).ok()
+    }
+
+    ///
+    /// Reads `val` as an object with the same keys (and types) as this one.
+    ///
+    /// Problems with a key are reported where it is (found from `path`,
+    /// the object's path in the config) -- or at `loc`, if it can't be found.
+    ///
+    pub fn parse_same_keys(
+        &self,
+        loc: Traceable,
+        path: &JSONPath,
+        val: Value,
+    ) -> Result<AvValue, Box<dyn TraceableError>> {
+        let raw = match val {
+            Value::Object(o) => o,
+            v => return Err(Box::new(UnexpectedType::from(loc, "Object", v))),
+        };
+
+        let mut object: BTreeMap<String, AvValue> = self.0.clone();
+        let mut unknown: Vec<Box<dyn TraceableError>> = vec![];
+
+        for (key, v) in raw {
+            let at: JSONPath = path.push(key.clone());
+            let locate =
+                |of_key: bool| Config::locate(&at, Some(of_key)).unwrap_or_else(|| loc.clone());
+
+            match self.0.get(&key) {
+                Some(default) => {
+                    let v: AvValue = default.parse_same_type(locate(false), &at, v)?;
+                    object.insert(key, v);
+                }
+
+                None => {
+                    let closest: Option<String> =
+                        closest_match(&key, self.0.keys().map(String::as_str)).map(str::to_string);
+
+                    unknown.push(Box::new(UnknownKey(locate(true), key, closest)));
+                }
+            }
+        }
+
+        // Extra keys are harmless, so they don't stop the rest being used.
+        Config::report(unknown);
+
+        Ok(AvValue::AvObject(Self(object)))
+    }
+}
+
+impl<const N: usize> From<[(&str, AvValue); N]> for AvObject {
+    fn from(entries: [(&str, AvValue); N]) -> Self {
+        Self(
+            entries
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+}
+
+#[AvError(TraceableError, CONFIG_UNKNOWN_KEY, "Config: Unknown Key", Warning)]
+pub struct UnknownKey(Traceable, String, Option<String>);
+
+impl TraceableError for UnknownKey {
+    location!(&self.0);
+    description!((
+        "This object doesn't have a `{}` -- ignoring it.",
+        self.1.blue()
+    ));
+
+    fn help(&self) -> Option<String> {
+        let closest: &String = self.2.as_ref()?;
+
+        Some(format!("did you mean `{}`?", closest.blue()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use compositor_macros::traceable;
+    use json_tree::JSONPath;
+    use serde_json::json;
+
+    use crate::config::templating::AvValue;
+
+    use super::{AvColor, AvDuration, AvEnum, AvObject};
+
+    #[test]
+    fn colors() {
+        assert_eq!(
+            AvColor::parse("#ff0000"),
+            Some(AvColor([1.0, 0.0, 0.0, 1.0]))
+        );
+        assert_eq!(
+            AvColor::parse("#00000066").unwrap().to_string(),
+            "#00000066"
+        );
+        assert_eq!(AvColor::parse("#cccce6").unwrap().to_string(), "#cccce6");
+        assert!(AvColor::parse("cccce6").is_none());
+        assert!(AvColor::parse("#ccce6").is_none());
+        assert!(AvColor::parse("#gggggg").is_none());
+    }
+
+    #[test]
+    fn durations() {
+        let ms = |ms: u64| Some(AvDuration(Duration::from_millis(ms)));
+
+        assert_eq!(AvDuration::parse("250ms"), ms(250));
+        assert_eq!(AvDuration::parse("1.5s"), ms(1500));
+        assert_eq!(AvDuration::parse("2m"), ms(120_000));
+        assert!(AvDuration::parse("250").is_none());
+        assert!(AvDuration::parse("-1s").is_none());
+        assert!(AvDuration::parse("3h").is_none());
+
+        assert_eq!(AvDuration(Duration::from_secs(10)).to_string(), "10s");
+        assert_eq!(AvDuration(Duration::from_millis(250)).to_string(), "250ms");
+    }
+
+    #[test]
+    fn enums() {
+        let e = AvEnum::new("click", &["click", "sloppy"]);
+
+        assert_eq!(
+            e.parse_variant(traceable!(), json!("sloppy")).unwrap(),
+            AvValue::AvEnum(AvEnum::new("sloppy", &["click", "sloppy"]))
+        );
+
+        let err = e.parse_variant(traceable!(), json!("slopy")).unwrap_err();
+        assert_eq!(err.code(), "CONFIG_UNEXPECTED_VARIANT");
+        assert!(err.help().is_some());
+    }
+
+    #[test]
+    fn objects() {
+        let o = AvObject::from([("gap", AvValue::i64(8)), ("smart", AvValue::bool(true))]);
+        let path: JSONPath = "$.snapping.layout".to_string().try_into().unwrap();
+
+        let parsed = o
+            .parse_same_keys(traceable!(), &path, json!({ "gap": 4 }))
+            .unwrap();
+        let AvValue::AvObject(parsed) = parsed else {
+            panic!()
+        };
+
+        assert_eq!(parsed.get::<i64>("gap"), Some(4));
+        assert_eq!(parsed.get::<bool>("smart"), Some(true));
+
+        let err = o
+            .parse_same_keys(traceable!(), &path, json!({ "gap": "wide" }))
+            .unwrap_err();
+        assert_eq!(err.code(), "CONFIG_UNEXPECTED_TYPE");
+    }
+}
@@ -5,6 +5,19 @@ use lazy_static::lazy_static;
 use crate::compat::{BaseDir, XdgBaseDir};
 
 lazy_static! {
-    pub static ref CONFIG_FOLDER: PathBuf = { XdgBaseDir::Config.path().join("avdan") };
+    /// `None` without a `$HOME` (or `$XDG_CONFIG_HOME`) to keep the user's config in.
+    pub static ref CONFIG_FOLDER: Option<PathBuf> =
+        XdgBaseDir::Config.path().map(|p| p.join("avdan"));
+
+    /// Where distros put their config (from `$XDG_CONFIG_DIRS`), most important first.
+    pub static ref SYSTEM_CONFIG_FOLDERS: Vec<PathBuf> = XdgBaseDir::Config
+        .search_path()
+        .into_iter()
+        .map(|p| p.join("avdan"))
+        .collect();
+
     pub static ref CONFIG_FILE: &'static Path = Path::new("Compositor.jsonc");
+
+    /// A copy of the last config that loaded -- used when the config's broken.
+    pub static ref LAST_GOOD_CONFIG_FILE: &'static Path = Path::new(".Compositor.last-good.jsonc");
 }
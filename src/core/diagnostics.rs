@@ -0,0 +1,250 @@
+//!
+//! Machine-readable renderings of [`TraceableError`]s,
+//! for editor plugins and CI.
+//!
+//! Picked with `--diagnostics <FORMAT>` (for `--check-config`),
+//! or the `NAVDA_DIAGNOSTICS` environment variable:
+//!
+//! * `human` -- the usual colourful text (the default).
+//! * `json` -- an array of [`Diagnostic`]s (one per line outside `--check-config`).
+//! * `sarif` -- a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log.
+//!
+
+use std::{collections::BTreeMap, env, str::FromStr};
+
+use serde_json::{json, Value};
+
+use super::error::TraceableError;
+
+pub const ENV_VAR: &str = "NAVDA_DIAGNOSTICS";
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+///
+/// How errors get printed.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Human,
+    Json,
+    Sarif,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            other => Err(format!(
+                "Unknown diagnostics format `{}` (expected `human`, `json` or `sarif`).",
+                other
+            )),
+        }
+    }
+}
+
+impl Format {
+    ///
+    /// The format asked for in `NAVDA_DIAGNOSTICS`
+    /// -- [`Format::Human`] if it's unset (or nonsense).
+    ///
+    pub fn from_env() -> Self {
+        env::var(ENV_VAR)
+            .ok()
+            .and_then(|f: String| f.parse().ok())
+            .unwrap_or_default()
+    }
+
+    ///
+    /// Renders a whole batch of `errors` at once.
+    ///
+    pub fn render(&self, errors: &[Box<dyn TraceableError>]) -> String {
+        match self {
+            Format::Human => errors.iter().map(|err| format!("{}\n", err)).collect(),
+            Format::Json => {
+                let diagnostics: Vec<Diagnostic> = errors.iter().map(Diagnostic::from).collect();
+                serde_json::to_string_pretty(&json!(diagnostics)).unwrap()
+            }
+            Format::Sarif => serde_json::to_string_pretty(&sarif(errors)).unwrap(),
+        }
+    }
+
+    ///
+    /// Renders a single error, as it's reported -- one line
+    /// of JSON for the machine-readable formats.
+    ///
+    pub fn render_one(&self, err: &Box<dyn TraceableError>) -> String {
+        match self {
+            Format::Human => err.to_string(),
+            Format::Json | Format::Sarif => json!(Diagnostic::from(err)).to_string(),
+        }
+    }
+}
+
+///
+/// Everything about an error, minus the colours.
+///
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Diagnostic {
+    pub code: String,
+    pub title: String,
+    pub severity: &'static str,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub description: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+}
+
+impl From<&Box<dyn TraceableError>> for Diagnostic {
+    fn from(err: &Box<dyn TraceableError>) -> Self {
+        let location = err.location();
+
+        // Descriptions highlight things with colours -- which
+        // would be escape codes in the middle of the JSON.
+        colored::control::set_override(false);
+        let description: String = err.description();
+        let help: Option<String> = err.help();
+        colored::control::unset_override();
+
+        Self {
+            code: err.code(),
+            title: err.title(),
+            severity: err.severity().name(),
+            file: location.path().to_string(),
+            line: location.line(),
+            column: location.column(),
+            description,
+            help,
+        }
+    }
+}
+
+///
+/// SARIF calls hints notes.
+///
+fn sarif_level(severity: &str) -> &str {
+    match severity {
+        "hint" => "note",
+        level => level,
+    }
+}
+
+///
+/// A SARIF log with a single run -- each error code is a rule.
+///
+fn sarif(errors: &[Box<dyn TraceableError>]) -> Value {
+    let diagnostics: Vec<Diagnostic> = errors.iter().map(Diagnostic::from).collect();
+
+    let rules: BTreeMap<&str, &str> = diagnostics
+        .iter()
+        .map(|d: &Diagnostic| (d.code.as_str(), d.title.as_str()))
+        .collect();
+
+    let rules: Vec<Value> = rules
+        .into_iter()
+        .map(|(code, title)| json!({ "id": code, "shortDescription": { "text": title } }))
+        .collect();
+
+    let results: Vec<Value> = diagnostics
+        .iter()
+        .map(|d: &Diagnostic| {
+            json!({
+                "ruleId": d.code,
+                "level": sarif_level(d.severity),
+                "message": {
+                    "text": match &d.help {
+                        Some(help) => format!("{}\nhelp: {}", d.description, help),
+                        None => d.description.clone(),
+                    },
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": d.file },
+                        "region": { "startLine": d.line, "startColumn": d.column },
+                    },
+                }],
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "navda",
+                    "informationUri": "https://avdanos.org",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use colored::Colorize;
+
+    use compositor_macros::AvError;
+
+    use crate::core::error::{AvError, Traceable, TraceableError};
+
+    use super::{Diagnostic, Format};
+
+    #[AvError(TraceableError, TEST_DIAGNOSTIC, "Test Diagnostic")]
+    struct TestError(Traceable);
+
+    impl TraceableError for TestError {
+        fn location(&self) -> &Traceable {
+            &self.0
+        }
+
+        fn description(&self) -> String {
+            format!("Something `{}` happened.", "blue".blue())
+        }
+    }
+
+    fn errors() -> Vec<Box<dyn TraceableError>> {
+        vec![Box::new(TestError(Traceable::new(
+            "Compositor.jsonc".to_string(),
+            (3, 5),
+        )))]
+    }
+
+    #[test]
+    fn plain_description() {
+        let d: Diagnostic = Diagnostic::from(&errors()[0]);
+
+        assert_eq!(d.code, "TEST_DIAGNOSTIC");
+        assert_eq!(d.description, "Something `blue` happened.");
+        assert_eq!((d.line, d.column), (3, 5));
+    }
+
+    #[test]
+    fn sarif() {
+        let log: serde_json::Value =
+            serde_json::from_str(&Format::Sarif.render(&errors())).unwrap();
+        let result = &log["runs"][0]["results"][0];
+
+        assert_eq!(result["ruleId"], "TEST_DIAGNOSTIC");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"]["startLine"],
+            3
+        );
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!("JSON".parse(), Ok(Format::Json));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
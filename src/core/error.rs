@@ -1,8 +1,8 @@
 use colored::Colorize;
 
-use json_tree::Location;
+use json_tree::{Location, TokenContent};
 
-use std::cmp::Ordering;
+use std::{cmp::Ordering, sync::Arc};
 
 pub mod color {
     use colored::Color;
@@ -26,6 +26,37 @@ pub mod color {
     };
 }
 
+///
+/// How bad an error is.
+///
+/// Errors stop the config from loading; warnings and
+/// hints are only shown (unless they're turned off).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Hint,
+}
+
+impl Severity {
+    pub fn color(&self) -> colored::Color {
+        match self {
+            Severity::Error => color::ERROR,
+            Severity::Warning => color::WARNING,
+            Severity::Hint => color::NEUTRAL,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Hint => "hint",
+        }
+    }
+}
+
 pub trait Indentable
 where
     Self: Sized,
@@ -108,6 +139,16 @@ pub trait AvError: std::fmt::Display + std::fmt::Debug + std::error::Error {
     ///
     fn title(&self) -> String;
 
+    ///
+    /// How bad this error is -- see [`Severity`].
+    ///
+    /// Set with the `#[AvError]` macro's
+    /// (optional) last parameter.
+    ///
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
     ///
     /// The content of this error warning.
     ///
@@ -143,6 +184,18 @@ pub struct Traceable {
     /// error occurred.
     ///
     column: usize,
+
+    ///
+    /// How many characters (from `column`)
+    /// the offending token is.
+    ///
+    length: usize,
+
+    ///
+    /// The file's contents, as they were read
+    /// -- for [`Traceable::snippet`].
+    ///
+    source: Option<Arc<str>>,
 }
 
 impl Traceable {
@@ -154,18 +207,49 @@ impl Traceable {
             path,
             line: loc.0,
             column: loc.1,
+            length: 1,
+            source: None,
+        }
+    }
+
+    ///
+    /// This, with the contents of its file (if they're known).
+    ///
+    pub fn with_source(self, source: Option<Arc<str>>) -> Self {
+        Self { source, ..self }
+    }
+
+    ///
+    /// This, covering `token` (as written in the file).
+    ///
+    pub fn spanning(self, token: &str) -> Self {
+        Self {
+            length: token.chars().count().max(1),
+            ..self
         }
     }
 
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
     ///
     /// Returns new [`Traceable`] at a string index
     /// relative to the current column pos.
     ///
     pub fn at_index(&self, index: usize) -> Self {
         Self {
-            path: self.path.clone(),
-            line: self.line,
             column: self.column + 1 + index, // Account for the ""  ^
+            length: 1,
+            ..self.clone()
         }
     }
 
@@ -175,9 +259,10 @@ impl Traceable {
     ///
     pub fn at_loc(&self, (line, column): (usize, usize)) -> Self {
         Self {
-            path: self.path.clone(),
             line,
             column,
+            length: 1,
+            ..self.clone()
         }
     }
 
@@ -186,7 +271,7 @@ impl Traceable {
     ///
     /// key object determines if it should return the Locatable for the key or value  
     pub fn combine(path: &String, loc: &Location, key: Option<bool>) -> Self {
-        let (line, column): (usize, usize) = match loc {
+        let token: TokenContent = match loc {
             Location::KeyValue(k, v) => match key {
                 None => {
                     unimplemented!("Should pass in Some(bool) as last argument for KeyValue pairs")
@@ -197,13 +282,10 @@ impl Traceable {
 
             Location::Value(v) => v,
         }
-        .loc();
+        .clone()
+        .into();
 
-        Self {
-            path: path.clone(),
-            line,
-            column,
-        }
+        Self::new(path.clone(), token.loc()).spanning(&token.content)
     }
 }
 
@@ -270,15 +352,140 @@ pub trait TraceableError: AvError {
 
     fn description(&self) -> String;
 
+    ///
+    /// A suggestion for fixing this error, if there is one
+    /// -- shown as `= help: ...` under the source.
+    ///
+    fn help(&self) -> Option<String> {
+        None
+    }
+
     fn body(&self) -> String {
-        format!(
-            "{}\nat {}\n",
+        let location: &Traceable = self.location();
+
+        let mut body: String = format!(
+            "{}\n{} {}\n",
             TraceableError::description(self),
-            self.location().to_string().color(color::NEUTRAL)
-        )
+            "-->".color(color::NEUTRAL),
+            location.to_string().color(color::NEUTRAL)
+        );
+
+        if let Some(snippet) = location.snippet(self.severity()) {
+            body += &snippet;
+        }
+
+        if let Some(help) = self.help() {
+            body += &format!("{} {}\n", "= help:".bold(), help);
+        }
+
+        body
+    }
+}
+
+impl Traceable {
+    ///
+    /// The offending line of the file, rustc-style, with the token
+    /// at this location underlined -- `None` without the file's contents.
+    ///
+    /// ```text
+    ///    |
+    /// 12 |     "closeWindw": "Alt+F4",
+    ///    |     ^^^^^^^^^^^^
+    /// ```
+    ///
+    pub fn snippet(&self, severity: Severity) -> Option<String> {
+        let line: &str = self
+            .source
+            .as_ref()?
+            .lines()
+            .nth(self.line.checked_sub(1)?)?;
+
+        let column: usize = self.column.max(1);
+        let start: String = line
+            .chars()
+            .take(column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        let number: String = self.line.to_string();
+        let gutter: String = " ".repeat(number.len());
+        let bar = "|".color(color::NEUTRAL);
+
+        Some(format!(
+            "{} {}\n{} {} {}\n{} {} {}{}\n",
+            gutter,
+            bar,
+            number.color(color::NEUTRAL),
+            bar,
+            line,
+            gutter,
+            bar,
+            start,
+            "^".repeat(underline_length(line, column, self.length))
+                .color(severity.color())
+        ))
+    }
+}
+
+///
+/// How many characters to underline for a token `length` long, at
+/// `column` (1-based) of `line` -- along with the quotes around
+/// it, if it's a string that left them off.
+///
+fn underline_length(line: &str, column: usize, length: usize) -> usize {
+    let token: Vec<char> = line.chars().skip(column - 1).take(length).collect();
+
+    match (token.first(), token.last()) {
+        (Some('"'), Some(c)) if length == 1 || *c != '"' => length + 2,
+        _ => length,
+    }
+}
+
+///
+/// The candidate closest to `name` -- if it's close
+/// enough to be a typo (for `help: did you mean ...?`).
+///
+pub fn closest_match<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    // About one typo every three characters.
+    let max: usize = (name.chars().count() / 3).max(1);
+
+    candidates
+        .into_iter()
+        .map(|c: &str| (edit_distance(&name.to_lowercase(), &c.to_lowercase()), c))
+        .filter(|(d, _)| *d <= max)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+///
+/// Levenshtein distance between `a` and `b`.
+///
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev: usize = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost: usize = if ca == *cb { 0 } else { 1 };
+            let next: usize = (row[j] + 1).min(row[j + 1] + 1).min(prev + cost);
+
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
     }
+
+    row[b.len()]
 }
 
+///
+/// Orders errors by file, then by where they are in it.
+///
 pub fn compare_errors(
     s: &Box<dyn TraceableError>,
     o: &Box<dyn TraceableError>,
@@ -287,7 +494,7 @@ pub fn compare_errors(
     let o: &Traceable = o.location();
 
     if s.path != o.path {
-        return None;
+        return s.path.partial_cmp(&o.path);
     }
 
     match s.line.partial_cmp(&o.line).unwrap() {
@@ -300,7 +507,11 @@ pub fn compare_errors(
 mod tests {
     use compositor_macros::AvError;
 
-    use crate::core::error::{Traceable, TraceableError};
+    use std::sync::Arc;
+
+    use crate::core::error::{
+        closest_match, underline_length, Severity, Traceable, TraceableError,
+    };
 
     use super::AvError;
 
@@ -313,16 +524,72 @@ mod tests {
         println!("{}", test);
     }
 
+    #[test]
+    fn severity() {
+        #[AvError(TEST_WARNING, "Test Warning", Warning)]
+        struct TestWarning;
+
+        #[AvError(TraceableError, TEST_DERIVE_ERROR, "Test Error")]
+        struct TestError(Traceable);
+
+        impl TraceableError for TestError {
+            fn location(&self) -> &Traceable {
+                &self.0
+            }
+
+            fn description(&self) -> String {
+                "Test error".into()
+            }
+        }
+
+        let error: TestError = TestError(Traceable::new("config.txt".to_string(), (1, 1)));
+
+        assert_eq!(TestWarning.severity(), Severity::Warning);
+        assert_eq!(error.severity(), Severity::Error);
+    }
+
+    #[test]
+    fn suggestions() {
+        let macros = ["closeWindow", "minimizeWindow", "workspace"];
+
+        assert_eq!(closest_match("closeWindw", macros), Some("closeWindow"));
+        assert_eq!(closest_match("CloseWindow", macros), Some("closeWindow"));
+        assert_eq!(closest_match("launchTerminal", macros), None);
+    }
+
+    #[test]
+    fn underlines() {
+        let line: &str = r#"    "closeWindw": "Alt+F4","#;
+
+        assert_eq!(underline_length(line, 5, 12), 12);
+        assert_eq!(underline_length(line, 5, 10), 12);
+        assert_eq!(underline_length(line, 19, 6), 8);
+        assert_eq!(underline_length("    96,", 5, 2), 2);
+    }
+
+    #[test]
+    fn snippets() {
+        let source: Arc<str> = "{\n  \"closeWindw\": \"Alt+F4\"\n}".into();
+        let at: Traceable = Traceable::new("config.jsonc".to_string(), (2, 3));
+
+        assert_eq!(at.snippet(Severity::Error), None);
+
+        let snippet: String = at
+            .with_source(Some(source))
+            .spanning("closeWindw")
+            .snippet(Severity::Error)
+            .unwrap();
+
+        assert!(snippet.contains(r#"  "closeWindw": "Alt+F4""#));
+        assert_eq!(snippet.matches('^').count(), 12);
+    }
+
     #[test]
     fn locatable() {
         #[AvError(TraceableError, TEST_DERIVE_ERROR, "Test Error")]
         struct Locatable(Traceable);
 
-        let test: Locatable = Locatable(Traceable {
-            path: "src/core/error.rs".to_string(),
-            line: 203,
-            column: 20,
-        });
+        let test: Locatable = Locatable(Traceable::new("src/core/error.rs".to_string(), (203, 20)));
 
         impl TraceableError for Locatable {
             fn location(&self) -> &Traceable {
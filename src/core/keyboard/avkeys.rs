@@ -6,14 +6,14 @@ use crate::{
     config::templating::{
         avvalue::{AvValue, UnexpectedType},
         r#macro::ParameterError,
-        AvDeserialize, MacroParameter,
+        AvDeserialize, MacroArgument, MacroParameter,
     },
     core::error::{AvError, Traceable, TraceableError},
 };
 
 use serde_json::Value;
 
-use std::convert::TryFrom;
+use std::{collections::HashMap, convert::TryFrom};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum AvKey {
@@ -53,6 +53,76 @@ impl ToString for AvKey {
 #[derive(Debug, PartialEq)]
 pub struct AvKeys(pub Vec<AvKey>);
 
+///
+/// What each parameter stands for, in
+/// one expansion of an [`AvKeys`].
+///
+pub type Arguments = HashMap<MacroParameter, MacroArgument>;
+
+impl AvKeys {
+    ///
+    /// The macro parameters used, in order
+    /// (repeats included).
+    ///
+    pub fn parameters(&self) -> Vec<MacroParameter> {
+        self.0
+            .iter()
+            .filter_map(|k: &AvKey| match k {
+                AvKey::Parameter(p) => Some(p.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    ///
+    /// Every combination of keys the parameters stand for
+    /// -- `Logo+{dir}` is `Logo+Left`, `Logo+Right`, ... --
+    /// along with what they meant.
+    ///
+    /// Keys without parameters expand to just themselves.
+    ///
+    pub fn expand(&self) -> Vec<(Arguments, AvKeys)> {
+        let mut expanded: Vec<(Arguments, Vec<AvKey>)> = vec![(HashMap::new(), vec![])];
+
+        for key in &self.0 {
+            let p: &MacroParameter = match key {
+                AvKey::Key(_) => {
+                    expanded
+                        .iter_mut()
+                        .for_each(|(_, keys)| keys.push(key.clone()));
+                    continue;
+                }
+                AvKey::Parameter(p) => p,
+            };
+
+            expanded = expanded
+                .into_iter()
+                .flat_map(|(arguments, keys)| {
+                    p.expansions()
+                        .into_iter()
+                        // A repeated parameter means the same thing each time.
+                        .filter(|(_, a)| arguments.get(p).map_or(true, |b| a == b))
+                        .map(|(k, a)| {
+                            let mut arguments: Arguments = arguments.clone();
+                            arguments.insert(p.clone(), a);
+
+                            let mut keys: Vec<AvKey> = keys.clone();
+                            keys.push(AvKey::Key(k));
+
+                            (arguments, keys)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+        }
+
+        expanded
+            .into_iter()
+            .map(|(arguments, keys)| (arguments, AvKeys(keys)))
+            .collect()
+    }
+}
+
 impl TryFrom<String> for AvKeys {
     type Error = (String, String);
 
@@ -126,13 +196,44 @@ impl Default for AvKeys {
     }
 }
 
+///
+/// Several shortcuts for the same thing -- written as
+/// an array of them, or just the one on its own.
+///
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AvKeysList(pub Vec<AvKeys>);
+
+impl AvDeserialize for AvKeysList {
+    fn deserialize(loc: Traceable, val: Value) -> Result<AvValue, Box<dyn TraceableError>> {
+        let keys: Vec<Value> = match val {
+            Value::Array(keys) => keys,
+            Value::String(_) => vec![val],
+            v => return Err(Box::new(UnexpectedType::from(loc, "String or Array", v))),
+        };
+
+        let keys = keys
+            .into_iter()
+            .map(|k: Value| match AvKeys::deserialize(loc.clone(), k)? {
+                AvValue::AvKeys(k) => Ok(k),
+                _ => unreachable!(),
+            });
+
+        Ok(AvValue::AvKeysList(Self(keys.collect::<Result<_, _>>()?)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::config::templating::MacroParameter;
+    use crate::config::templating::{Direction, MacroArgument, MacroParameter};
 
     use std::convert::TryFrom;
 
-    use super::{AvKey, AvKeys};
+    use compositor_macros::traceable;
+    use serde_json::json;
+
+    use crate::config::templating::{AvDeserialize, AvValue};
+
+    use super::{Arguments, AvKey, AvKeys, AvKeysList};
 
     #[test]
     fn deserialize() {
@@ -148,4 +249,38 @@ mod tests {
             ]))
         )
     }
+
+    #[test]
+    fn expand() {
+        let keys = AvKeys::try_from("Logo+{dir}".to_string()).unwrap();
+        let expanded = keys.expand();
+
+        assert_eq!(expanded.len(), 4);
+        assert_eq!(expanded[0].1.to_string(), "Logo+Left");
+        assert_eq!(
+            expanded[0].0.get(&MacroParameter::Direction),
+            Some(&MacroArgument::Direction(Direction::Left))
+        );
+
+        let keys = AvKeys::try_from("Ctrl+{kp}+{F}".to_string()).unwrap();
+        assert_eq!(keys.expand().len(), 10 * 12);
+
+        let keys = AvKeys::try_from("Alt+F4".to_string()).unwrap();
+        assert_eq!(keys.expand(), vec![(Arguments::new(), keys)]);
+    }
+
+    #[test]
+    fn lists() {
+        let keys = |k: &str| AvKeys::try_from(k.to_string()).unwrap();
+
+        assert_eq!(
+            AvKeysList::deserialize(traceable!(), json!("Alt+F4")).unwrap(),
+            AvValue::AvKeysList(AvKeysList(vec![keys("Alt+F4")]))
+        );
+        assert_eq!(
+            AvKeysList::deserialize(traceable!(), json!(["Logo+q", "Alt+F4"])).unwrap(),
+            AvValue::AvKeysList(AvKeysList(vec![keys("Logo+q"), keys("Alt+F4")]))
+        );
+        assert!(AvKeysList::deserialize(traceable!(), json!(["Logo+q", 4])).is_err());
+    }
 }
@@ -1,3 +1,4 @@
 pub mod avkeys;
 pub use avkeys::AvKey;
 pub use avkeys::AvKeys;
+pub use avkeys::AvKeysList;
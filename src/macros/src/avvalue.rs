@@ -9,6 +9,8 @@ use syn::{
     Error, Ident, LitBool, LitFloat, LitInt, LitStr, Result, Token,
 };
 
+use std::time::Duration;
+
 pub enum AvValue {
     String(LitStr),
     Integer(LitInt),
@@ -17,6 +19,19 @@ pub enum AvValue {
     Boolean(LitBool),
     AvKeys(Punctuated<AvKey, Token![+]>),
     List(Punctuated<AvValue, Token![,]>),
+    Enum(Punctuated<LitStr, Token![|]>),
+    Color(LitStr),
+    Duration(LitStr),
+    Object(Punctuated<Field, Token![,]>),
+}
+
+///
+/// `key: value`, in an object.
+///
+pub struct Field {
+    key: Ident,
+    _colon: Token![:],
+    value: AvValue,
 }
 
 pub enum AvKey {
@@ -34,7 +49,11 @@ impl AvValue {
             Null(_) => panic!("Null token is not supported for deserialization!"),
             Boolean(_) => quote! { bool },
             AvKeys(_) => quote! { AvKeys },
-            List(_) => panic!("List tokens are not supported for deserialization yet!"),
+            List(_) => quote! { AvKeysList },
+            Enum(_) => quote! { AvEnum },
+            Color(_) => quote! { AvColor },
+            Duration(_) => quote! { AvDuration },
+            Object(_) => quote! { AvObject },
         }
     }
 
@@ -46,36 +65,112 @@ impl AvValue {
             AvValue::Float(s) => quote! { #s.into() },
             AvValue::Null(_) => panic!("Null token is not supported for deserialization!"),
             AvValue::Boolean(s) => quote! { #s.into() },
-            AvValue::AvKeys(s) => {
-                let t = s.iter().map(|k: &AvKey| match k {
-                    AvKey::Key(k) => {
-                        let k: String = k.to_string();
-
-                        quote! {
-                            AvKey::Key(#k.into())
-                        }
-                    }
-                    AvKey::Parameter(_, p) => {
-                        let p: String = p.to_string();
-
-                        quote! {
-                            AvKey::Parameter(#p.try_into().unwrap())
-                        }
-                    }
+            AvValue::AvKeys(s) => keys(s),
+
+            AvValue::List(l) => {
+                // Only lists of shortcuts get past parsing.
+                let t = l.iter().map(|v: &AvValue| match v {
+                    AvValue::AvKeys(s) => keys(s),
+                    _ => unreachable!(),
                 });
 
                 quote! {
-                    AvKeys ( vec![#(#t),*] )
+                    AvKeysList ( vec![#(#t),*] )
+                }
+            }
+
+            AvValue::Enum(e) => {
+                let default: &LitStr = e.first().unwrap();
+                let variants = e.iter();
+
+                quote! {
+                    AvEnum::new(#default, &[#(#variants),*])
                 }
             }
 
-            AvValue::List(_) => panic!("List tokens are not supported for deserialization yet!"),
+            AvValue::Color(s) => quote! { AvColor::parse(#s).unwrap() },
+            AvValue::Duration(s) => quote! { AvDuration::parse(#s).unwrap() },
+
+            AvValue::Object(o) => {
+                let t = o.iter().map(|f: &Field| {
+                    let k: String = f.key.to_string();
+                    let v: TokenStream = f.value.value();
+
+                    quote! { (#k, #v) }
+                });
+
+                quote! {
+                    AvObject::from([#(#t),*])
+                }
+            }
         };
 
         quote! { AvValue::#t(#v) }
     }
 }
 
+fn keys(s: &Punctuated<AvKey, Token![+]>) -> TokenStream {
+    let t = s.iter().map(|k: &AvKey| match k {
+        AvKey::Key(k) => {
+            let k: String = k.to_string();
+
+            quote! {
+                AvKey::Key(#k.into())
+            }
+        }
+        AvKey::Parameter(_, p) => {
+            let p: String = p.to_string();
+
+            quote! {
+                AvKey::Parameter(#p.try_into().unwrap())
+            }
+        }
+    });
+
+    quote! {
+        AvKeys ( vec![#(#t),*] )
+    }
+}
+
+///
+/// Same as `AvColor::parse`, to catch bad defaults at compile time.
+///
+fn is_color(color: &str) -> bool {
+    match color.strip_prefix('#') {
+        Some(hex) => {
+            (hex.len() == 6 || hex.len() == 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => false,
+    }
+}
+
+///
+/// Same as `AvDuration::parse`, to catch bad defaults at compile time.
+///
+fn is_duration(duration: &str) -> bool {
+    let split: usize = match duration.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => i,
+        None => return false,
+    };
+    let (amount, unit): (&str, &str) = duration.split_at(split);
+
+    matches!(unit, "ms" | "s" | "m")
+        && amount
+            .trim()
+            .parse::<f64>()
+            .map_or(false, |a| Duration::try_from_secs_f64(a).is_ok())
+}
+
+impl syn::parse::Parse for Field {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Field {
+            key: input.parse()?,
+            _colon: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+
 impl syn::parse::Parse for AvKey {
     fn parse(input: ParseStream) -> Result<Self> {
         let look: Lookahead1 = input.lookahead1();
@@ -110,9 +205,39 @@ impl syn::parse::Parse for AvValue {
         }
 
         if input.peek(syn::LitStr) {
+            // "a" | "b" | ...
+            if input.peek2(Token![|]) {
+                return Ok(Self::Enum(Punctuated::parse_separated_nonempty(input)?));
+            }
+
             return Ok(Self::String(input.parse()?));
         }
 
+        // color("#...") and duration("...")
+        if input.peek(syn::Ident) && input.peek2(syn::token::Paren) {
+            let ident: syn::Ident = input.parse()?;
+            let content: ParseBuffer;
+
+            let _ = syn::parenthesized!(content in input);
+            let lit: LitStr = content.parse()?;
+
+            return match ident.to_string().as_str() {
+                "color" if is_color(&lit.value()) => Ok(Self::Color(lit)),
+                "color" => Err(Error::new(lit.span(), "Expected `#RRGGBB` or `#RRGGBBAA`.")),
+
+                "duration" if is_duration(&lit.value()) => Ok(Self::Duration(lit)),
+                "duration" => Err(Error::new(
+                    lit.span(),
+                    "Expected a duration, like `250ms`, `1.5s` or `2m`.",
+                )),
+
+                _ => Err(Error::new(
+                    ident.span(),
+                    "Expected `color(...)` or `duration(...)`.",
+                )),
+            };
+        }
+
         if input.peek(syn::Ident) {
             let ident: syn::Ident = input.parse()?;
             if ident.to_string().eq("null") {
@@ -133,7 +258,27 @@ impl syn::parse::Parse for AvValue {
 
             let _ = syn::bracketed!(content in input);
 
-            return Ok(Self::List(content.parse_terminated(AvValue::parse)?));
+            let list: Punctuated<AvValue, Token![,]> = content.parse_terminated(AvValue::parse)?;
+
+            if !list
+                .iter()
+                .all(|v: &AvValue| matches!(v, AvValue::AvKeys(_)))
+            {
+                return Err(Error::new(
+                    input.span(),
+                    "Only lists of key combinations, like `[(Logo+q), (Alt+F4)]`, are supported.",
+                ));
+            }
+
+            return Ok(Self::List(list));
+        }
+
+        if input.peek(syn::token::Brace) {
+            let content: ParseBuffer;
+
+            let _ = syn::braced!(content in input);
+
+            return Ok(Self::Object(content.parse_terminated(Field::parse)?));
         }
 
         // let mut e = Diagnostic::new(Level::Error, "Expected a bool, float/int, string, null, or AvKey collection.");
@@ -141,7 +286,8 @@ impl syn::parse::Parse for AvValue {
         // e.emit();
         Err(Error::new(
             input.span(),
-            "Expected a bool, float/int, string, null, or AvKey collection.",
+            "Expected a bool, float/int, string, enum, color(...), duration(...), \
+            AvKey collection, list or object.",
         ))
     }
 }
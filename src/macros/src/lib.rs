@@ -62,7 +62,7 @@ pub fn AvValue(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     });
 
     quote! {
-        #[derive(Debug, PartialEq,)]
+        #[derive(Debug, Clone, PartialEq,)]
         #[allow(non_camel_case_types)]
 
         pub enum AvValue { #(#variants),* }
@@ -149,6 +149,27 @@ pub fn config_section(struc: proc_macro::TokenStream) -> proc_macro::TokenStream
         }
     });
 
+    // Both `Deserialize` and `Default` register the macros.
+    let macro_registration: Vec<TokenStream> = macro_registration.collect();
+
+    // What the JSON Schema generator needs to know about each macro.
+    let schema_entries = iter.clone().map(|m: &AvMacro| {
+        let default: TokenStream = m.default().value();
+        let (name, parameters): (String, Vec<String>) = m.av_macro();
+        let description: String = m.description();
+
+        quote! {
+            crate::config::schema::MacroSchema {
+                name: #name,
+                parameters: vec![#(#parameters),*],
+                description: #description,
+                default: #default,
+            }
+        }
+    });
+
+    let schema_entries: Vec<TokenStream> = schema_entries.collect();
+
     let macro_idents = iter.map(|m: &AvMacro| m.av_macro().0).map(|k: String| {
         let n: Ident = syn::Ident::new(&k, ident.span());
         quote! {
@@ -156,19 +177,28 @@ pub fn config_section(struc: proc_macro::TokenStream) -> proc_macro::TokenStream
         }
     });
 
+    let macro_idents: Vec<TokenStream> = macro_idents.collect();
+
     quote! {
         use compositor_macros::traceable;
 
+        // Not every section uses every type.
+        #[allow(unused_imports)]
         use crate::{
             config::{
                 templating::{
+                    AvColor,
+                    AvDuration,
+                    AvEnum,
                     AvMacro,
+                    AvObject,
                     AvValue,
                 },
             },
             core::keyboard::{
                 AvKey,
                 AvKeys,
+                AvKeysList,
             },
         };
 
@@ -202,6 +232,31 @@ pub fn config_section(struc: proc_macro::TokenStream) -> proc_macro::TokenStream
                 )
             }
         }
+
+        ///
+        /// Every macro set to its declared default --
+        /// used when this section is missing from the config.
+        ///
+        impl Default for #ident {
+            fn default() -> Self {
+                let mut ids = HashMap::new();
+                let m = {
+                    let mut declared: HashMap<AvMacro, AvValue> = HashMap::new();
+
+                    #(#macro_registration)*
+
+                    declared
+                };
+
+                #ident { #(#macro_idents)* }
+            }
+        }
+
+        impl crate::config::schema::SectionSchema for #ident {
+            fn macros() -> Vec<crate::config::schema::MacroSchema> {
+                vec![#(#schema_entries),*]
+            }
+        }
     }.into()
 }
 
@@ -217,6 +272,7 @@ extern crate proc_macro;
 /// 1. *(Optional)* Error Type - AvError (default), or a super trait of it.
 /// 2. Error Code -- The error code as an identifier (in TRAIN_CASE)
 /// 3. Error Title -- A user-friendly description of the error.
+/// 4. *(Optional)* Severity -- `Error` (default), `Warning` or `Hint`.
 ///
 ///
 #[proc_macro_attribute]
@@ -227,18 +283,44 @@ pub fn AvError(
 ) -> proc_macro::TokenStream {
     let a: Vec<NestedMeta> = parse_macro_input!(attributes as AttributeArgs);
 
-    let (parent, code, title): (Option<&NestedMeta>, &NestedMeta, &NestedMeta) = match a.len() {
-        2 => (None, a.get(0).unwrap(), a.get(1).unwrap()),
+    // The title's the only literal -- everything else is placed around it.
+    let t: Option<usize> = a.iter().position(|m| matches!(m, NestedMeta::Lit(_)));
+
+    let (parent, code, title, severity): (
+        Option<&NestedMeta>,
+        &NestedMeta,
+        &NestedMeta,
+        Option<&NestedMeta>,
+    ) = match (t, a.len()) {
+        (Some(1), 2 | 3) => (None, &a[0], &a[1], a.get(2)),
 
-        3 => (
-            Some(a.get(0).unwrap()),
-            a.get(1).unwrap(),
-            a.get(2).unwrap(),
-        ),
+        (Some(2), 3 | 4) => (Some(&a[0]), &a[1], &a[2], a.get(3)),
 
         _ => {
             return quote::quote! {
-                compile_error!("Expected two or three elements: [`TYPE`], `ERROR CODE`, `TITLE`")
+                compile_error!(
+                    "Expected two to four elements: [`TYPE`], `ERROR CODE`, `TITLE`, [`SEVERITY`]"
+                )
+            }
+            .into();
+        }
+    };
+
+    // Errors are errors, unless they say otherwise.
+    let severity: TokenStream = match severity {
+        None => quote! {},
+
+        Some(NestedMeta::Meta(Meta::Path(p))) if p.get_ident().is_some() => {
+            quote! {
+                fn severity(&self) -> crate::core::error::Severity {
+                    crate::core::error::Severity::#p
+                }
+            }
+        }
+
+        Some(_) => {
+            return quote::quote! {
+                compile_error!("`SEVERITY` should be one of `Error`, `Warning` or `Hint`")
             }
             .into();
         }
@@ -356,6 +438,8 @@ pub fn AvError(
             fn code(&self) -> String { #code.to_string() }
 
             fn title(&self) -> String { #title.to_string() }
+
+            #severity
         }
 
         impl std::fmt::Display for #ident {
@@ -364,15 +448,14 @@ pub fn AvError(
 
                 use crate::core::error::{
                     AvError,
-                    color,
                     Indentable,
                 };
 
                 writeln! (
                     f,
                     "{} -- {}:",
-                    format!("{}", self.code()).bold().color(color::ERROR),
-                    self.title().color(color::ERROR),
+                    format!("{}", self.code()).bold().color(self.severity().color()),
+                    self.title().color(self.severity().color()),
                 )?;
 
                 write! (
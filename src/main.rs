@@ -18,12 +18,58 @@ pub mod core;
 
 pub(crate) use crate::config::Config;
 use crate::consts as CONST;
+use crate::core::{diagnostics::Format, error::Severity};
 
-use std::error::Error;
+use std::{env, error::Error, fs, path::Path, process};
 
 pub mod config;
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // `navda --schema [PATH]` -- write the config's JSON Schema and exit.
+    if args.first().map(String::as_str) == Some("--schema") {
+        let schema = serde_json::to_string_pretty(&config::schema::generate())?;
+
+        match args.get(1) {
+            Some(path) => fs::write(path, schema + "\n")?,
+            None => println!("{}", schema),
+        }
+
+        return Ok(());
+    }
+
+    // `navda --check-config <PATH> [--diagnostics <FORMAT>]`
+    // -- report any problems with the config and exit.
+    if args.first().map(String::as_str) == Some("--check-config") {
+        let path: &String = args.get(1).ok_or("Usage: navda --check-config <PATH>")?;
+        let format: Format = match args.iter().position(|a| a == "--diagnostics") {
+            Some(i) => args.get(i + 1).ok_or("Missing diagnostics format")?.parse()?,
+            None => Format::from_env(),
+        };
+
+        let errors = Config::check(Path::new(path))?;
+        let valid: bool = errors.iter().all(|e| e.severity() != Severity::Error);
+
+        match format {
+            Format::Human => {
+                print!("{}", format.render(&errors));
+
+                if valid {
+                    println!("{} is valid.", path);
+                }
+            }
+            _ => println!("{}", format.render(&errors)),
+        }
+
+        // Only errors fail the check -- warnings and hints don't.
+        if !valid {
+            process::exit(1);
+        }
+
+        return Ok(());
+    }
+
     println!("\n");
     // Load Nadva's Config
     Config::load().unwrap();